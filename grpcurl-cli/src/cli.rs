@@ -1,20 +1,52 @@
+use std::time::{Duration, Instant};
+
 use clap::Parser;
 
-use grpcurl_core::commands::invoke::InvokeConfig;
-use grpcurl_core::connection::ConnectionConfig;
-use grpcurl_core::format::Format;
+use grpcurl_core::commands::invoke::{AcceptEncoding, InvokeConfig};
+use grpcurl_core::connection::{self, ConnectionConfig};
+use grpcurl_core::descriptor::{self, SourcePreference};
+use grpcurl_core::format::{self, ErrorFormat, Format, StatusStyle};
+use grpcurl_core::reflection::ReflectionVersion;
+
+/// Environment variable holding the server address used when none is given
+/// on the command line.
+pub const ADDRESS_ENV: &str = "GRPCURL_ADDRESS";
+
+/// Environment variable holding default headers, in 'name: value' form,
+/// separated by newlines or, on a single line, by commas.
+pub const HEADERS_ENV: &str = "GRPCURL_HEADERS";
+
+/// Environment variable holding the -cacert file used when the flag is unset.
+pub const CACERT_ENV: &str = "GRPCURL_CACERT";
+
+/// Environment variable holding the -cert file used when neither -cert nor
+/// -key is given.
+pub const CERT_ENV: &str = "GRPCURL_CERT";
+
+/// Environment variable holding the -key file used when neither -cert nor
+/// -key is given.
+pub const KEY_ENV: &str = "GRPCURL_KEY";
 
 /// All known long flag names (without dashes).
 /// Used by `normalize_args` to convert Go-style `-flag` to `--flag`.
 const LONG_FLAGS: &[&str] = &[
+    "address",
     "plaintext",
+    "plaintext-fallback",
     "insecure",
+    "skip-hostname-verify",
     "authority",
     "servername",
+    "tls-server-name-indication-disable",
     "connect-timeout",
+    "wait-for-ready",
+    "retry-base",
+    "retry-max",
     "keepalive-time",
     "max-time",
+    "no-deadline-header",
     "unix",
+    "grpc-web",
     "cacert",
     "cert",
     "key",
@@ -23,21 +55,70 @@ const LONG_FLAGS: &[&str] = &[
     "alts-target-service-account",
     "proto",
     "import-path",
+    "proto-dir",
     "protoset",
     "use-reflection",
+    "reflect-version",
+    "prefer",
+    "reflect-timeout",
+    "trace-reflection",
+    "no-reflect-cache-wellknown",
     "format",
+    "data",
+    "ndjson-input",
+    "request-type",
     "allow-unknown-fields",
+    "empty-text-stream",
+    "no-default-request",
+    "require-request",
     "emit-defaults",
+    "text-legacy-brackets",
+    "no-resolve-any",
+    "indent",
+    "indent-tabs",
+    "oneof-wrapped",
+    "humanize-time",
+    "response-fields",
+    "warn-unknown-response-fields",
+    "count-only",
+    "output-dir",
     "msg-template",
+    "jsonschema",
+    "extensions",
+    "preserve-order",
+    "list-json",
+    "types",
+    "all-methods",
+    "count",
+    "fail-if-empty",
     "format-error",
+    "error-format",
+    "status-style",
+    "fail-exit-code",
+    "expect-code",
     "rpc-header",
+    "gen-request-id",
+    "rpc-path",
+    "expect-trailer",
     "reflect-header",
     "expand-headers",
+    "expand-data",
     "user-agent",
     "protoset-out",
     "proto-out-dir",
+    "strip-comments",
+    "max-depth",
+    "dump-wire",
     "max-msg-sz",
+    "accept-encoding",
+    "max-recv-total",
+    "dry-run",
+    "echo-request",
+    "summary-json",
     "vv",
+    #[cfg(feature = "tracing")]
+    "log-level",
+    "repl",
     "help",
     "version",
 ];
@@ -90,15 +171,25 @@ pub fn normalize_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
 ///
 /// If neither verb is present, the symbol must be a fully-qualified method name in
 /// 'service/method' or 'service.method' format. In this case, the request body will
-/// be used to invoke the named method. If no body is given but one is required
+/// be used to invoke the named method. The body comes from -d, or from a trailing
+/// argument after the method name that looks like request data (a JSON object or
+/// array, or protobuf text). If no body is given but one is required
 /// (i.e. the method is unary or server-streaming), an empty instance of the
 /// method's request type will be sent.
 ///
+/// With --repl, only the address is given. grpcurl connects once and then reads
+/// 'list', 'describe', and '<method> [data]' commands from a prompt.
+///
+/// The 'reflection-info' verb reports which server reflection API versions
+/// (v1 and v1alpha) the server at the address answers, to help debug
+/// reflection problems.
+///
 /// The address will typically be in the form "host:port" where host can be an IP
 /// address or a hostname and port is a numeric port or service name. If an IPv6
 /// address is given, it must be surrounded by brackets, like "[2001:db8::1]". For
 /// Unix variants, if a --unix flag is present, then the address must be the
-/// path to the domain socket.
+/// path to the domain socket. The socket may also be given as an address of
+/// the form "unix:///path/to/socket" or "unix:path/to/socket".
 #[derive(Parser, Debug)]
 #[command(
     name = "grpcurl",
@@ -110,59 +201,121 @@ pub fn normalize_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
 )]
 pub struct Cli {
     // -- Connection and Networking --
+    /// The server address (host:port) to use when none is given as a
+    /// positional argument. Defaults to the GRPCURL_ADDRESS environment
+    /// variable. A positional address always takes precedence.
+    #[arg(long, value_name = "HOST:PORT")]
+    pub address: Option<String>,
+
     /// Use plain-text HTTP/2 when connecting to server (no TLS).
     #[arg(long)]
     pub plaintext: bool,
 
+    /// If a TLS connection fails because the server only speaks plain-text
+    /// HTTP/2, warn and retry the connection in plain-text. Other TLS
+    /// failures, such as a rejected certificate, are not retried.
+    /// Not valid with -plaintext option.
+    #[arg(long)]
+    pub plaintext_fallback: bool,
+
     /// Skip server certificate and domain verification. (NOT SECURE!)
     /// Not valid with -plaintext option.
     #[arg(long)]
     pub insecure: bool,
 
+    /// Verify the server's certificate chain against the trusted roots, but
+    /// not that the certificate covers the server name. Useful when
+    /// connecting by IP address. Rejected with -plaintext.
+    #[arg(long)]
+    pub skip_hostname_verify: bool,
+
     /// The authoritative name of the remote server. This value is passed as the
     /// value of the ":authority" pseudo-header in the HTTP/2 protocol. When TLS
-    /// is used, this will also be used as the server name when verifying the
-    /// server's certificate.
+    /// is used and -servername is not set, this will also be used as the server
+    /// name when verifying the server's certificate.
     #[arg(long)]
     pub authority: Option<String>,
 
     /// Override server name when validating TLS certificate. This flag is
-    /// ignored if -plaintext or -insecure is used.
-    /// NOTE: Prefer -authority. This flag may be removed in the future.
+    /// ignored if -insecure is used and rejected with -plaintext. It takes precedence over
+    /// -authority for TLS and does not change the ":authority" header, so the
+    /// two may differ.
     #[arg(long)]
     pub servername: Option<String>,
 
+    /// Do not send the SNI extension in the TLS handshake, for legacy servers
+    /// that reject it. The server may then present a default certificate that
+    /// fails hostname verification; use -servername to verify against the name
+    /// it covers. Rejected with -plaintext.
+    #[arg(long)]
+    pub tls_server_name_indication_disable: bool,
+
     /// The maximum time, in seconds, to wait for connection to be established.
     /// Defaults to 10 seconds.
     #[arg(long, value_name = "SECONDS")]
     pub connect_timeout: Option<f64>,
 
+    /// Instead of failing on the first refused connection or unready channel,
+    /// keep retrying with backoff until -connect-timeout elapses. Useful when
+    /// the server is still starting up.
+    #[arg(long)]
+    pub wait_for_ready: bool,
+
+    /// With -wait-for-ready, the delay in seconds before the first retry. Each
+    /// later delay doubles, up to -retry-max, plus up to 20% random jitter.
+    /// Defaults to 0.05 seconds.
+    #[arg(long, value_name = "SECONDS")]
+    pub retry_base: Option<f64>,
+
+    /// With -wait-for-ready, the longest delay in seconds between retries,
+    /// before jitter. Defaults to 1 second, or -retry-base if that is longer.
+    #[arg(long, value_name = "SECONDS")]
+    pub retry_max: Option<f64>,
+
     /// If present, the maximum idle time in seconds, after which a keepalive
     /// probe is sent.
     #[arg(long, value_name = "SECONDS")]
     pub keepalive_time: Option<f64>,
 
-    /// The maximum total time the operation can take, in seconds.
+    /// The maximum total time the operation can take, in seconds. The time
+    /// left of it when the RPC starts is also sent to the server as the RPC's
+    /// deadline, in the grpc-timeout header.
     #[arg(long, value_name = "SECONDS")]
     pub max_time: Option<f64>,
 
+    /// Do not send -max-time to the server in the grpc-timeout header; the
+    /// timeout is then only enforced by the client. For servers that
+    /// misbehave when they see grpc-timeout.
+    #[arg(long)]
+    pub no_deadline_header: bool,
+
     /// Indicates that the server address is the path to a Unix domain socket.
     #[arg(long)]
     pub unix: bool,
 
+    /// Use the grpc-web protocol (application/grpc-web) instead of native
+    /// gRPC when invoking the RPC. Useful for servers that are only reachable
+    /// through a grpc-web proxy. Reflection still uses native gRPC, so pair
+    /// this with -proto or -protoset if the proxy does not forward it.
+    #[arg(long)]
+    pub grpc_web: bool,
+
     // -- TLS and Security --
     /// File containing trusted root certificates for verifying the server.
-    /// Ignored if -insecure is specified.
+    /// Ignored if -insecure is specified. Rejected with -plaintext. Defaults
+    /// to the GRPCURL_CACERT environment variable.
     #[arg(long, value_name = "FILE")]
     pub cacert: Option<String>,
 
     /// File containing client certificate (public key), to present to the
     /// server. Not valid with -plaintext option. Must also provide -key option.
+    /// Defaults to the GRPCURL_CERT environment variable.
     #[arg(long, value_name = "FILE")]
     pub cert: Option<String>,
 
     /// File containing client private key, to present to the server. Not valid
-    /// with -plaintext option. Must also provide -cert option.
+    /// with -plaintext option. Must also provide -cert option. Defaults to the
+    /// GRPCURL_KEY environment variable.
     #[arg(long, value_name = "FILE")]
     pub key: Option<String>,
 
@@ -184,6 +337,13 @@ pub struct Cli {
     #[arg(long, value_name = "FILE")]
     pub proto: Vec<String>,
 
+    /// A directory to search recursively for proto source files, all of which
+    /// are compiled as if each were given via --proto. May specify more than
+    /// one. A --proto value ending in "/..." behaves the same way. Imports are
+    /// still resolved using --import-path.
+    #[arg(long, value_name = "DIR")]
+    pub proto_dir: Vec<String>,
+
     /// The path to a directory from which proto sources can be imported.
     /// Multiple import paths can be configured by specifying multiple flags.
     #[arg(long, value_name = "DIR")]
@@ -191,7 +351,8 @@ pub struct Cli {
 
     /// The name of a file containing an encoded FileDescriptorSet. May specify
     /// more than one via multiple --protoset flags. It is an error to use both
-    /// --protoset and --proto flags.
+    /// --protoset and --proto flags. A value of '-' or '@' reads the
+    /// FileDescriptorSet from stdin.
     #[arg(long, value_name = "FILE")]
     pub protoset: Vec<String>,
 
@@ -200,39 +361,247 @@ pub struct Cli {
     #[arg(long)]
     pub use_reflection: Option<bool>,
 
-    // -- Request Data --
-    /// Data for request contents. If the value is '@' then the request contents
-    /// are read from stdin.
-    #[arg(short = 'd', value_name = "DATA")]
-    pub data: Option<String>,
+    /// The server reflection API version to use. The allowed values are
+    /// 'auto' (try v1, then fall back to v1alpha), 'v1' or 'v1alpha'. Forcing
+    /// a version helps with servers whose v1 reflection is broken.
+    #[arg(long, default_value = "auto")]
+    pub reflect_version: ReflectionVersion,
+
+    /// When both reflection and proto or protoset files are used, which one
+    /// symbols are looked up in first. The allowed values are 'reflection'
+    /// (the default) or 'file', to trust local descriptors over a possibly
+    /// stale server.
+    #[arg(long, default_value = "reflection", value_name = "SOURCE")]
+    pub prefer: SourcePreference,
+
+    /// The maximum time, in seconds, to wait for each server reflection
+    /// request. Defaults to the -connect-timeout value.
+    #[arg(long, value_name = "SECONDS")]
+    pub reflect_timeout: Option<f64>,
+
+    /// Log every server reflection request and the response it got to
+    /// stderr, to see which symbols are fetched and where a lookup fails.
+    #[arg(long)]
+    pub trace_reflection: bool,
+
+    /// Resolve dependencies on well-known types (google/protobuf/*.proto)
+    /// from descriptors bundled with grpcurl instead of fetching them from
+    /// the server via reflection. Helps with servers that do not serve them.
+    #[arg(long)]
+    pub no_reflect_cache_wellknown: bool,
 
-    /// The format of request data. The allowed values are 'json' or 'text'.
+    // -- Request Data --
+    /// Data for request contents. If the value is '@' or '@-' then the request
+    /// contents are read from stdin. May be given more than once; the values
+    /// are concatenated (separated by whitespace for JSON, or by the 0x1e
+    /// record separator for text) so each can hold one or more messages. When
+    /// omitted, the request data may instead be given as a trailing argument
+    /// after the method name.
+    #[arg(short = 'd', long = "data", value_name = "DATA")]
+    pub data: Vec<String>,
+
+    /// Read JSON request data as newline-delimited JSON: each non-blank line
+    /// is exactly one message, parsed on its own. Errors name the line.
+    #[arg(long)]
+    pub ndjson_input: bool,
+
+    /// Read the request data as base64 instead of -format: each
+    /// whitespace-separated blob is the binary encoding of one message of
+    /// this fully-qualified type, which is decoded and sent as is. Useful for
+    /// payloads captured in binary form.
+    #[arg(long, value_name = "TYPE")]
+    pub request_type: Option<String>,
+
+    /// The format of request data. The allowed values are 'json', 'text' or
+    /// 'ndjson'. With 'ndjson', request data is read as JSON and each response
+    /// is printed as one line of compact JSON.
     #[arg(long, default_value = "json")]
     pub format: Format,
 
-    /// When true, the request contents, if 'json' format is used, allows
-    /// unknown fields to be present.
+    /// When true, the request contents, in 'json' or 'text' format, allow
+    /// unknown fields to be present. They are ignored.
     #[arg(long)]
     pub allow_unknown_fields: bool,
 
+    /// When using 'text' format with a client- or bidi-streaming method, treat
+    /// empty request data as zero messages. By default, empty text input is
+    /// sent as one empty message, as Go grpcurl does.
+    #[arg(long)]
+    pub empty_text_stream: bool,
+
+    /// For unary and server-streaming methods, fail with "no request data
+    /// provided" when the request data is empty, instead of sending one
+    /// empty message. For servers where no request and an empty request
+    /// mean different things.
+    #[arg(long)]
+    pub no_default_request: bool,
+
+    /// For client and bidi streaming methods, fail when the request data
+    /// holds no messages, instead of opening a stream and closing it without
+    /// sending anything. For servers that require at least one message.
+    #[arg(long)]
+    pub require_request: bool,
+
+    /// Resolve the method and print the request message(s), metadata, and
+    /// method path that would be sent, without invoking the RPC.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print each request message, in the --format of responses and with
+    /// every line prefixed '>> ', as it is sent. Helps match requests to
+    /// responses in streaming calls.
+    #[arg(long)]
+    pub echo_request: bool,
+
+    /// After the call, print a one-line JSON summary to stderr with the
+    /// request and response counts, status code, and duration, for scripts
+    /// and CI to check.
+    #[arg(long)]
+    pub summary_json: bool,
+
     // -- Response Formatting --
     /// Emit default values for JSON-encoded responses.
     #[arg(long)]
     pub emit_defaults: bool,
 
+    /// When using 'text' format, write submessages with the legacy angle
+    /// brackets ('name: <...>') used by Go grpcurl, instead of '{...}'.
+    #[arg(long)]
+    pub text_legacy_brackets: bool,
+
+    /// Write google.protobuf.Any fields in JSON responses as their type URL
+    /// and base64-encoded value, instead of expanding the packed message.
+    #[arg(long)]
+    pub no_resolve_any: bool,
+
+    /// When using 'json' format, indent each nesting level of responses by
+    /// this many spaces (or tabs with -indent-tabs). Defaults to 2 spaces,
+    /// or 1 tab with -indent-tabs.
+    #[arg(long, value_name = "N")]
+    pub indent: Option<usize>,
+
+    /// When using 'json' format, indent responses with tabs instead of spaces.
+    #[arg(long)]
+    pub indent_tabs: bool,
+
+    /// When using 'json' or 'ndjson' format, write each set oneof in responses
+    /// as an object keyed by the oneof name, {"which": "<member>", "value": ...},
+    /// instead of as the member field. Request data may use either form.
+    #[arg(long)]
+    pub oneof_wrapped: bool,
+
+    /// When using 'json' or 'ndjson' format, write Duration values in
+    /// responses like '3s 120ms' and Timestamp values as a date and time in
+    /// the local time zone, instead of their proto3 JSON strings.
+    #[arg(long)]
+    pub humanize_time: bool,
+
+    /// Comma-separated field paths, like 'a.b,c', to keep in each response.
+    /// All other fields are cleared before the response is printed. Paths
+    /// reach into repeated and map fields. May be specified more than once.
+    #[arg(long, value_name = "PATHS", value_delimiter = ',')]
+    pub response_fields: Vec<String>,
+
+    /// Print a warning to stderr with the tag numbers of any response fields
+    /// not defined in the local descriptors, to spot schema drift between the
+    /// client's protos and the server.
+    #[arg(long)]
+    pub warn_unknown_response_fields: bool,
+
+    /// For server and bidi streaming RPCs, print only the number of response
+    /// messages received instead of each message. Useful for measuring the
+    /// throughput of high-volume streams.
+    #[arg(long)]
+    pub count_only: bool,
+
+    /// Write each response message to its own numbered file in this
+    /// directory (response-0001.json, ...) instead of printing it. The
+    /// directory is created if needed.
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<String>,
+
     /// When describing messages, show a template of input data.
     #[arg(long)]
     pub msg_template: bool,
 
+    /// When describing a message, or a method's request message, print a
+    /// JSON Schema (draft-07) of the JSON it accepts instead of its
+    /// description. Useful for validating requests before sending them.
+    #[arg(long)]
+    pub jsonschema: bool,
+
+    /// When describing a message, list every known extension of it instead
+    /// of its description. With server reflection, the server is asked for
+    /// all of the message's extensions.
+    #[arg(long)]
+    pub extensions: bool,
+
+    /// When describing, keep fields, methods, and enum values in the order
+    /// they are declared in the source file instead of sorting them.
+    #[arg(long)]
+    pub preserve_order: bool,
+
+    /// When listing, print a JSON array of service names, or of method objects
+    /// (name, input and output types, streaming flags) when a service is given.
+    #[arg(long)]
+    pub list_json: bool,
+
+    /// When listing, print every message and enum type instead of services.
+    /// A symbol given to 'list' is then a package, limiting the output to
+    /// types in that package and its subpackages.
+    #[arg(long)]
+    pub types: bool,
+
+    /// When listing, print every method of every service, fully qualified
+    /// and sorted, instead of services.
+    #[arg(long)]
+    pub all_methods: bool,
+
+    /// When listing, print only the number of services, or of methods when
+    /// a service is given, instead of their names.
+    #[arg(long)]
+    pub count: bool,
+
+    /// Exit with status 1 when 'list' or 'describe' finds nothing: no
+    /// services, no methods in the given service, or no types.
+    #[arg(long)]
+    pub fail_if_empty: bool,
+
     /// When a non-zero status is returned, format the response using the
     /// value set by the --format flag.
     #[arg(long)]
     pub format_error: bool,
 
+    /// How a non-OK RPC status is reported on stderr. The allowed values are
+    /// 'human' (the default 'ERROR:' block) or 'json', a single-line JSON
+    /// object with the code, message and expanded details for automation.
+    #[arg(long, default_value = "human")]
+    pub error_format: ErrorFormat,
+
+    /// How status code names are written in status output and summaries.
+    /// The allowed values are 'go' (the default, Go's names such as
+    /// 'Canceled') or 'upper' (the canonical names such as 'CANCELLED').
+    #[arg(long, default_value = "go", value_name = "STYLE")]
+    pub status_style: StatusStyle,
+
+    /// If set, a non-OK RPC status makes grpcurl exit with this code instead
+    /// of 64 plus the gRPC status code.
+    #[arg(long, value_name = "CODE")]
+    pub fail_exit_code: Option<i32>,
+
+    /// Expect the RPC to end with this gRPC status code, given as a number
+    /// or a name in either status style, such as 'NotFound' or 'NOT_FOUND'
+    /// (case-insensitive). grpcurl then exits 0 when the call returns that
+    /// code, and 1 for any other code, including OK. Useful for negative
+    /// tests.
+    #[arg(long, value_name = "CODE")]
+    pub expect_code: Option<String>,
+
     // -- Headers and Metadata --
     /// Additional headers in 'name: value' format. May specify more than one
     /// via multiple flags. These headers will also be included in reflection
-    /// requests to a server.
+    /// requests to a server. Headers in the GRPCURL_HEADERS environment
+    /// variable are sent too, unless a header flag sets the same name.
     #[arg(short = 'H', value_name = "HEADER")]
     pub header: Vec<String>,
 
@@ -241,6 +610,32 @@ pub struct Cli {
     #[arg(long, value_name = "HEADER")]
     pub rpc_header: Vec<String>,
 
+    /// Send a freshly generated request ID (a random UUID) with the RPC, in
+    /// the given header, or 'x-request-id' if no name is given. Streams use
+    /// one ID for the whole call. The ID is printed to stderr with -v, to
+    /// correlate the call with server logs.
+    #[arg(
+        long,
+        value_name = "HEADER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "x-request-id"
+    )]
+    pub gen_request_id: Option<String>,
+
+    /// Send the RPC to this HTTP/2 path instead of '/package.Service/Method',
+    /// for gateways and proxies that route on a custom path. The method given
+    /// on the command line still determines the request and response types.
+    #[arg(long, value_name = "PATH")]
+    pub rpc_path: Option<String>,
+
+    /// Check that the response trailers contain this header, in 'name: value'
+    /// format, or just 'name' to only require it to be present. May specify
+    /// more than one via multiple flags. Each unmet expectation is reported
+    /// and makes grpcurl exit with code 1, unless the RPC already failed.
+    #[arg(long, value_name = "HEADER")]
+    pub expect_trailer: Vec<String>,
+
     /// Additional reflection headers in 'name: value' format. These headers
     /// will *only* be used during reflection requests.
     #[arg(long, value_name = "HEADER")]
@@ -251,6 +646,11 @@ pub struct Cli {
     #[arg(long)]
     pub expand_headers: bool,
 
+    /// If set, request data may use '${NAME}' syntax to reference environment
+    /// variables. Use '$${NAME}' for a literal '${NAME}'.
+    #[arg(long)]
+    pub expand_data: bool,
+
     /// If set, the specified value will be added to the User-Agent header.
     #[arg(long, value_name = "STRING")]
     pub user_agent: Option<String>,
@@ -265,12 +665,42 @@ pub struct Cli {
     #[arg(long, value_name = "DIR")]
     pub proto_out_dir: Option<String>,
 
+    /// Leave comments out of the .proto files written by -proto-out-dir, even
+    /// when the descriptors carry source info.
+    #[arg(long)]
+    pub strip_comments: bool,
+
+    /// The deepest chain of imports followed from each symbol's file when
+    /// writing -protoset-out or -proto-out-dir. A deeper import graph is an
+    /// error. Defaults to 100.
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// The name of a file to be written with the raw encoded bytes of each
+    /// request and response message. Each record is a direction byte ('>'
+    /// for requests, '<' for responses), a 4-byte big-endian length, and the
+    /// message bytes.
+    #[arg(long, value_name = "FILE")]
+    pub dump_wire: Option<String>,
+
     // -- Performance and Limits --
     /// The maximum encoded size of a response message, in bytes, that grpcurl
     /// will accept. If not specified, defaults to 4,194,304 (4 megabytes).
     #[arg(long, value_name = "BYTES")]
     pub max_msg_sz: Option<i32>,
 
+    /// The maximum total encoded size, in bytes, of all messages grpcurl will
+    /// accept from a server or bidi stream. The call is aborted once the total
+    /// is exceeded. Unlimited if not specified.
+    #[arg(long, value_name = "BYTES")]
+    pub max_recv_total: Option<u64>,
+
+    /// Comma-separated compression encodings to accept for responses, from
+    /// 'gzip', 'deflate', and 'identity'. Pass 'identity' alone to ask for
+    /// uncompressed responses. Defaults to 'gzip'.
+    #[arg(long, value_name = "LIST")]
+    pub accept_encoding: Option<AcceptEncoding>,
+
     // -- Verbosity --
     /// Enable verbose output.
     #[arg(short = 'v')]
@@ -280,13 +710,97 @@ pub struct Cli {
     #[arg(long = "vv")]
     pub very_verbose: bool,
 
+    /// Log connection setup, reflection round-trips, and the RPC lifecycle
+    /// to stderr at this level or above: 'error', 'warn', 'info', 'debug',
+    /// 'trace', or 'off'. Only grpcurl's own events are logged.
+    #[cfg(feature = "tracing")]
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<tracing_subscriber::filter::LevelFilter>,
+
+    // -- Interactive --
+    /// Start an interactive prompt that accepts 'list [service]',
+    /// 'describe [symbol]', and '<method> [data]' commands, reusing one
+    /// connection and descriptor source for all of them.
+    #[arg(long)]
+    pub repl: bool,
+
     // -- Positional Arguments --
-    /// Positional arguments: [address] [list|describe] [symbol]
+    /// Positional arguments: [address] [list|describe] [symbol] [data]
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub args: Vec<String>,
 }
 
 impl Cli {
+    /// The address to fall back to when no positional address is given: the
+    /// --address flag, else a non-empty GRPCURL_ADDRESS environment variable.
+    pub fn default_address(&self) -> Option<String> {
+        self.address.clone().or_else(|| env_value(ADDRESS_ENV))
+    }
+
+    /// The -cacert file, else a non-empty GRPCURL_CACERT environment variable.
+    fn cacert_in(&self, env: Env) -> Option<String> {
+        self.cacert.clone().or_else(|| env(CACERT_ENV))
+    }
+
+    /// The client certificate and key files: the -cert and -key flags, or,
+    /// when neither is given, GRPCURL_CERT and GRPCURL_KEY. Flags and the
+    /// environment are never mixed, so one flag cannot pair with a stale key
+    /// or certificate from the environment.
+    pub fn cert_and_key(&self) -> (Option<String>, Option<String>) {
+        self.cert_and_key_in(&env_value)
+    }
+
+    fn cert_and_key_in(&self, env: Env) -> (Option<String>, Option<String>) {
+        if self.cert.is_some() || self.key.is_some() {
+            (self.cert.clone(), self.key.clone())
+        } else {
+            (env(CERT_ENV), env(KEY_ENV))
+        }
+    }
+
+    /// The status code given to -expect-code, if it names a valid code.
+    pub fn expect_code(&self) -> Option<tonic::Code> {
+        self.expect_code
+            .as_deref()
+            .and_then(format::status_code_from_name)
+    }
+
+    /// The indentation for pretty-printed JSON, from -indent and
+    /// -indent-tabs. `None` keeps the default of two spaces.
+    pub fn json_indent(&self) -> Option<String> {
+        if self.indent_tabs {
+            Some("\t".repeat(self.indent.unwrap_or(1)))
+        } else {
+            self.indent.map(|width| " ".repeat(width))
+        }
+    }
+
+    /// The import depth limit for -protoset-out and -proto-out-dir.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.unwrap_or(descriptor::DEFAULT_MAX_DEPTH)
+    }
+
+    /// The headers sent with every request: those from GRPCURL_HEADERS,
+    /// then -H. An environment header is dropped when -H or `scoped` (the
+    /// -rpc-header or -reflect-header values for the request) names it, so
+    /// flags take precedence.
+    pub fn headers(&self, scoped: &[String]) -> Vec<String> {
+        let overridden: Vec<String> = self
+            .header
+            .iter()
+            .chain(scoped)
+            .map(|h| header_name(h))
+            .collect();
+        let mut headers: Vec<String> = env_value(HEADERS_ENV)
+            .map(|value| split_env_headers(&value))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|h| !overridden.contains(&header_name(h)))
+            .collect();
+        headers.extend(self.header.iter().cloned());
+        headers
+    }
+
     /// Compute the verbosity level from CLI flags.
     /// 0 = default, 1 = -v, 2 = --vv
     pub fn verbosity(&self) -> u8 {
@@ -299,51 +813,154 @@ impl Cli {
         }
     }
 
+    /// All proto source arguments: the --proto files plus a recursive
+    /// `dir/...` entry for each --proto-dir.
+    pub fn proto_sources(&self) -> Vec<String> {
+        let mut sources = self.proto.clone();
+        sources.extend(
+            self.proto_dir
+                .iter()
+                .map(|dir| format!("{}/...", dir.trim_end_matches('/'))),
+        );
+        sources
+    }
+
     /// Build a `ConnectionConfig` from CLI arguments.
     pub fn connection_config(&self) -> ConnectionConfig {
+        self.connection_config_in(&env_value)
+    }
+
+    /// Build a `ConnectionConfig`, reading environment defaults with `env`.
+    fn connection_config_in(&self, env: Env) -> ConnectionConfig {
+        let (cert, key) = self.cert_and_key_in(env);
         ConnectionConfig {
             plaintext: self.plaintext,
+            plaintext_fallback: self.plaintext_fallback,
             insecure: self.insecure,
+            skip_hostname_verify: self.skip_hostname_verify,
             authority: self.authority.clone(),
             servername: self.servername.clone(),
+            disable_sni: self.tls_server_name_indication_disable,
             connect_timeout: self.connect_timeout,
+            wait_for_ready: self.wait_for_ready,
+            retry_base: self.retry_base,
+            retry_max: self.retry_max,
             keepalive_time: self.keepalive_time,
             max_time: self.max_time,
             unix: self.unix,
-            cacert: self.cacert.clone(),
-            cert: self.cert.clone(),
-            key: self.key.clone(),
+            cacert: self.cacert_in(env),
+            cert,
+            key,
             alts: self.alts,
             user_agent: self.user_agent.clone(),
             max_msg_sz: self.max_msg_sz,
         }
     }
 
+    /// Merge repeated `-d` values into a single request body.
+    fn request_data(&self) -> Option<String> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let separator = match self.format {
+            // Base64 blobs are whitespace-separated whatever the format
+            _ if self.request_type.is_some() => "\n",
+            Format::Text => "\x1e",
+            Format::Json | Format::Ndjson => "\n",
+        };
+        Some(self.data.join(separator))
+    }
+
     /// Build an `InvokeConfig` from CLI arguments.
     pub fn invoke_config(&self) -> InvokeConfig {
         InvokeConfig {
             format: self.format,
             emit_defaults: self.emit_defaults,
+            text_legacy_brackets: self.text_legacy_brackets,
             allow_unknown_fields: self.allow_unknown_fields,
+            no_resolve_any: self.no_resolve_any,
+            json_indent: self.json_indent(),
+            oneof_wrapped: self.oneof_wrapped,
+            humanize_time: self.humanize_time,
+            empty_text_stream: self.empty_text_stream,
+            no_default_request: self.no_default_request,
+            require_request: self.require_request,
             format_error: self.format_error,
-            data: self.data.clone(),
-            headers: self.header.clone(),
+            data: self.request_data(),
+            ndjson_input: self.ndjson_input,
+            request_type: self.request_type.clone(),
+            headers: self.headers(&self.rpc_header),
             rpc_headers: self.rpc_header.clone(),
+            request_id_header: self.gen_request_id.clone(),
             expand_headers: self.expand_headers,
+            expand_data: self.expand_data,
             max_msg_sz: self.max_msg_sz,
+            max_recv_total: self.max_recv_total,
+            wait_for_ready: self
+                .wait_for_ready
+                .then(|| connection::connect_timeout(&self.connection_config())),
+            retry_backoff: connection::backoff_policy(&self.connection_config()),
+            // The budget starts now, so build this before connecting
+            deadline: self
+                .max_time
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .and_then(|max_time| Instant::now().checked_add(max_time)),
+            no_deadline_header: self.no_deadline_header,
             verbosity: self.verbosity(),
             protoset_out: self.protoset_out.clone(),
             proto_out_dir: self.proto_out_dir.clone(),
+            strip_comments: self.strip_comments,
+            max_depth: self.max_depth(),
+            grpc_web: self.grpc_web,
+            dry_run: self.dry_run,
+            echo_request: self.echo_request,
+            warn_unknown_response_fields: self.warn_unknown_response_fields,
+            count_only: self.count_only,
+            output_dir: self.output_dir.clone(),
+            accept_encoding: self.accept_encoding.clone().unwrap_or_default(),
+            response_fields: self.response_fields.clone(),
+            rpc_path: self.rpc_path.clone(),
+            dump_wire: self.dump_wire.clone(),
         }
     }
 }
 
+/// Looks up an environment variable; `env_value` outside of tests.
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// The value of environment variable `name`, if set and non-empty.
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Split a GRPCURL_HEADERS value into headers: one per line, or, when it is
+/// a single line, one per comma. Blank entries are skipped.
+fn split_env_headers(value: &str) -> Vec<String> {
+    let separator = if value.contains('\n') { '\n' } else { ',' };
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The lowercased name of a 'name: value' header.
+fn header_name(header: &str) -> String {
+    let name = header.split_once(':').map_or(header, |(name, _)| name);
+    name.trim().to_lowercase()
+}
+
 /// The resolved command to execute.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Command {
     List,
     Describe,
     Invoke,
+    /// Interactive prompt reusing one connection and descriptor source.
+    Repl,
+    /// Probe which server reflection API versions respond.
+    ReflectionInfo,
 }
 
 /// Result of parsing and validating positional arguments.
@@ -352,4 +969,49 @@ pub struct ParsedArgs {
     pub address: Option<String>,
     pub command: Command,
     pub symbol: Option<String>,
+    /// Request data given as a trailing argument after the method name.
+    pub data: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_of<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn tls_files_prefer_flags_over_env() {
+        let env = [
+            (CACERT_ENV, "env-ca.pem"),
+            (CERT_ENV, "env-cert.pem"),
+            (KEY_ENV, "env-key.pem"),
+        ];
+        let env = env_of(&env);
+
+        let cli = Cli::parse_from(["grpcurl"]);
+        let config = cli.connection_config_in(&env);
+        assert_eq!(config.cacert.as_deref(), Some("env-ca.pem"));
+        assert_eq!(config.cert.as_deref(), Some("env-cert.pem"));
+        assert_eq!(config.key.as_deref(), Some("env-key.pem"));
+
+        let cli = Cli::parse_from([
+            "grpcurl", "--cacert", "ca.pem", "--cert", "cert.pem", "--key", "key.pem",
+        ]);
+        let config = cli.connection_config_in(&env);
+        assert_eq!(config.cacert.as_deref(), Some("ca.pem"));
+        assert_eq!(config.cert.as_deref(), Some("cert.pem"));
+        assert_eq!(config.key.as_deref(), Some("key.pem"));
+
+        // One flag never pairs with the other half from the environment
+        let cli = Cli::parse_from(["grpcurl", "--cert", "cert.pem"]);
+        let config = cli.connection_config_in(&env);
+        assert_eq!(config.cert.as_deref(), Some("cert.pem"));
+        assert_eq!(config.key, None);
+    }
 }
@@ -1,10 +1,13 @@
 mod cli;
+mod repl;
 mod validate;
 
 use clap::Parser;
 use cli::{Cli, Command};
+use std::io::IsTerminal;
 use std::process;
 
+use grpcurl_core::commands::invoke::{InvokeConfig, InvokeResult};
 use grpcurl_core::connection::{self, ConnectionConfig};
 use grpcurl_core::descriptor::{self, DescriptorSource};
 use grpcurl_core::format;
@@ -29,6 +32,11 @@ async fn main() {
         }
     };
 
+    #[cfg(feature = "tracing")]
+    if let Some(level) = cli.log_level {
+        init_logging(level);
+    }
+
     let conn_config = cli.connection_config();
 
     match parsed.command {
@@ -43,22 +51,72 @@ async fn main() {
                     }
                 };
 
-            if let Err(err) =
-                grpcurl_core::commands::list::run_list(source.as_ref(), parsed.symbol.as_deref())
+            let listed = if cli.all_methods {
+                match grpcurl_core::commands::list::run_list_all_methods(
+                    source.as_ref(),
+                    cli.list_json,
+                    cli.verbosity(),
+                )
+                .await
+                {
+                    Ok(count) => count,
+                    Err(err) => {
+                        eprintln!("Failed to list methods: {err}");
+                        process::exit(1);
+                    }
+                }
+            } else if cli.types {
+                match grpcurl_core::commands::list::run_list_types(
+                    source.as_ref(),
+                    parsed.symbol.as_deref(),
+                    cli.list_json,
+                )
+                .await
+                {
+                    Ok(count) => count,
+                    Err(err) => {
+                        eprintln!("Failed to list types: {err}");
+                        process::exit(1);
+                    }
+                }
+            } else {
+                let result = if cli.count {
+                    grpcurl_core::commands::list::run_count(
+                        source.as_ref(),
+                        parsed.symbol.as_deref(),
+                    )
                     .await
-            {
-                match parsed.symbol.as_deref() {
-                    Some(svc) => eprintln!("Failed to list methods for service \"{svc}\": {err}"),
-                    None => eprintln!("Failed to list services: {err}"),
+                } else {
+                    grpcurl_core::commands::list::run_list(
+                        source.as_ref(),
+                        parsed.symbol.as_deref(),
+                        cli.list_json,
+                        cli.verbosity(),
+                    )
+                    .await
+                };
+                match result {
+                    Ok(count) => count,
+                    Err(err) => {
+                        match parsed.symbol.as_deref() {
+                            Some(svc) => {
+                                eprintln!("Failed to list methods for service \"{svc}\": {err}")
+                            }
+                            None => eprintln!("Failed to list services: {err}"),
+                        }
+                        process::exit(1);
+                    }
                 }
-                process::exit(1);
-            }
+            };
+
+            report_reflection_version(&cli, source.as_ref());
 
             // Export protoset/protos if requested
             let export_symbols =
                 resolve_export_symbols(source.as_ref(), parsed.symbol.as_deref()).await;
             export_protoset(&cli, source.as_ref(), &export_symbols).await;
             export_proto_files(&cli, source.as_ref(), &export_symbols).await;
+            exit_if_empty(&cli, listed);
         }
         Command::Describe => {
             let source =
@@ -73,27 +131,94 @@ async fn main() {
             let format_options = format::FormatOptions {
                 emit_defaults: cli.emit_defaults,
                 allow_unknown_fields: cli.allow_unknown_fields,
+                raw_any: cli.no_resolve_any,
+                json_indent: cli.json_indent(),
+                oneof_wrapped: cli.oneof_wrapped,
+                humanize_time: cli.humanize_time,
             };
-            if let Err(err) = grpcurl_core::commands::describe::run_describe(
-                source.as_ref(),
-                parsed.symbol.as_deref(),
-                &format_options,
-                cli.msg_template,
-            )
-            .await
-            {
-                match parsed.symbol.as_deref() {
-                    Some(sym) => eprintln!("Failed to resolve symbol \"{sym}\": {err}"),
-                    None => eprintln!("Failed to describe services: {err}"),
+            let result = match parsed.symbol.as_deref() {
+                Some(sym) if cli.jsonschema => {
+                    grpcurl_core::commands::describe::run_json_schema(source.as_ref(), sym)
+                        .await
+                        .map(|()| 1)
                 }
-                process::exit(1);
-            }
+                Some(sym) if cli.extensions => {
+                    grpcurl_core::commands::describe::run_extensions(source.as_ref(), sym).await
+                }
+                symbol => {
+                    grpcurl_core::commands::describe::run_describe(
+                        source.as_ref(),
+                        symbol,
+                        &format_options,
+                        cli.msg_template,
+                        cli.preserve_order,
+                    )
+                    .await
+                }
+            };
+            let described = match result {
+                Ok(count) => count,
+                Err(err) => {
+                    match parsed.symbol.as_deref() {
+                        Some(sym) => eprintln!("Failed to resolve symbol \"{sym}\": {err}"),
+                        None => eprintln!("Failed to describe services: {err}"),
+                    }
+                    process::exit(1);
+                }
+            };
+
+            report_reflection_version(&cli, source.as_ref());
 
             // Export protoset/protos if requested
             let export_symbols =
                 resolve_export_symbols(source.as_ref(), parsed.symbol.as_deref()).await;
             export_protoset(&cli, source.as_ref(), &export_symbols).await;
             export_proto_files(&cli, source.as_ref(), &export_symbols).await;
+            exit_if_empty(&cli, described);
+        }
+        Command::Repl => {
+            let source =
+                match create_descriptor_source(&cli, &conn_config, parsed.address.as_deref()).await
+                {
+                    Ok(s) => s,
+                    Err(e) => {
+                        eprintln!("Failed to create descriptor source: {e}");
+                        process::exit(1);
+                    }
+                };
+
+            // One channel serves every RPC in the session
+            let channel = match parsed.address.as_deref() {
+                Some(address) => match connection::create_channel(&conn_config, address).await {
+                    Ok(ch) => Some(ch),
+                    Err(e) => {
+                        eprintln!("Failed to connect to {address}: {e}");
+                        process::exit(1);
+                    }
+                },
+                None => None,
+            };
+
+            repl::run_repl(&cli, source.as_ref(), channel).await;
+        }
+        Command::ReflectionInfo => {
+            let address = parsed
+                .address
+                .as_deref()
+                .expect("address required for reflection-info");
+            let source = match create_server_source(&cli, &conn_config, address).await {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Failed to connect to {address}: {e}");
+                    process::exit(1);
+                }
+            };
+            let available =
+                grpcurl_core::commands::reflection_info::run_reflection_info(&source).await;
+            if available == 0 {
+                eprintln!("No server reflection API version responded.");
+                process::exit(1);
+            }
         }
         Command::Invoke => {
             let address = parsed
@@ -104,7 +229,12 @@ async fn main() {
                 .symbol
                 .as_deref()
                 .expect("symbol required for invoke");
-            let verbosity = cli.verbosity();
+
+            // Built first: the --max-time budget covers reflection too
+            let mut invoke_config = cli.invoke_config();
+            if parsed.data.is_some() {
+                invoke_config.data = parsed.data.clone();
+            }
 
             let source = match create_descriptor_source(&cli, &conn_config, Some(address)).await {
                 Ok(s) => s,
@@ -114,17 +244,21 @@ async fn main() {
                 }
             };
 
-            // Create a channel for the RPC invocation
-            let channel = match connection::create_channel(&conn_config, address).await {
-                Ok(ch) => ch,
-                Err(e) => {
-                    eprintln!("Failed to connect to {address}: {e}");
-                    process::exit(1);
+            // Create a channel for the RPC invocation. A dry run never sends the
+            // RPC, so it gets a lazy placeholder that does not dial the server.
+            let channel = if invoke_config.dry_run {
+                tonic::transport::Channel::from_static("http://[::]:0").connect_lazy()
+            } else {
+                match connection::create_channel(&conn_config, address).await {
+                    Ok(ch) => ch,
+                    Err(e) => {
+                        eprintln!("Failed to connect to {address}: {e}");
+                        process::exit(1);
+                    }
                 }
             };
 
-            let invoke_config = cli.invoke_config();
-
+            let start = std::time::Instant::now();
             match grpcurl_core::commands::invoke::run_invoke(
                 &invoke_config,
                 channel,
@@ -134,47 +268,20 @@ async fn main() {
             .await
             {
                 Ok(invoke_result) => {
-                    // Verbose summary: "Sent N request(s) and received M response(s)"
-                    // Go prints this to stdout (fmt.Printf in main.go)
-                    if verbosity > 0 {
-                        let req_word = if invoke_result.num_requests == 1 {
-                            "request"
-                        } else {
-                            "requests"
-                        };
-                        let resp_word = if invoke_result.num_responses == 1 {
-                            "response"
-                        } else {
-                            "responses"
-                        };
-                        println!(
-                            "Sent {} {} and received {} {}",
-                            invoke_result.num_requests,
-                            req_word,
-                            invoke_result.num_responses,
-                            resp_word
-                        );
-                    }
-
-                    // Handle gRPC status
-                    if let Some(ref status) = invoke_result.status {
-                        if status.code() != tonic::Code::Ok {
-                            if cli.format_error {
-                                // Format the error using the format flag
-                                eprintln!(
-                                    "ERROR:\n  Code: {}\n  Message: {}",
-                                    format::status_code_name(status.code()),
-                                    status.message()
-                                );
-                            } else {
-                                format::print_status(status, None);
-                            }
-                            process::exit(STATUS_CODE_OFFSET + status.code() as i32);
-                        }
+                    if let Some(code) =
+                        report_invoke_result(&cli, &invoke_result, &invoke_config, source.as_ref())
+                            .await
+                    {
+                        process::exit(code);
                     }
                 }
                 Err(err) => {
                     eprintln!("Error invoking method \"{symbol}\": {err}");
+                    // No status came back, so nothing is known to have been
+                    // exchanged with the server
+                    if cli.summary_json && !invoke_config.dry_run {
+                        print_summary_json(&cli, 0, 0, tonic::Code::Unknown, start.elapsed());
+                    }
                     process::exit(1);
                 }
             }
@@ -182,6 +289,160 @@ async fn main() {
     }
 }
 
+/// Print the verbose or `--summary-json` summary, any unmet `--expect-trailer`
+/// expectations, and any non-OK status for a completed RPC.
+///
+/// Returns the exit code for a non-OK status, 1 for unmet expectations, or
+/// `None` if the call succeeded.
+async fn report_invoke_result(
+    cli: &Cli,
+    invoke_result: &InvokeResult,
+    invoke_config: &InvokeConfig,
+    source: &dyn DescriptorSource,
+) -> Option<i32> {
+    // Verbose summary: "Sent N request(s) and received M response(s)"
+    // Go prints this to stdout (fmt.Printf in main.go)
+    if invoke_config.verbosity > 0 && !invoke_config.dry_run {
+        let req_word = if invoke_result.num_requests == 1 {
+            "request"
+        } else {
+            "requests"
+        };
+        let resp_word = if invoke_result.num_responses == 1 {
+            "response"
+        } else {
+            "responses"
+        };
+        println!(
+            "Sent {} {} and received {} {}",
+            invoke_result.num_requests, req_word, invoke_result.num_responses, resp_word
+        );
+        println!(
+            "Finished with status {} in {:.3}ms",
+            cli.status_style.code_name(invoke_result.code),
+            invoke_result.elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    if cli.summary_json && !invoke_config.dry_run {
+        print_summary_json(
+            cli,
+            invoke_result.num_requests,
+            invoke_result.num_responses,
+            invoke_result.code,
+            invoke_result.elapsed,
+        );
+    }
+
+    // Check --expect-trailer; a dry run receives no trailers
+    let unmet = if invoke_config.dry_run {
+        Vec::new()
+    } else {
+        metadata::unmet_trailer_expectations(&invoke_result.trailers, &cli.expect_trailer)
+    };
+    for expectation in &unmet {
+        eprintln!("Expected trailer \"{expectation}\" not found in response trailers");
+    }
+    let expectations_code = (!unmet.is_empty()).then_some(1);
+
+    // Handle gRPC status
+    let Some(status) = invoke_result.status.as_ref() else {
+        return expectations_code;
+    };
+
+    // Check --expect-code; a dry run always "succeeds", so it is not checked
+    let expected_code = cli.expect_code().filter(|_| !invoke_config.dry_run);
+    if let Some(expected) = expected_code.filter(|&code| code != status.code()) {
+        eprintln!(
+            "Expected status {}, got {}",
+            cli.status_style.code_name(expected),
+            cli.status_style.code_name(status.code())
+        );
+    }
+    if status.code() == tonic::Code::Ok {
+        return match expected_code {
+            Some(expected) if expected != tonic::Code::Ok => Some(1),
+            _ => expectations_code,
+        };
+    }
+    if cli.error_format == format::ErrorFormat::Json {
+        format::print_status_json(status, Some(source), cli.status_style);
+    } else if cli.format_error {
+        // Format the error using the format flag
+        eprintln!(
+            "ERROR:\n  Code: {}\n  Message: {}",
+            cli.status_style.code_name(status.code()),
+            status.message()
+        );
+    } else {
+        // Expand status details with the same formatter as
+        // responses, resolving types via the descriptor source
+        let format_options = format::FormatOptions {
+            emit_defaults: cli.emit_defaults,
+            allow_unknown_fields: cli.allow_unknown_fields,
+            raw_any: cli.no_resolve_any,
+            json_indent: cli.json_indent(),
+            oneof_wrapped: cli.oneof_wrapped,
+            humanize_time: cli.humanize_time,
+        };
+        let detail_formatter = match cli.format {
+            format::Format::Json => {
+                format::json_formatter(&format_options, source.descriptor_pool_snapshot())
+            }
+            format::Format::Text => format::text_formatter(false, cli.text_legacy_brackets),
+            format::Format::Ndjson => {
+                format::ndjson_formatter(&format_options, source.descriptor_pool_snapshot())
+            }
+        };
+        format::print_status(
+            status,
+            Some(&detail_formatter),
+            Some(source),
+            cli.status_style,
+        );
+    }
+    match expected_code {
+        Some(expected) if expected == status.code() => expectations_code,
+        Some(_) => Some(1),
+        None => Some(
+            cli.fail_exit_code
+                .unwrap_or(STATUS_CODE_OFFSET + status.code() as i32),
+        ),
+    }
+}
+
+/// Print the one-line `--summary-json` summary to stderr.
+fn print_summary_json(
+    cli: &Cli,
+    requests: usize,
+    responses: usize,
+    code: tonic::Code,
+    elapsed: std::time::Duration,
+) {
+    // Code names are bare identifiers, so nothing here needs escaping
+    eprintln!(
+        r#"{{"requests":{requests},"responses":{responses},"code":"{}","durationMs":{:.3}}}"#,
+        cli.status_style.code_name(code),
+        elapsed.as_secs_f64() * 1000.0
+    );
+}
+
+/// Install a subscriber that writes grpcurl's tracing events at `level` or
+/// above to stderr. Events from dependencies such as tonic and h2 are left
+/// out; they would bury grpcurl's own at debug level.
+#[cfg(feature = "tracing")]
+fn init_logging(level: tracing_subscriber::filter::LevelFilter) {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let targets = tracing_subscriber::filter::Targets::new()
+        .with_target("grpcurl_core", level)
+        .with_target("grpcurl", level);
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(targets)
+        .init();
+}
+
 /// Resolve export symbols: if a specific symbol was given, use it;
 /// otherwise list all services.
 async fn resolve_export_symbols(
@@ -203,7 +464,9 @@ async fn resolve_export_symbols(
 /// Export protoset file if --protoset-out is set.
 async fn export_protoset(cli: &Cli, source: &dyn DescriptorSource, symbols: &[String]) {
     if let Some(ref protoset_out) = cli.protoset_out {
-        if let Err(e) = descriptor::write_protoset(protoset_out, source, symbols).await {
+        if let Err(e) =
+            descriptor::write_protoset(protoset_out, source, symbols, cli.max_depth()).await
+        {
             eprintln!("Failed to write protoset output: {e}");
             process::exit(1);
         }
@@ -213,18 +476,85 @@ async fn export_protoset(cli: &Cli, source: &dyn DescriptorSource, symbols: &[St
 /// Export .proto files if --proto-out-dir is set.
 async fn export_proto_files(cli: &Cli, source: &dyn DescriptorSource, symbols: &[String]) {
     if let Some(ref proto_out_dir) = cli.proto_out_dir {
-        if let Err(e) = descriptor::write_proto_files(proto_out_dir, source, symbols).await {
+        let result = if cli.strip_comments {
+            descriptor::write_proto_files_without_comments(
+                proto_out_dir,
+                source,
+                symbols,
+                cli.max_depth(),
+            )
+            .await
+        } else {
+            descriptor::write_proto_files(proto_out_dir, source, symbols, cli.max_depth()).await
+        };
+        if let Err(e) = result {
             eprintln!("Failed to write proto files: {e}");
             process::exit(1);
         }
     }
 }
 
+/// With `--fail-if-empty`, exit with status 1 when list or describe found
+/// nothing, so scripts can detect a missing service without parsing output.
+fn exit_if_empty(cli: &Cli, count: usize) {
+    if cli.fail_if_empty && count == 0 {
+        process::exit(1);
+    }
+}
+
+/// In verbose mode, report which server reflection API version was used so
+/// servers that only support v1alpha are easy to spot.
+fn report_reflection_version(cli: &Cli, source: &dyn DescriptorSource) {
+    if cli.verbosity() > 0 {
+        if let Some(version) = source.reflection_version() {
+            eprintln!("Using server reflection API {version}");
+        }
+    }
+}
+
+/// Connect to `address` and create a reflection source configured from CLI
+/// flags.
+async fn create_server_source(
+    cli: &Cli,
+    conn_config: &ConnectionConfig,
+    address: &str,
+) -> grpcurl_core::error::Result<reflection::ServerSource> {
+    let channel = connection::create_channel(conn_config, address).await?;
+
+    // Build reflection metadata: GRPCURL_HEADERS and -H (all) + --reflect-header
+    // (reflection-only)
+    let mut reflect_headers: Vec<String> = cli.headers(&cli.reflect_header);
+    reflect_headers.extend(cli.reflect_header.clone());
+    if cli.expand_headers {
+        reflect_headers = metadata::expand_headers(&reflect_headers)?;
+    }
+    let reflect_md = metadata::metadata_from_headers(&reflect_headers);
+
+    let source = if reflect_md.is_empty() {
+        reflection::ServerSource::new(channel)
+    } else {
+        reflection::ServerSource::with_metadata(channel, reflect_md)
+    }
+    .with_max_msg_sz(cli.max_msg_sz)
+    .with_reflection_version(cli.reflect_version)
+    .with_trace(cli.trace_reflection)
+    .with_bundled_well_known(cli.no_reflect_cache_wellknown)
+    // Trace lines would break up the redrawn progress line
+    .with_progress(cli.verbosity() > 0 && !cli.trace_reflection && std::io::stderr().is_terminal())
+    .with_timeout(Some(
+        cli.reflect_timeout
+            .and_then(connection::duration_from_secs)
+            .unwrap_or_else(|| connection::connect_timeout(conn_config)),
+    ));
+    Ok(source)
+}
+
 /// Create a descriptor source from CLI flags.
 ///
 /// Matching Go's behavior:
 /// - If proto/protoset files are specified AND an address is available with
-///   reflection enabled, creates a CompositeSource (reflection + file fallback)
+///   reflection enabled, creates a CompositeSource (reflection + file fallback,
+///   or the reverse with `--prefer file`)
 /// - If only proto/protoset files: uses FileSource
 /// - If only address: uses ServerSource (reflection)
 async fn create_descriptor_source(
@@ -232,60 +562,52 @@ async fn create_descriptor_source(
     conn_config: &ConnectionConfig,
     address: Option<&str>,
 ) -> grpcurl_core::error::Result<Box<dyn DescriptorSource>> {
+    let proto_sources = cli.proto_sources();
+
     // Build file-based source if proto/protoset files are specified
-    let file_source: Option<Box<dyn DescriptorSource>> = if !cli.protoset.is_empty() {
-        Some(Box::new(descriptor::descriptor_source_from_protosets(
-            &cli.protoset,
-        )?))
-    } else if !cli.proto.is_empty() {
-        Some(Box::new(descriptor::descriptor_source_from_proto_files(
-            &cli.import_path,
-            &cli.proto,
-        )?))
-    } else {
-        None
-    };
+    let file_source: Option<Box<dyn DescriptorSource>> =
+        if !cli.protoset.is_empty() && !proto_sources.is_empty() {
+            Some(Box::new(
+                descriptor::descriptor_source_from_protosets_and_proto_files(
+                    &cli.protoset,
+                    &cli.import_path,
+                    &proto_sources,
+                )?,
+            ))
+        } else if !cli.protoset.is_empty() {
+            Some(Box::new(descriptor::descriptor_source_from_protosets(
+                &cli.protoset,
+            )?))
+        } else if !proto_sources.is_empty() {
+            Some(Box::new(descriptor::descriptor_source_from_proto_files(
+                &cli.import_path,
+                &proto_sources,
+            )?))
+        } else {
+            None
+        };
 
     // Build reflection source if address is available and reflection is not disabled.
     // When proto/protoset files are provided, auto-disable reflection unless
     // explicitly enabled with --use-reflection=true (matching Go behavior).
-    let has_proto_files = !cli.protoset.is_empty() || !cli.proto.is_empty();
+    let has_proto_files = !cli.protoset.is_empty() || !proto_sources.is_empty();
     let use_reflection = match cli.use_reflection {
         Some(true) => true,
         Some(false) => false,
         None => !has_proto_files,
     };
-    let reflection_source: Option<Box<dyn DescriptorSource>> = if let Some(addr) = address {
-        if use_reflection {
-            let channel = connection::create_channel(conn_config, addr).await?;
-
-            // Build reflection metadata: -H (all) + --reflect-header (reflection-only)
-            let mut reflect_headers: Vec<String> = cli.header.clone();
-            reflect_headers.extend(cli.reflect_header.clone());
-            if cli.expand_headers {
-                reflect_headers = metadata::expand_headers(&reflect_headers)?;
-            }
-            let reflect_md = metadata::metadata_from_headers(&reflect_headers);
-
-            let source = if reflect_md.is_empty() {
-                reflection::ServerSource::new(channel).with_max_msg_sz(cli.max_msg_sz)
-            } else {
-                reflection::ServerSource::with_metadata(channel, reflect_md)
-                    .with_max_msg_sz(cli.max_msg_sz)
-            };
-            Some(Box::new(source))
-        } else {
-            None
-        }
-    } else {
-        None
+    let reflection_source: Option<Box<dyn DescriptorSource>> = match address {
+        Some(addr) if use_reflection => Some(Box::new(
+            create_server_source(cli, conn_config, addr).await?,
+        )),
+        _ => None,
     };
 
     // Combine sources: composite when both available, otherwise use whichever exists
     match (reflection_source, file_source) {
-        (Some(reflection), Some(file)) => {
-            Ok(Box::new(descriptor::CompositeSource::new(reflection, file)))
-        }
+        (Some(reflection), Some(file)) => Ok(Box::new(
+            descriptor::CompositeSource::new(reflection, file).with_preference(cli.prefer),
+        )),
         (Some(reflection), None) => Ok(reflection),
         (None, Some(file)) => Ok(file),
         (None, None) => Err(grpcurl_core::error::GrpcurlError::InvalidArgument(
@@ -0,0 +1,126 @@
+use std::io::{IsTerminal, Write};
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tonic::transport::Channel;
+
+use grpcurl_core::commands::{describe, invoke, list};
+use grpcurl_core::descriptor::DescriptorSource;
+use grpcurl_core::format;
+
+use crate::cli::Cli;
+
+const PROMPT: &str = "grpcurl> ";
+
+const HELP: &str = "\
+Commands:
+  list [service]       List services, or the methods of a service
+  describe [symbol]    Describe a service, method, message, or enum
+  <method> [data]      Invoke a method, e.g. my.Svc/Get {\"id\": 1}
+  help                 Show this help
+  exit, quit           Leave the prompt";
+
+/// Run the `--repl` prompt until `exit`, `quit`, or end of input.
+///
+/// Every command runs against the same channel and descriptor source, so
+/// exploring a server does not reconnect or redo reflection setup each time.
+/// Failed commands are reported to stderr and the prompt continues. The prompt
+/// itself is only printed when stdin is a terminal, so commands can be piped in.
+pub async fn run_repl(cli: &Cli, source: &dyn DescriptorSource, channel: Option<Channel>) {
+    let interactive = std::io::stdin().is_terminal();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    loop {
+        if interactive {
+            print!("{PROMPT}");
+            let _ = std::io::stdout().flush();
+        }
+
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Failed to read command: {e}");
+                break;
+            }
+        };
+
+        let line = line.trim();
+        let (command, arg) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, Some(rest.trim())),
+            None => (line, None),
+        };
+
+        match command {
+            "" => {}
+            "exit" | "quit" => break,
+            "help" => println!("{HELP}"),
+            "list" => run_list(cli, source, arg).await,
+            "describe" => run_describe(cli, source, arg).await,
+            method => run_method(cli, source, channel.as_ref(), method, arg).await,
+        }
+    }
+}
+
+async fn run_list(cli: &Cli, source: &dyn DescriptorSource, symbol: Option<&str>) {
+    if let Err(err) = list::run_list(source, symbol, cli.list_json, cli.verbosity()).await {
+        match symbol {
+            Some(svc) => eprintln!("Failed to list methods for service \"{svc}\": {err}"),
+            None => eprintln!("Failed to list services: {err}"),
+        }
+    }
+}
+
+async fn run_describe(cli: &Cli, source: &dyn DescriptorSource, symbol: Option<&str>) {
+    let format_options = format::FormatOptions {
+        emit_defaults: cli.emit_defaults,
+        allow_unknown_fields: cli.allow_unknown_fields,
+        raw_any: cli.no_resolve_any,
+        json_indent: cli.json_indent(),
+        oneof_wrapped: cli.oneof_wrapped,
+        humanize_time: cli.humanize_time,
+    };
+    if let Err(err) = describe::run_describe(
+        source,
+        symbol,
+        &format_options,
+        cli.msg_template,
+        cli.preserve_order,
+    )
+    .await
+    {
+        match symbol {
+            Some(sym) => eprintln!("Failed to resolve symbol \"{sym}\": {err}"),
+            None => eprintln!("Failed to describe services: {err}"),
+        }
+    }
+}
+
+async fn run_method(
+    cli: &Cli,
+    source: &dyn DescriptorSource,
+    channel: Option<&Channel>,
+    method: &str,
+    data: Option<&str>,
+) {
+    let Some(channel) = channel else {
+        eprintln!("No host:port specified; cannot invoke \"{method}\".");
+        return;
+    };
+    // stdin carries the commands themselves, so it cannot also hold request data
+    if matches!(data, Some("@" | "@-")) {
+        eprintln!("Reading request data from stdin is not supported in --repl mode.");
+        return;
+    }
+
+    let mut invoke_config = cli.invoke_config();
+    invoke_config.data = data.map(str::to_string);
+
+    match invoke::run_invoke(&invoke_config, channel.clone(), method, source).await {
+        Ok(invoke_result) => {
+            // Any error status is printed; its exit code is ignored so the
+            // session keeps going
+            let _ = crate::report_invoke_result(cli, &invoke_result, &invoke_config, source).await;
+        }
+        Err(err) => eprintln!("Error invoking method \"{method}\": {err}"),
+    }
+}
@@ -1,10 +1,17 @@
-use grpcurl_core::format::Format;
+use grpcurl_core::connection;
+use grpcurl_core::descriptor::{self, SourcePreference};
+use grpcurl_core::error::GrpcurlError;
+use grpcurl_core::format::{ErrorFormat, Format};
+use grpcurl_core::reflection::ReflectionVersion;
 
-use crate::cli::{Cli, Command, ParsedArgs};
+use crate::cli::{Cli, Command, ParsedArgs, CERT_ENV, KEY_ENV};
 
 /// Validate all CLI flags and positional arguments.
 ///
-/// Implements all 28 validation rules from the original grpcurl, in order.
+/// Implements the validation rules from the original grpcurl, in order,
+/// plus checks for grpcurl-rs extensions (rule 29 onward, numbered as they
+/// were added and placed next to related checks). Go's rule 24,
+/// which kept -protoset and -proto apart, is dropped: they may be combined.
 /// Hard errors return `Err(message)`. Warnings are printed to stderr but
 /// do not prevent execution.
 pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
@@ -29,6 +36,40 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         }
     }
 
+    // Rule 60: -retry-base and -retry-max must be positive, with the cap at
+    // least the initial delay.
+    for (flag, value) in [("retry-base", cli.retry_base), ("retry-max", cli.retry_max)] {
+        if let Some(t) = value {
+            let delay = std::time::Duration::try_from_secs_f64(t).ok();
+            if delay.filter(|d| !d.is_zero()).is_none() {
+                return Err(format!(
+                    "The --{flag} argument must be a positive number of seconds."
+                ));
+            }
+        }
+    }
+    if let (Some(base), Some(max)) = (cli.retry_base, cli.retry_max) {
+        if max < base {
+            return Err("The --retry-max argument must not be less than --retry-base.".into());
+        }
+    }
+
+    // Rule 61: -retry-base and -retry-max only apply to -wait-for-ready.
+    if (cli.retry_base.is_some() || cli.retry_max.is_some()) && !cli.wait_for_ready {
+        warn("The --retry-base and --retry-max arguments are only used with --wait-for-ready.");
+    }
+
+    // Rule 63: -strip-comments only affects files written by -proto-out-dir.
+    if cli.strip_comments && cli.proto_out_dir.is_none() {
+        warn("The --strip-comments argument is only used with --proto-out-dir.");
+    }
+
+    // Rule 74: -max-depth only limits the files written by -protoset-out and
+    // -proto-out-dir.
+    if cli.max_depth.is_some() && cli.protoset_out.is_none() && cli.proto_out_dir.is_none() {
+        warn("The --max-depth argument is only used with --protoset-out or --proto-out-dir.");
+    }
+
     // Rule 4: -max-msg-sz must not be negative.
     if let Some(sz) = cli.max_msg_sz {
         if sz < 0 {
@@ -36,6 +77,27 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         }
     }
 
+    // Rule 45: -reflect-timeout must be a non-negative, finite duration.
+    if let Some(t) = cli.reflect_timeout {
+        if t < 0.0 {
+            return Err("The --reflect-timeout argument must not be negative.".into());
+        }
+        if connection::duration_from_secs(t).is_none() {
+            return Err(format!(
+                "The --reflect-timeout argument must be a number of seconds, not {t}."
+            ));
+        }
+    }
+
+    // Rule 54: -rpc-path must be an absolute path.
+    if let Some(ref path) = cli.rpc_path {
+        if !path.starts_with('/') {
+            return Err(format!(
+                "The --rpc-path argument must start with '/', got '{path}'."
+            ));
+        }
+    }
+
     // Derive TLS mode: default is TLS unless plaintext or alts.
     let use_tls = !cli.plaintext && !cli.alts;
 
@@ -49,6 +111,16 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         return Err("The --insecure argument can only be used with TLS.".into());
     }
 
+    // Rule 83: -skip-hostname-verify requires TLS.
+    if cli.skip_hostname_verify && !use_tls {
+        return Err("The --skip-hostname-verify argument can only be used with TLS.".into());
+    }
+
+    // Rule 84: -insecure already skips all certificate verification.
+    if cli.skip_hostname_verify && cli.insecure {
+        warn("The --skip-hostname-verify argument is not used with --insecure.");
+    }
+
     // Rule 7: -cert requires TLS.
     if cli.cert.is_some() && !use_tls {
         return Err("The --cert argument can only be used with TLS.".into());
@@ -59,6 +131,38 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         return Err("The --key argument can only be used with TLS.".into());
     }
 
+    // Rule 42: -cacert requires TLS.
+    if cli.cacert.is_some() && !use_tls {
+        return Err("The --cacert argument can only be used with TLS.".into());
+    }
+
+    // Rule 43: -servername requires TLS.
+    if cli.servername.is_some() && !use_tls {
+        return Err("The --servername argument can only be used with TLS.".into());
+    }
+
+    // Rule 78: -tls-server-name-indication-disable requires TLS.
+    if cli.tls_server_name_indication_disable && !use_tls {
+        return Err(
+            "The --tls-server-name-indication-disable argument can only be used with TLS.".into(),
+        );
+    }
+
+    // Rule 79: without SNI the server may pick a certificate for another name,
+    // unless --servername already names the one it presents.
+    if cli.tls_server_name_indication_disable && !cli.insecure && cli.servername.is_none() {
+        warn(
+            "With --tls-server-name-indication-disable the server may present a default \
+             certificate that fails hostname verification; use --servername to verify it \
+             against the name it covers.",
+        );
+    }
+
+    // Rule 56: -plaintext-fallback only applies when connecting with TLS.
+    if cli.plaintext_fallback && !use_tls {
+        warn("The --plaintext-fallback argument is only used with TLS.");
+    }
+
     // Rule 9: -cert and -key must both be present or both absent.
     if cli.cert.is_some() != cli.key.is_some() {
         return Err(
@@ -66,6 +170,18 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         );
     }
 
+    // Rule 91: without -cert and -key, GRPCURL_CERT and GRPCURL_KEY must both
+    // be set or both unset.
+    if use_tls && cli.cert.is_none() && cli.key.is_none() {
+        let (cert, key) = cli.cert_and_key();
+        if cert.is_some() != key.is_some() {
+            return Err(format!(
+                "The {CERT_ENV} and {KEY_ENV} environment variables must be set together \
+                 when --cert and --key are not given."
+            ));
+        }
+    }
+
     // Rule 10: -alts-handshaker-service requires -alts.
     if cli.alts_handshaker_service.is_some() && !cli.alts {
         return Err(
@@ -81,63 +197,128 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         );
     }
 
-    // Rule 12: -format must be json or text.
+    // Rule 12: -format must be json, text or ndjson.
     // (Handled by clap's FromStr on Format enum, but kept as a conceptual rule.)
 
-    // Rule 13: -emit-defaults with non-json format emits a warning.
-    if cli.emit_defaults && cli.format != Format::Json {
-        warn("The --emit-defaults is only used when using json format.");
+    // Rule 13: -emit-defaults with text format emits a warning.
+    if cli.emit_defaults && cli.format == Format::Text {
+        warn("The --emit-defaults is only used when using json or ndjson format.");
     }
 
+    let has_proto = !cli.proto_sources().is_empty();
+
     // ── Parse positional arguments ────────────────────────────────────
 
     let mut args = cli.args.iter().map(String::as_str).collect::<Vec<_>>();
 
     // Rule 14: At least one positional argument is required.
-    if args.is_empty() {
+    if args.is_empty() && !cli.repl {
         return Err("Too few arguments.".into());
     }
 
     // Rule 15: If first arg is not 'list' or 'describe', it is the address.
-    let address = if args[0] != "list" && args[0] != "describe" {
-        let addr = args.remove(0).to_string();
-        Some(addr)
+    // Rule 50: Without a positional address, fall back to --address or
+    // GRPCURL_ADDRESS. With such a default, the first arg is only an address
+    // when a verb or symbol follows it; otherwise it is the method to invoke.
+    let default_address = cli.default_address();
+    let address = if has_positional_address(&args, default_address.is_some(), cli) {
+        Some(args.remove(0).to_string())
     } else {
-        None
+        default_address
     };
 
-    if args.is_empty() {
+    if args.is_empty() && !cli.repl {
         return Err("Too few arguments.".into());
     }
 
     // Rule 16: Determine the command.
     let command;
-    if args[0] == "list" {
+    if cli.repl {
+        // Rule 38: -repl takes only an address; commands are entered at the prompt.
+        if !args.is_empty() {
+            return Err(
+                "The --repl argument takes only an address; enter commands at the prompt.".into(),
+            );
+        }
+        if !cli.data.is_empty() {
+            warn("The -d argument is not used with --repl; give request data at the prompt.");
+        }
+        command = Command::Repl;
+    } else if args[0] == "list" {
         command = Command::List;
         args.remove(0);
     } else if args[0] == "describe" {
         command = Command::Describe;
         args.remove(0);
+    } else if args[0] == "reflection-info" {
+        command = Command::ReflectionInfo;
+        args.remove(0);
     } else {
         // Rule 16: If neither list nor describe, mode is invoke.
         command = Command::Invoke;
     }
 
     // Rule 17: For invoke, the symbol (method name) is required.
-    let symbol = if command == Command::Invoke {
+    let symbol = if matches!(command, Command::Repl | Command::ReflectionInfo) {
+        None
+    } else if command == Command::Invoke {
         if args.is_empty() {
             return Err("Too few arguments.".into());
         }
         Some(args.remove(0).to_string())
     } else {
         // Rule 18: -d with list/describe emits a warning (unused).
-        if cli.data.is_some() {
+        if !cli.data.is_empty() {
             warn("The -d argument is not used with 'list' or 'describe' verb.");
         }
         // Rule 19: -rpc-header with list/describe emits a warning (unused).
         if !cli.rpc_header.is_empty() {
             warn("The --rpc-header argument is not used with 'list' or 'describe' verb.");
         }
+        // Rule 31: -dry-run with list/describe emits a warning (unused).
+        if cli.dry_run {
+            warn("The --dry-run argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 52: -response-fields with list/describe emits a warning (unused).
+        if !cli.response_fields.is_empty() {
+            warn("The --response-fields argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 55: -rpc-path with list/describe emits a warning (unused).
+        if cli.rpc_path.is_some() {
+            warn("The --rpc-path argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 57: -dump-wire with list/describe emits a warning (unused).
+        if cli.dump_wire.is_some() {
+            warn("The --dump-wire argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 65: -echo-request with list/describe emits a warning (unused).
+        if cli.echo_request {
+            warn("The --echo-request argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 66: -summary-json with list/describe emits a warning (unused).
+        if cli.summary_json {
+            warn("The --summary-json argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 76: -request-type with list/describe emits a warning (unused).
+        if cli.request_type.is_some() {
+            warn("The --request-type argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 72: -no-default-request with list/describe emits a warning (unused).
+        if cli.no_default_request {
+            warn("The --no-default-request argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 85: -require-request with list/describe emits a warning (unused).
+        if cli.require_request {
+            warn("The --require-request argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 86: -expect-trailer with list/describe emits a warning (unused).
+        if !cli.expect_trailer.is_empty() {
+            warn("The --expect-trailer argument is not used with 'list' or 'describe' verb.");
+        }
+        // Rule 88: -expect-code with list/describe emits a warning (unused).
+        if cli.expect_code.is_some() {
+            warn("The --expect-code argument is not used with 'list' or 'describe' verb.");
+        }
         if !args.is_empty() {
             Some(args.remove(0).to_string())
         } else {
@@ -145,18 +326,41 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         }
     };
 
+    // Rule 36: For invoke, a trailing argument that looks like request data is
+    // the request body. It cannot be combined with -d.
+    let mut data = None;
+    if command == Command::Invoke && args.len() == 1 && looks_like_request_data(args[0], cli.format)
+    {
+        if !cli.data.is_empty() {
+            return Err(
+                "Request data cannot be given both with -d and as a positional argument.".into(),
+            );
+        }
+        data = Some(args.remove(0).to_string());
+    }
+
+    // Rule 37: -expand-data without request data emits a warning (unused).
+    if cli.expand_data && cli.data.is_empty() && data.is_none() {
+        warn("The --expand-data argument is not used without request data.");
+    }
+
     // Rule 20: Extra positional arguments are rejected.
     if !args.is_empty() {
         return Err("Too many arguments.".into());
     }
 
+    // Rule 62: 'reflection-info' probes a server, so it needs an address.
+    if command == Command::ReflectionInfo && address.is_none() {
+        return Err("The 'reflection-info' verb requires a server address.".into());
+    }
+
     // Rule 21: For invoke, address is required.
     if command == Command::Invoke && address.is_none() {
         return Err("No host:port specified.".into());
     }
 
     // Rule 22: At least one of: address, -protoset, or -proto must be given.
-    if address.is_none() && cli.protoset.is_empty() && cli.proto.is_empty() {
+    if address.is_none() && cli.protoset.is_empty() && !has_proto {
         return Err(
             "No host:port specified, no protoset specified, and no proto sources specified.".into(),
         );
@@ -167,19 +371,14 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
         warn("The --reflect-header argument is not used when --protoset files are used.");
     }
 
-    // Rule 24: -protoset and -proto are mutually exclusive.
-    if !cli.protoset.is_empty() && !cli.proto.is_empty() {
-        return Err("Use either --protoset files or --proto files, but not both.".into());
-    }
-
     // Rule 25: -import-path without -proto emits a warning (unused).
-    if !cli.import_path.is_empty() && cli.proto.is_empty() {
+    if !cli.import_path.is_empty() && !has_proto {
         warn("The --import-path argument is not used unless --proto files are used.");
     }
 
     // Rule 26: If -use-reflection is false, at least one of -protoset or -proto must be given.
     let use_reflection_explicit = cli.use_reflection;
-    if use_reflection_explicit == Some(false) && cli.protoset.is_empty() && cli.proto.is_empty() {
+    if use_reflection_explicit == Some(false) && cli.protoset.is_empty() && !has_proto {
         return Err(
             "No protoset files or proto files specified and --use-reflection set to false.".into(),
         );
@@ -189,22 +388,254 @@ pub fn validate(cli: &Cli) -> Result<ParsedArgs, String> {
     // reflection defaults to false.
     // (This is runtime behavior, not validation. Noted here for completeness.)
 
-    // Rule 28: -servername and -authority cannot both be set to different values.
+    // Rule 28: -servername and -authority with the same value is redundant.
+    // Unlike Go, different values are allowed: --authority sets the :authority
+    // header and --servername sets the TLS server name.
     if let (Some(sn), Some(auth)) = (&cli.servername, &cli.authority) {
         if sn == auth {
             warn("Both --servername and --authority are present; prefer only --authority.");
-        } else {
-            return Err("Cannot specify different values for --servername and --authority.".into());
         }
     }
 
+    // Rule 29: A unix:// or unix: address must name a socket path.
+    if let Some(addr) = &address {
+        if connection::unix_socket_path(addr) == Some("") {
+            return Err(format!(
+                "The address {addr:?} does not include a socket path."
+            ));
+        }
+    }
+
+    // Rule 59: Only dns:, ipv4:, ipv6: and unix: address schemes are supported,
+    // and unix:// only with an absolute path.
+    if let Some(addr) = &address {
+        if let Err(GrpcurlError::InvalidArgument(msg)) = connection::target_address(addr) {
+            return Err(format!("The address {addr:?} is not supported: {msg}."));
+        }
+    }
+
+    // Rule 30: -fail-exit-code must be a non-zero process exit code.
+    if let Some(code) = cli.fail_exit_code {
+        if !(1..=255).contains(&code) {
+            return Err("The --fail-exit-code argument must be between 1 and 255.".into());
+        }
+    }
+
+    // Rule 87: -expect-code must name a gRPC status code.
+    if let Some(code) = &cli.expect_code {
+        if cli.expect_code().is_none() {
+            return Err(format!(
+                "The --expect-code argument must be a gRPC status code number (0-16) or name, \
+                 got {code:?}."
+            ));
+        }
+    }
+
+    // Rule 32: -list-json is only meaningful with 'list'.
+    if cli.list_json && command != Command::List {
+        warn("The --list-json argument is only used with the 'list' verb.");
+    }
+
+    // Rule 46: -types is only meaningful with 'list'.
+    if cli.types && command != Command::List {
+        warn("The --types argument is only used with the 'list' verb.");
+    }
+
+    // Rule 68: -all-methods is only meaningful with 'list'.
+    // Rule 69: There it takes the place of both a service name and -types.
+    if cli.all_methods {
+        if command != Command::List {
+            warn("The --all-methods argument is only used with the 'list' verb.");
+        } else if cli.types || symbol.is_some() {
+            return Err(
+                "The --all-methods argument cannot be combined with --types or a service name."
+                    .into(),
+            );
+        }
+    }
+
+    // Rule 89: -count is only meaningful with 'list'.
+    // Rule 90: -count cannot be combined with -list-json, -types, or
+    // -all-methods.
+    if cli.count {
+        if command != Command::List {
+            warn("The --count argument is only used with the 'list' verb.");
+        } else if cli.list_json || cli.types || cli.all_methods {
+            return Err(
+                "The --count argument cannot be combined with --list-json, --types, \
+                 or --all-methods."
+                    .into(),
+            );
+        }
+    }
+
+    // Rule 49: -fail-if-empty is only meaningful with 'list' or 'describe'.
+    if cli.fail_if_empty && !matches!(command, Command::List | Command::Describe) {
+        warn("The --fail-if-empty argument is only used with 'list' or 'describe' verb.");
+    }
+
+    // Rule 75: -jsonschema replaces the description of one symbol.
+    if cli.jsonschema && (command != Command::Describe || symbol.is_none()) {
+        return Err("The --jsonschema argument requires 'describe' with a symbol.".into());
+    }
+
+    // Rule 80: -extensions replaces the description of one message.
+    if cli.extensions && (command != Command::Describe || symbol.is_none()) {
+        return Err("The --extensions argument requires 'describe' with a symbol.".into());
+    }
+
+    // Rule 81: -jsonschema and -extensions each replace the description.
+    if cli.jsonschema && cli.extensions {
+        return Err("The --jsonschema and --extensions arguments are mutually exclusive.".into());
+    }
+
+    // Rule 53: -preserve-order is only meaningful with 'describe'.
+    if cli.preserve_order && !matches!(command, Command::Describe | Command::Repl) {
+        warn("The --preserve-order argument is only used with the 'describe' verb.");
+    }
+
+    // Rule 33: -max-recv-total must allow at least some data.
+    if cli.max_recv_total == Some(0) {
+        return Err("The --max-recv-total argument must be greater than zero.".into());
+    }
+
+    // Rule 34: -text-legacy-brackets with non-text format emits a warning.
+    if cli.text_legacy_brackets && cli.format != Format::Text {
+        warn("The --text-legacy-brackets argument is only used when using text format.");
+    }
+
+    // Rule 35: -empty-text-stream with non-text format emits a warning.
+    if cli.empty_text_stream && cli.format != Format::Text {
+        warn("The --empty-text-stream argument is only used when using text format.");
+    }
+
+    // Rule 48: -no-resolve-any with text format emits a warning.
+    if cli.no_resolve_any && cli.format == Format::Text {
+        warn("The --no-resolve-any argument is only used when using json or ndjson format.");
+    }
+
+    // Rule 51: -indent and -indent-tabs only affect pretty-printed json.
+    if (cli.indent.is_some() || cli.indent_tabs) && cli.format != Format::Json {
+        warn("The --indent and --indent-tabs arguments are only used when using json format.");
+    }
+
+    // Rule 58: -oneof-wrapped only affects json and ndjson output.
+    if cli.oneof_wrapped && cli.format == Format::Text {
+        warn("The --oneof-wrapped argument is only used when using json or ndjson format.");
+    }
+
+    // Rule 67: -humanize-time only affects json and ndjson output.
+    if cli.humanize_time && cli.format == Format::Text {
+        warn("The --humanize-time argument is only used when using json or ndjson format.");
+    }
+
+    // Rule 64: -ndjson-input only changes how JSON request data is split.
+    if cli.ndjson_input && cli.format == Format::Text {
+        warn("The --ndjson-input argument is only used when using json or ndjson format.");
+    }
+
+    // Rule 39: -count-only with list/describe emits a warning (unused).
+    if cli.count_only && matches!(command, Command::List | Command::Describe) {
+        warn("The --count-only argument is not used with 'list' or 'describe' verb.");
+    }
+
+    // Rule 82: -gen-request-id with list/describe emits a warning (unused).
+    if cli.gen_request_id.is_some() && matches!(command, Command::List | Command::Describe) {
+        warn("The --gen-request-id argument is not used with 'list' or 'describe' verb.");
+    }
+
+    // Rule 77: -output-dir with list/describe emits a warning (unused).
+    if cli.output_dir.is_some() && matches!(command, Command::List | Command::Describe) {
+        warn("The --output-dir argument is not used with 'list' or 'describe' verb.");
+    }
+
+    // Rule 40: stdin can only be read once, so '-d @' must be the only -d.
+    if cli.data.len() > 1 && cli.data.iter().any(|d| d == "@" || d == "@-") {
+        return Err(
+            "The -d @ argument (read from stdin) cannot be combined with other -d values.".into(),
+        );
+    }
+
+    // Rule 41: -format-error is superseded by -error-format=json.
+    if cli.format_error && cli.error_format == ErrorFormat::Json {
+        warn("The --format-error argument is not used with --error-format=json.");
+    }
+
+    // Rule 44: -reflect-version emits a warning when reflection is not used.
+    let file_sources = !cli.protoset.is_empty() || has_proto;
+    let reflection_used = cli.use_reflection.unwrap_or(!file_sources);
+    if cli.reflect_version != ReflectionVersion::Auto && !reflection_used {
+        warn("The --reflect-version argument is not used when reflection is disabled.");
+    }
+
+    // Rule 70: -no-reflect-cache-wellknown only changes how reflection
+    // resolves dependencies.
+    if cli.no_reflect_cache_wellknown && !reflection_used {
+        warn("The --no-reflect-cache-wellknown argument is not used when reflection is disabled.");
+    }
+
+    // Rule 71: -no-deadline-header only changes how -max-time is applied.
+    if cli.no_deadline_header && cli.max_time.is_none() {
+        warn("The --no-deadline-header argument is not used without --max-time.");
+    }
+
+    // Rule 73: -prefer only orders the sources when both are in use.
+    if cli.prefer != SourcePreference::Reflection && !(file_sources && reflection_used) {
+        warn("The --prefer argument is only used when both reflection and proto sources are used.");
+    }
+
+    // Rule 47: stdin can only be consumed once per invocation: by one
+    // '--protoset -', by '-d @', or by the --repl prompt.
+    let stdin_protosets = cli
+        .protoset
+        .iter()
+        .filter(|p| descriptor::is_stdin_path(p))
+        .count();
+    let stdin_data = cli.data.iter().any(|d| d == "@" || d == "@-");
+    if stdin_protosets > 1 || (stdin_protosets == 1 && (stdin_data || cli.repl)) {
+        return Err("Only one of --protoset -, -d @ and --repl may read from stdin.".into());
+    }
+
     Ok(ParsedArgs {
         address,
         command,
         symbol,
+        data,
     })
 }
 
+/// Whether the first positional argument is the server address.
+///
+/// Without a default address, anything other than a verb is the address. With
+/// one, the address may be omitted, so the first argument is only an address
+/// when a verb or symbol follows it (or it is the lone `--repl` argument).
+fn has_positional_address(args: &[&str], has_default: bool, cli: &Cli) -> bool {
+    let Some(first) = args.first() else {
+        return false;
+    };
+    if matches!(*first, "list" | "describe" | "reflection-info") {
+        return false;
+    }
+    if !has_default || cli.repl {
+        return true;
+    }
+    args.get(1)
+        .is_some_and(|next| !looks_like_request_data(next, cli.format))
+}
+
+/// Whether a positional argument is request data rather than a stray symbol.
+///
+/// JSON data must be an object or array. Text data is recognized by the
+/// separators protobuf text format needs, none of which appear in a
+/// fully-qualified symbol name.
+fn looks_like_request_data(arg: &str, format: Format) -> bool {
+    let trimmed = arg.trim_start();
+    match format {
+        Format::Json | Format::Ndjson => trimmed.starts_with('{') || trimmed.starts_with('['),
+        Format::Text => trimmed.contains([':', '{', '<']),
+    }
+}
+
 fn warn(msg: &str) {
     eprintln!("Warning: {msg}");
 }
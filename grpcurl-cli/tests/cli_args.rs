@@ -1,6 +1,6 @@
 mod common;
 
-use common::{assert_exit_code, run};
+use common::{assert_exit_code, assert_output_contains, run, run_with_env, testdata};
 
 /// Assert that the exit code is NOT 2 (validation error).
 /// These tests verify that args pass validation, even if the operation
@@ -69,3 +69,50 @@ fn import_path_without_proto_warning_not_error() {
     let r = run(&["-protoset", "/dev/null", "-import-path", "/tmp", "list"]);
     assert_exit_code(&r, 0);
 }
+
+#[test]
+fn tls_files_from_env() {
+    let pb = testdata("test.pb");
+    let args = ["-protoset", &pb, "localhost:1", "test.v1.Greeter/SayHello"];
+
+    let r = run_with_env(&args, &[("GRPCURL_CACERT", "/nonexistent/env-ca.pem")]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "'/nonexistent/env-ca.pem'");
+
+    // The flag overrides the environment
+    let mut with_flag = vec!["-cacert", "/nonexistent/flag-ca.pem"];
+    with_flag.extend(args);
+    let r = run_with_env(&with_flag, &[("GRPCURL_CACERT", "/nonexistent/env-ca.pem")]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "'/nonexistent/flag-ca.pem'");
+
+    let env = [
+        ("GRPCURL_CERT", "/nonexistent/env-cert.pem"),
+        ("GRPCURL_KEY", "/nonexistent/env-key.pem"),
+    ];
+    let r = run_with_env(&args, &env);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "'/nonexistent/env-cert.pem'");
+
+    // Half of the pair is a usage error naming the variables
+    let r = run_with_env(&args, &env[..1]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(
+        &r,
+        "The GRPCURL_CERT and GRPCURL_KEY environment variables must be set together",
+    );
+
+    // Environment defaults are not validated against -plaintext
+    let r = run_with_env(
+        &[
+            "-plaintext",
+            "-dry-run",
+            "-protoset",
+            &pb,
+            "localhost:1",
+            "test.v1.Greeter/SayHello",
+        ],
+        &[("GRPCURL_CACERT", "/nonexistent/env-ca.pem")],
+    );
+    assert_exit_code(&r, 0);
+}
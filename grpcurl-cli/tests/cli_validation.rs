@@ -1,6 +1,6 @@
 mod common;
 
-use common::{assert_exit_code, assert_output_contains, run};
+use common::{assert_exit_code, assert_output_contains, assert_output_not_contains, run, testdata};
 
 #[test]
 fn no_arguments() {
@@ -30,13 +30,6 @@ fn key_without_cert() {
     assert_output_contains(&r, "cert");
 }
 
-#[test]
-fn protoset_and_proto_conflict() {
-    let r = run(&["-protoset", "a.pb", "-proto", "b.proto", "list"]);
-    assert_exit_code(&r, 2);
-    assert_output_contains(&r, "protoset");
-}
-
 #[test]
 fn negative_connect_timeout() {
     let r = run(&["-connect-timeout", "-1", "localhost:8080", "list"]);
@@ -58,6 +51,15 @@ fn negative_max_time() {
     assert_output_contains(&r, "max-time");
 }
 
+#[test]
+fn non_finite_reflect_timeout() {
+    for value in ["NaN", "inf", "1e300"] {
+        let r = run(&["-reflect-timeout", value, "localhost:8080", "list"]);
+        assert_exit_code(&r, 2);
+        assert_output_contains(&r, "--reflect-timeout argument must be a number of seconds");
+    }
+}
+
 #[test]
 fn negative_max_msg_sz() {
     let r = run(&["-max-msg-sz", "-1", "localhost:8080", "list"]);
@@ -87,6 +89,26 @@ fn cert_with_plaintext() {
     assert_output_contains(&r, "cert");
 }
 
+#[test]
+fn cacert_with_plaintext() {
+    let r = run(&["-plaintext", "-cacert", "ca.pem", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "The --cacert argument can only be used with TLS");
+}
+
+#[test]
+fn servername_with_plaintext() {
+    let r = run(&[
+        "-plaintext",
+        "-servername",
+        "foo.com",
+        "localhost:8080",
+        "list",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "The --servername argument can only be used with TLS");
+}
+
 #[test]
 fn alts_handshaker_without_alts() {
     let r = run(&[
@@ -148,14 +170,540 @@ fn use_reflection_false_without_sources() {
 
 #[test]
 fn servername_and_authority_differ() {
+    // --authority sets the :authority header and --servername the TLS name,
+    // so they may differ. The protoset keeps this from needing a server.
+    let pb = testdata("test.pb");
     let r = run(&[
         "-servername",
         "foo.com",
         "-authority",
         "bar.com",
+        "-protoset",
+        &pb,
+        "localhost:8080",
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_not_contains(&r, "servername");
+}
+
+#[test]
+fn unix_scheme_without_path() {
+    let r = run(&["-plaintext", "unix://", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "socket path");
+}
+
+#[test]
+fn unix_scheme_with_relative_path() {
+    let r = run(&["-plaintext", "unix://run/app.sock", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "use unix:run/app.sock for a relative one");
+}
+
+#[test]
+fn unsupported_address_scheme() {
+    let r = run(&["-plaintext", "xds:///my-service", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "unsupported address scheme \"xds\"");
+}
+
+#[test]
+fn dns_address_without_host() {
+    let r = run(&["-plaintext", "dns:///", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "use dns:///host:port");
+}
+
+#[test]
+fn retry_base_must_be_positive() {
+    let r = run(&[
+        "-retry-base",
+        "0",
+        "-wait-for-ready",
+        "localhost:8080",
+        "list",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "--retry-base argument must be a positive number");
+}
+
+#[test]
+fn retry_max_below_base() {
+    let r = run(&[
+        "-retry-base",
+        "2",
+        "-retry-max",
+        "1",
+        "-wait-for-ready",
         "localhost:8080",
         "list",
     ]);
     assert_exit_code(&r, 2);
-    assert_output_contains(&r, "servername");
+    assert_output_contains(
+        &r,
+        "--retry-max argument must not be less than --retry-base",
+    );
+}
+
+#[test]
+fn retry_base_without_wait_for_ready_warns() {
+    let r = run(&[
+        "-retry-base",
+        "0.1",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "only used with --wait-for-ready");
+}
+
+#[test]
+fn reflection_info_requires_address() {
+    let r = run(&["-protoset", &testdata("test.pb"), "reflection-info"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "'reflection-info' verb requires a server address");
+}
+
+#[test]
+fn expect_code_invalid() {
+    for code in ["17", "-1", "not found", "Bogus"] {
+        let r = run(&["-expect-code", code, "localhost:8080", "my.Svc/Method"]);
+        assert_exit_code(&r, 2);
+        assert_output_contains(&r, "expect-code");
+    }
+}
+
+#[test]
+fn expect_code_with_list_warns() {
+    let r = run(&[
+        "-expect-code",
+        "NotFound",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_output_contains(&r, "--expect-code argument is not used");
+}
+
+#[test]
+fn fail_exit_code_zero() {
+    let r = run(&["-fail-exit-code", "0", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "fail-exit-code");
+}
+
+#[test]
+fn max_recv_total_zero() {
+    let r = run(&["-max-recv-total", "0", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "max-recv-total");
+}
+
+#[test]
+fn positional_data_with_d_flag() {
+    let r = run(&[
+        "-d",
+        "{}",
+        "localhost:8080",
+        "my.Svc/Method",
+        r#"{"id": 1}"#,
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "both with -d and as a positional argument");
+}
+
+#[test]
+fn positional_symbol_is_not_data() {
+    let r = run(&["localhost:8080", "my.Svc/Method", "my.Svc/Other"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "Too many arguments");
+}
+
+#[test]
+fn count_only_with_list_warns() {
+    let r = run(&["-count-only", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--count-only argument is not used");
+}
+
+#[test]
+fn fail_if_empty_with_invoke_warns() {
+    let r = run(&[
+        "-fail-if-empty",
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &testdata("test.pb"),
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--fail-if-empty argument is only used");
+}
+
+#[test]
+fn unsupported_accept_encoding() {
+    let r = run(&[
+        "-accept-encoding",
+        "gzip,zstd",
+        "-plaintext",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "supported encodings are gzip, deflate, identity");
+}
+
+#[test]
+fn indent_with_text_format_warns() {
+    let r = run(&[
+        "-indent",
+        "4",
+        "-format",
+        "text",
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &testdata("test.pb"),
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--indent-tabs arguments are only used");
+}
+
+#[test]
+fn stdin_data_with_other_data_flags() {
+    let r = run(&[
+        "-d",
+        "@",
+        "-d",
+        r#"{"name": "a"}"#,
+        "localhost:8080",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "cannot be combined with other -d values");
+}
+
+#[test]
+fn reflect_version_without_reflection_warns() {
+    let r = run(&[
+        "-reflect-version",
+        "v1alpha",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--reflect-version argument is not used");
+}
+
+#[test]
+fn invalid_reflect_version() {
+    let r = run(&["-reflect-version", "v2", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "reflect-version");
+}
+
+#[test]
+fn stdin_protoset_with_stdin_data() {
+    let r = run(&[
+        "-protoset",
+        "-",
+        "-d",
+        "@",
+        "localhost:8080",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "may read from stdin");
+}
+
+#[test]
+fn preserve_order_with_list_warns() {
+    let r = run(&["-preserve-order", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--preserve-order argument is only used");
+}
+
+#[test]
+fn plaintext_fallback_with_plaintext_warns() {
+    let r = run(&[
+        "-plaintext",
+        "-plaintext-fallback",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--plaintext-fallback argument is only used with TLS");
+}
+
+#[test]
+fn dump_wire_with_list_warns() {
+    let r = run(&[
+        "-dump-wire",
+        "wire.bin",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--dump-wire argument is not used");
+}
+
+#[test]
+fn rpc_path_must_be_absolute() {
+    let r = run(&[
+        "-rpc-path",
+        "custom/Method",
+        "-plaintext",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "--rpc-path argument must start with '/'");
+}
+
+#[test]
+fn strip_comments_without_proto_out_dir_warns() {
+    let r = run(&["-strip-comments", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(
+        &r,
+        "--strip-comments argument is only used with --proto-out-dir",
+    );
+}
+
+#[test]
+fn ndjson_input_with_text_format_warns() {
+    let r = run(&[
+        "-ndjson-input",
+        "-format",
+        "text",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--ndjson-input argument is only used when using json");
+}
+
+#[test]
+fn echo_request_with_list_warns() {
+    let r = run(&["-echo-request", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--echo-request argument is not used with 'list'");
+}
+
+#[test]
+fn summary_json_with_list_warns() {
+    let r = run(&["-summary-json", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--summary-json argument is not used with 'list'");
+}
+
+#[test]
+fn humanize_time_with_text_format_warns() {
+    let r = run(&[
+        "-humanize-time",
+        "-format",
+        "text",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--humanize-time argument is only used when using json");
+}
+
+#[test]
+fn all_methods_with_service_or_types_fails() {
+    let pb = testdata("test.pb");
+    let r = run(&["-all-methods", "-protoset", &pb, "list", "test.v1.Greeter"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "--all-methods argument cannot be combined");
+
+    let r = run(&["-all-methods", "-types", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 2);
+}
+
+#[test]
+fn count_with_list_json_types_or_all_methods_fails() {
+    let pb = testdata("test.pb");
+    for flag in ["-list-json", "-types", "-all-methods"] {
+        let r = run(&["-count", flag, "-protoset", &pb, "list"]);
+        assert_exit_code(&r, 2);
+        assert_output_contains(&r, "--count argument cannot be combined");
+    }
+
+    let r = run(&["-count", "-protoset", &pb, "describe"]);
+    assert_output_contains(&r, "--count argument is only used");
+}
+
+#[test]
+fn no_deadline_header_without_max_time_warns() {
+    let r = run(&[
+        "-no-deadline-header",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--no-deadline-header argument is not used");
+}
+
+#[test]
+fn invalid_status_style() {
+    let r = run(&["-status-style", "snake", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "must be 'go' or 'upper'");
+}
+
+#[test]
+fn prefer_without_composite_source_warns() {
+    let r = run(&["-prefer", "file", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--prefer argument is only used when both");
+
+    let r = run(&["-prefer", "server", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "must be 'file' or 'reflection'");
+}
+
+#[test]
+fn jsonschema_requires_describe_with_symbol() {
+    let pb = testdata("test.pb");
+    let r = run(&["-jsonschema", "-protoset", &pb, "describe"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(
+        &r,
+        "--jsonschema argument requires 'describe' with a symbol",
+    );
+
+    let r = run(&["-jsonschema", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 2);
+}
+
+#[test]
+fn request_type_with_list_warns() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-request-type",
+        "test.v1.HelloRequest",
+        "-protoset",
+        &pb,
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--request-type argument is not used");
+}
+
+#[test]
+fn output_dir_with_list_warns() {
+    let r = run(&[
+        "-output-dir",
+        "out",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--output-dir argument is not used");
+}
+
+#[test]
+fn tls_server_name_indication_disable_requires_tls() {
+    let r = run(&[
+        "-plaintext",
+        "-tls-server-name-indication-disable",
+        "localhost:8080",
+        "list",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(
+        &r,
+        "--tls-server-name-indication-disable argument can only be used with TLS",
+    );
+
+    let r = run(&[
+        "-tls-server-name-indication-disable",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "use --servername to verify it");
+
+    // Already following that advice
+    let r = run(&[
+        "-tls-server-name-indication-disable",
+        "-servername",
+        "legacy.internal",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_not_contains(&r, "use --servername to verify it");
+}
+
+#[test]
+fn extensions_requires_describe_with_symbol() {
+    let pb = testdata("test.pb");
+    let r = run(&["-extensions", "-protoset", &pb, "describe"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(
+        &r,
+        "--extensions argument requires 'describe' with a symbol",
+    );
+
+    let r = run(&[
+        "-extensions",
+        "-jsonschema",
+        "-protoset",
+        &pb,
+        "describe",
+        "test.v1.HelloRequest",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "mutually exclusive");
+}
+
+#[test]
+fn gen_request_id_with_list_warns() {
+    let r = run(&["-gen-request-id", "-protoset", &testdata("test.pb"), "list"]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--gen-request-id argument is not used");
+}
+
+#[test]
+fn skip_hostname_verify_requires_tls() {
+    let r = run(&[
+        "-plaintext",
+        "-skip-hostname-verify",
+        "localhost:8080",
+        "list",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(
+        &r,
+        "--skip-hostname-verify argument can only be used with TLS",
+    );
+}
+
+#[test]
+fn expect_trailer_with_list_warns() {
+    let r = run(&[
+        "-expect-trailer",
+        "x-a",
+        "-protoset",
+        &testdata("test.pb"),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "--expect-trailer argument is not used");
 }
@@ -58,8 +58,18 @@ pub fn run(args: &[&str]) -> RunResult {
     RunResult::from_output(output)
 }
 
+/// Run the grpcurl binary with extra environment variables set.
+pub fn run_with_env(args: &[&str], envs: &[(&str, &str)]) -> RunResult {
+    let output = Command::new(grpcurl_bin())
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("failed to execute grpcurl binary");
+    RunResult::from_output(output)
+}
+
 /// Run the grpcurl binary with stdin data piped in.
-pub fn run_with_stdin(args: &[&str], stdin_data: &str) -> RunResult {
+pub fn run_with_stdin(args: &[&str], stdin_data: impl AsRef<[u8]>) -> RunResult {
     use std::io::Write;
     use std::process::Stdio;
 
@@ -73,7 +83,7 @@ pub fn run_with_stdin(args: &[&str], stdin_data: &str) -> RunResult {
 
     if let Some(mut stdin) = child.stdin.take() {
         stdin
-            .write_all(stdin_data.as_bytes())
+            .write_all(stdin_data.as_ref())
             .expect("failed to write stdin");
     }
 
@@ -20,7 +20,13 @@ impl TestServer {
     ///
     /// Panics if the server fails to start or the port is not ready within 10s.
     pub fn start() -> Self {
-        let port = find_free_port();
+        Self::start_on(find_free_port())
+    }
+
+    /// Start a new testserver on the given port.
+    ///
+    /// Panics if the server fails to start or the port is not ready within 10s.
+    pub fn start_on(port: u16) -> Self {
         let addr = format!("localhost:{port}");
 
         // The testserver binary is built as a workspace member.
@@ -61,7 +67,7 @@ fn testserver_bin() -> std::path::PathBuf {
 }
 
 /// Bind to port 0 to get an ephemeral port from the OS.
-fn find_free_port() -> u16 {
+pub fn find_free_port() -> u16 {
     let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind ephemeral port");
     listener.local_addr().unwrap().port()
 }
@@ -0,0 +1,531 @@
+mod common;
+
+use common::{
+    assert_exit_code, assert_output_contains, assert_stdout_contains, run, run_with_env,
+    run_with_stdin, testdata,
+};
+
+#[test]
+fn dry_run_prints_request_without_server() {
+    let pb = testdata("test.pb");
+    // Nothing listens on port 1; a dry run must not try to connect.
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "-H",
+        "x-trace: abc",
+        "-d",
+        r#"{"name": "world"}"#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Method path: /test.v1.Greeter/SayHello");
+    assert_stdout_contains(&r, "x-trace: abc");
+    assert_stdout_contains(&r, "\"name\": \"world\"");
+    assert_stdout_contains(&r, "1 request message would be sent");
+}
+
+#[test]
+fn dry_run_empty_request() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Echo/Echo",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Method path: /test.v1.Echo/Echo");
+    assert_stdout_contains(&r, "1 request message would be sent");
+}
+
+#[test]
+fn dry_run_rejects_extra_unary_messages() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "-d",
+        r#"{"name": "a"} {"name": "b"}"#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "more than 1 message");
+}
+
+#[test]
+fn dry_run_invalid_json() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "-d",
+        r#"{"nope": 1}"#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+}
+
+#[test]
+fn dry_run_positional_request_data() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        r#"{"name": "world"}"#,
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\"name\": \"world\"");
+}
+
+#[test]
+fn dry_run_positional_text_request_data() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-format",
+        "text",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        r#"name: "world""#,
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "name: \"world\"");
+}
+
+#[test]
+fn dry_run_text_request_unknown_fields() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-format",
+        "text",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        r#"name: "world" nickname: "w""#,
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "field 'nickname' not found");
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-format",
+        "text",
+        "-allow-unknown-fields",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        r#"name: "world" nickname: "w""#,
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "name: \"world\"");
+}
+
+#[test]
+fn dry_run_data_from_stdin_dash() {
+    let pb = testdata("test.pb");
+    let r = run_with_stdin(
+        &[
+            "-plaintext",
+            "-dry-run",
+            "--data",
+            "@-",
+            "-protoset",
+            &pb,
+            "localhost:1",
+            "test.v1.Greeter/SayHello",
+        ],
+        r#"{"name": "stdin"}"#,
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\"name\": \"stdin\"");
+}
+
+#[test]
+fn dry_run_expand_data() {
+    // The binary inherits the test process environment.
+    std::env::set_var("GRPCURL_DRY_RUN_NAME", "from-env");
+    let pb = testdata("test.pb");
+    let r = run_with_stdin(
+        &[
+            "-plaintext",
+            "-dry-run",
+            "-expand-data",
+            "-d",
+            "@",
+            "-protoset",
+            &pb,
+            "localhost:1",
+            "test.v1.Greeter/SayHello",
+        ],
+        r#"{"name": "${GRPCURL_DRY_RUN_NAME} $${GRPCURL_DRY_RUN_NAME}"}"#,
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, r#""name": "from-env ${GRPCURL_DRY_RUN_NAME}""#);
+}
+
+#[test]
+fn dry_run_expand_data_undefined_variable() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-expand-data",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        r#"{"name": "${GRPCURL_DRY_RUN_UNDEFINED_12345}"}"#,
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "GRPCURL_DRY_RUN_UNDEFINED_12345");
+}
+
+#[test]
+fn dry_run_concatenates_repeated_data_flags() {
+    let pb = testdata("test.pb");
+    // Two -d values make two messages, which a unary method rejects
+    let r = run(&[
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "-d",
+        r#"{"name": "a"}"#,
+        "-d",
+        r#"{"name": "b"}"#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "more than 1 message");
+}
+
+#[test]
+fn dry_run_concatenates_repeated_text_data_flags() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-dry-run",
+        "-format",
+        "text",
+        "-protoset",
+        &pb,
+        "-d",
+        r#"name: "a""#,
+        "-d",
+        r#"name: "b""#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "more than 1 message");
+}
+
+#[test]
+fn dry_run_with_address_from_env() {
+    let pb = testdata("test.pb");
+    let env = [("GRPCURL_ADDRESS", "localhost:1")];
+    let r = run_with_env(
+        &[
+            "-plaintext",
+            "-dry-run",
+            "-protoset",
+            &pb,
+            "test.v1.Greeter/SayHello",
+            r#"{"name": "env"}"#,
+        ],
+        &env,
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Method path: /test.v1.Greeter/SayHello");
+    assert_stdout_contains(&r, r#""name": "env""#);
+
+    // Without the variable, the method would be taken as the address
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &pb,
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "Too few arguments");
+}
+
+#[test]
+fn dry_run_rejects_unknown_response_field() {
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &testdata("test.pb"),
+        "-response-fields",
+        "message,nope",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(
+        &r,
+        "field path \"nope\" does not match a field of test.v1.HelloReply",
+    );
+}
+
+#[test]
+fn dry_run_with_rpc_path() {
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-protoset",
+        &testdata("test.pb"),
+        "-rpc-path",
+        "/gateway/greeter/hello",
+        "-d",
+        r#"{"name": "world"}"#,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Method path: /gateway/greeter/hello");
+    assert_stdout_contains(&r, "\"name\": \"world\"");
+}
+
+#[test]
+fn dry_run_oneof_wrapped() {
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-oneof-wrapped",
+        "-protoset",
+        &testdata("test_complex.pb"),
+        "-d",
+        r#"{"name": "a", "choice": {"which": "number", "value": 7}}"#,
+        "localhost:1",
+        "test.v1.ComplexService/Unary",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\"which\": \"number\"");
+    assert_stdout_contains(&r, "\"value\": 7");
+}
+
+#[test]
+fn dry_run_ndjson_input() {
+    let pb = testdata("test_complex.pb");
+    let args = [
+        "-plaintext",
+        "-dry-run",
+        "-ndjson-input",
+        "-d",
+        "@",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.ComplexService/ClientStream",
+    ];
+
+    let r = run_with_stdin(&args, "{\"name\": \"a\"}\n\n{\"name\": \"b\"}\n");
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "2 request messages would be sent");
+
+    let r = run_with_stdin(
+        &args,
+        "{\"name\": \"a\"}\n{\"name\": \"b\"} {\"name\": \"c\"}\n",
+    );
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "line 2: invalid JSON request");
+
+    let r = run_with_stdin(&args, "{\"name\": \"a\"}\n{\"nmae\": \"b\"}\n");
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "line 2: failed to parse JSON request");
+}
+
+#[test]
+fn dry_run_no_default_request() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-no-default-request",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "no request data provided");
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-no-default-request",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+        "{}",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "1 request message would be sent");
+}
+
+#[test]
+fn dry_run_base64_request_type() {
+    let pb = testdata("test.pb");
+    // HelloRequest{name: "world"} in binary form
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-request-type",
+        "test.v1.HelloRequest",
+        "-protoset",
+        &pb,
+        "-d",
+        "CgV3b3JsZA==",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\"name\": \"world\"");
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-request-type",
+        "test.v1.HelloRequest",
+        "-protoset",
+        &pb,
+        "-d",
+        "not base64!",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "invalid base64 in request message 1");
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-request-type",
+        "test.v1.Nope",
+        "-protoset",
+        &pb,
+        "-d",
+        "CgV3b3JsZA==",
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "test.v1.Nope");
+}
+
+#[test]
+fn dry_run_gen_request_id() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-gen-request-id",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "x-request-id: ");
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-v",
+        "-gen-request-id=x-trace-id",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ]);
+    assert_exit_code(&r, 0);
+    let id = r
+        .stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Request ID (x-trace-id): "))
+        .expect("request ID on stderr");
+    assert_stdout_contains(&r, &format!("x-trace-id: {id}"));
+}
+
+#[test]
+fn dry_run_headers_from_env() {
+    let pb = testdata("test.pb");
+    let args = [
+        "-plaintext",
+        "-dry-run",
+        "-H",
+        "B: 3",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.Greeter/SayHello",
+    ];
+
+    let r = run_with_env(&args, &[("GRPCURL_HEADERS", "a: 1, b: 2")]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "a: 1\nb: 3\n");
+
+    // Lines keep their commas
+    let r = run_with_env(&args, &[("GRPCURL_HEADERS", "c: x,y\n\nd: 4\n")]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "b: 3\nc: x,y\nd: 4\n");
+}
+
+#[test]
+fn dry_run_require_request() {
+    let pb = testdata("test_complex.pb");
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-require-request",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.ComplexService/ClientStream",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(
+        &r,
+        "is a client-streaming RPC, but request data contained no messages",
+    );
+
+    let r = run(&[
+        "-plaintext",
+        "-dry-run",
+        "-require-request",
+        "-protoset",
+        &pb,
+        "localhost:1",
+        "test.v1.ComplexService/ClientStream",
+        r#"{"name": "a"}"#,
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "1 request message would be sent");
+}
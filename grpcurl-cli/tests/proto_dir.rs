@@ -0,0 +1,181 @@
+mod common;
+
+use std::path::Path;
+
+use common::{assert_exit_code, assert_output_contains, assert_stdout_contains, run};
+
+/// Write a small proto tree where `a/one.proto` imports `b/two.proto`.
+fn write_tree(root: &Path) {
+    std::fs::create_dir_all(root.join("a")).unwrap();
+    std::fs::create_dir_all(root.join("b/nested")).unwrap();
+    std::fs::write(
+        root.join("a/one.proto"),
+        "syntax = \"proto3\";\npackage tree.a;\nimport \"b/two.proto\";\n\
+         service One { rpc Call (tree.b.Two) returns (tree.b.Two); }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("b/two.proto"),
+        "syntax = \"proto3\";\npackage tree.b;\nmessage Two { string id = 1; }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("b/nested/three.proto"),
+        "syntax = \"proto3\";\npackage tree.c;\n\
+         service Three { rpc Call (Req) returns (Req); }\nmessage Req {}\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("b/README.md"), "not a proto").unwrap();
+}
+
+#[test]
+fn proto_dir_compiles_all_files() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    let root = dir.path().to_str().unwrap();
+    let r = run(&["-import-path", root, "-proto-dir", root, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "tree.a.One");
+    assert_stdout_contains(&r, "tree.c.Three");
+}
+
+#[test]
+fn proto_with_ellipsis_suffix() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    let root = dir.path().to_str().unwrap();
+    let tree = format!("{root}/...");
+    let r = run(&["-import-path", root, "-proto", &tree, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "tree.a.One");
+    assert_stdout_contains(&r, "tree.c.Three");
+}
+
+#[cfg(unix)]
+#[test]
+fn proto_dir_skips_symlinked_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    // A link back to the root would otherwise be walked forever
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("b/loop")).unwrap();
+    let root = dir.path().to_str().unwrap();
+    let r = run(&["-import-path", root, "-proto-dir", root, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "tree.a.One");
+    assert_stdout_contains(&r, "tree.c.Three");
+}
+
+#[test]
+fn proto_dir_without_protos() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let r = run(&["-import-path", root, "-proto-dir", root, "list"]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "no .proto files found");
+}
+
+#[test]
+fn proto_resolves_imports_from_its_own_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("api")).unwrap();
+    std::fs::write(
+        dir.path().join("api/svc.proto"),
+        "syntax = \"proto3\";\npackage api;\nimport \"types.proto\";\n\
+         service Svc { rpc Get (Item) returns (Item); }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("api/types.proto"),
+        "syntax = \"proto3\";\npackage api;\nmessage Item { string id = 1; }\n",
+    )
+    .unwrap();
+    let proto = dir.path().join("api/svc.proto");
+    let r = run(&["-proto", proto.to_str().unwrap(), "list", "api.Svc"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "api.Svc.Get");
+}
+
+#[test]
+fn compile_error_names_file_and_line() {
+    let dir = tempfile::tempdir().unwrap();
+    write_tree(dir.path());
+    std::fs::write(
+        dir.path().join("b/broken.proto"),
+        "syntax = \"proto3\";\npackage tree.b;\nmessage Broken {\n  string x = 1\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap();
+    let r = run(&["-import-path", root, "-proto-dir", root, "list"]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "b/broken.proto:5:1: expected ';'");
+
+    // Errors found after parsing are located too
+    std::fs::remove_file(dir.path().join("b/broken.proto")).unwrap();
+    std::fs::write(
+        dir.path().join("b/undefined.proto"),
+        "syntax = \"proto3\";\npackage tree.b;\nmessage A { Missing m = 1; }\n",
+    )
+    .unwrap();
+    let r = run(&["-import-path", root, "-proto-dir", root, "list"]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "b/undefined.proto:3:13: name 'Missing' is not defined");
+}
+
+#[test]
+fn describe_shows_custom_field_options() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("opts.proto"),
+        "syntax = \"proto3\";\npackage opts;\nimport \"google/protobuf/descriptor.proto\";\n\
+         message Rule { string pattern = 1; }\n\
+         extend google.protobuf.FieldOptions {\n  string label = 50001;\n  Rule rule = 50002;\n}\n\
+         message Item {\n  string id = 1 [(label) = \"say \\\"hi\\\"\", \
+         (rule) = { pattern: \"^x\" }];\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap();
+    let r = run(&[
+        "-import-path",
+        root,
+        "-proto",
+        "opts.proto",
+        "describe",
+        "opts.Item",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(
+        &r,
+        r#"string id = 1 [(opts.label) = "say \"hi\"", (opts.rule) = { pattern: "^x" }];"#,
+    );
+}
+
+#[test]
+fn describe_extensions_of_message() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("ext.proto"),
+        "syntax = \"proto2\";\npackage ext;\n\
+         message Base {\n  optional string id = 1;\n  extensions 100 to 200;\n}\n\
+         extend Base {\n  repeated int32 tags = 101;\n  optional string note = 100;\n}\n\
+         message Plain { optional string id = 1; }\n",
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap();
+    let args = ["-import-path", root, "-proto", "ext.proto", "-extensions"];
+
+    let r = run(&[&args[..], &["describe", "ext.Base"]].concat());
+    assert_exit_code(&r, 0);
+    assert_eq!(
+        r.stdout,
+        "ext.note is an extension:\nstring note = 100;\n\
+         ext.tags is an extension:\nrepeated int32 tags = 101;\n"
+    );
+
+    let r = run(&[&args[..], &["-fail-if-empty", "describe", "ext.Plain"]].concat());
+    assert_exit_code(&r, 1);
+    assert_eq!(r.stdout, "");
+
+    let r = run(&[&args[..], &["describe", "ext.Base.id"]].concat());
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "ext.Base.id is a field, not a message");
+}
@@ -53,6 +53,90 @@ fn proto_out_dir_with_describe() {
     );
 }
 
+#[test]
+fn proto_out_dir_keeps_message_and_method_options() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("opts.proto"),
+        "syntax = \"proto3\";\npackage opts;\nimport \"google/protobuf/descriptor.proto\";\n\
+         message Http { string get = 1; }\n\
+         extend google.protobuf.MethodOptions {\n  Http http = 50001;\n}\n\
+         message Item {\n  option deprecated = true;\n  string id = 1;\n}\n\
+         service Items {\n  rpc Get (Item) returns (Item) {\n    \
+         option (http) = { get: \"/v1/items\" };\n  }\n}\n",
+    )
+    .unwrap();
+    let out_dir = dir.path().join("out");
+    std::fs::create_dir(&out_dir).unwrap();
+    let root = dir.path().to_str().unwrap();
+    let r = run(&[
+        "-import-path",
+        root,
+        "-proto",
+        "opts.proto",
+        "-proto-out-dir",
+        out_dir.to_str().unwrap(),
+        "describe",
+        "opts.Items",
+    ]);
+    assert_exit_code(&r, 0);
+
+    let text = std::fs::read_to_string(out_dir.join("opts.proto")).unwrap();
+    assert!(
+        text.contains("message Item {\n  option deprecated = true;\n\n  string id = 1;\n}"),
+        "message option missing:\n{text}"
+    );
+    assert!(
+        text.contains(
+            "  rpc Get ( Item ) returns ( Item ) {\n    \
+             option (opts.http) = { get: \"/v1/items\" };\n  }\n"
+        ),
+        "method option missing:\n{text}"
+    );
+}
+
+#[test]
+fn proto_out_dir_comments_and_strip_comments() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("notes.proto"),
+        "syntax = \"proto3\";\npackage notes;\n\
+         // A note to self.\n\
+         message Note {\n  // The note text.\n  string text = 1; // trailing\n}\n\
+         // Stores notes.\n\
+         service Notes {\n  // Saves a note.\n  rpc Save (Note) returns (Note);\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap();
+    let export = |name: &str, extra: &[&str]| {
+        let out_dir = dir.path().join(name);
+        let out = out_dir.to_str().unwrap();
+        let mut args = vec!["-import-path", root, "-proto", "notes.proto"];
+        args.extend(["-proto-out-dir", out]);
+        args.extend(extra);
+        args.extend(["describe", "notes.Notes"]);
+        assert_exit_code(&run(&args), 0);
+        std::fs::read_to_string(out_dir.join("notes.proto")).unwrap()
+    };
+
+    let text = export("kept", &[]);
+    assert!(
+        text.contains(
+            "// A note to self.\nmessage Note {\n  // The note text.\n  string text = 1;\n}"
+        ),
+        "message comments missing:\n{text}"
+    );
+    assert!(
+        text.contains("// Stores notes.\nservice Notes {\n  // Saves a note.\n  rpc Save"),
+        "service comments missing:\n{text}"
+    );
+    assert!(!text.contains("trailing"), "trailing comment kept:\n{text}");
+
+    let text = export("stripped", &["-strip-comments"]);
+    assert!(!text.contains("//"), "comments not stripped:\n{text}");
+    assert!(text.contains("message Note {\n  string text = 1;\n}"));
+}
+
 // Server-dependent proto-out-dir test
 #[test]
 #[ignore]
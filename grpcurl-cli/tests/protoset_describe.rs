@@ -52,6 +52,17 @@ fn describe_field() {
     assert_stdout_contains(&r, "string name = 1");
 }
 
+#[test]
+fn describe_file() {
+    let pb = testdata("test_full.pb");
+    let r = run(&["-protoset", &pb, "describe", "test_full.proto"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "test_full.proto is a file:");
+    assert_stdout_contains(&r, "package test.v1;");
+    assert_stdout_contains(&r, "message HelloRequest {");
+    assert_stdout_contains(&r, "service Greeter {");
+}
+
 #[test]
 fn describe_all_services() {
     let pb = testdata("test_full.pb");
@@ -86,6 +97,26 @@ fn describe_streaming_service() {
     assert_stdout_contains(&r, "stream .test.v1.ComplexMessage");
 }
 
+#[test]
+fn describe_preserve_order() {
+    let pb = testdata("test.pb");
+    let r = run(&["-protoset", &pb, "describe", "test.v1.Greeter"]);
+    assert_exit_code(&r, 0);
+    // Sorted by name by default
+    assert!(r.stdout.find("SayGoodbye").unwrap() < r.stdout.find("SayHello").unwrap());
+
+    let r = run(&[
+        "-protoset",
+        &pb,
+        "-preserve-order",
+        "describe",
+        "test.v1.Greeter",
+    ]);
+    assert_exit_code(&r, 0);
+    // Declared SayHello first
+    assert!(r.stdout.find("SayHello").unwrap() < r.stdout.find("SayGoodbye").unwrap());
+}
+
 // -- msg-template tests --------------------------------------------------------
 
 #[test]
@@ -115,3 +146,57 @@ fn msg_template_complex_message() {
     assert_exit_code(&r, 0);
     assert_stdout_contains(&r, "Message template:");
 }
+
+#[test]
+fn msg_template_method_shows_request_and_response() {
+    let pb = testdata("test_full.pb");
+    let r = run(&[
+        "-protoset",
+        &pb,
+        "-msg-template",
+        "describe",
+        "test.v1.Echo.Echo",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Request template:");
+    assert_stdout_contains(&r, "\"count\": 0");
+    assert_stdout_contains(&r, "Response template:");
+    assert!(
+        !r.stdout.contains("Message template:"),
+        "method templates should be labeled per direction"
+    );
+}
+
+#[test]
+fn describe_jsonschema() {
+    let pb = testdata("test_complex.pb");
+    let r = run(&[
+        "-protoset",
+        &pb,
+        "-jsonschema",
+        "describe",
+        "test.v1.ComplexService.Unary",
+    ]);
+    assert_exit_code(&r, 0);
+    let schema: serde_json::Value = serde_json::from_str(&r.stdout).unwrap();
+    assert_eq!(schema["$ref"], "#/definitions/test.v1.ComplexMessage");
+    let props = &schema["definitions"]["test.v1.ComplexMessage"]["properties"];
+    assert_eq!(
+        props["labels"],
+        serde_json::json!({"type": "object", "additionalProperties": {"type": "integer"}})
+    );
+    assert_eq!(
+        props["items"]["items"]["$ref"],
+        "#/definitions/test.v1.NestedMessage"
+    );
+
+    let r = run(&[
+        "-protoset",
+        &pb,
+        "-jsonschema",
+        "describe",
+        "test.v1.ComplexService",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "not a message or method");
+}
@@ -81,3 +81,49 @@ fn protoset_out_with_reflection() {
         "protoset-out file should be non-empty"
     );
 }
+
+#[test]
+fn protoset_out_max_depth_exceeded() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("api.proto"),
+        "syntax = \"proto3\";\npackage api;\nimport \"types.proto\";\n\
+         service Api { rpc Get(types.Item) returns (types.Item); }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("types.proto"),
+        "syntax = \"proto3\";\npackage types;\nmessage Item { string id = 1; }\n",
+    )
+    .unwrap();
+    let import_path = dir.path().to_str().unwrap();
+    let out = dir.path().join("out.pb");
+    let out_path = out.to_str().unwrap();
+    let args = |depth| {
+        [
+            "-import-path",
+            import_path,
+            "-proto",
+            "api.proto",
+            "-protoset-out",
+            out_path,
+            "-max-depth",
+            depth,
+            "describe",
+            "api.Api",
+        ]
+    };
+
+    let r = run(&args("0"));
+    assert_exit_code(&r, 1);
+    assert!(
+        r.stderr
+            .contains("dependency depth limit of 0 exceeded at file 'types.proto'"),
+        "{}",
+        r.stderr
+    );
+
+    let r = run(&args("1"));
+    assert_exit_code(&r, 0);
+    assert!(out.exists());
+}
@@ -1,6 +1,6 @@
 mod common;
 
-use common::{assert_exit_code, assert_stdout_contains, run, testdata};
+use common::{assert_exit_code, assert_stdout_contains, run, run_with_stdin, testdata};
 
 #[test]
 fn list_all_services() {
@@ -25,3 +25,163 @@ fn list_nonexistent_service() {
     let r = run(&["-protoset", &pb, "list", "no.Such.Service"]);
     assert_exit_code(&r, 1);
 }
+
+#[test]
+fn list_json_services() {
+    let pb = testdata("test.pb");
+    let r = run(&["-protoset", &pb, "-list-json", "list"]);
+    assert_exit_code(&r, 0);
+    let services: Vec<String> = serde_json::from_str(&r.stdout).expect("valid JSON");
+    assert!(services.contains(&"test.v1.Echo".to_string()));
+    assert!(services.contains(&"test.v1.Greeter".to_string()));
+}
+
+#[test]
+fn list_json_methods() {
+    let pb = testdata("test.pb");
+    let r = run(&["-protoset", &pb, "-list-json", "list", "test.v1.Greeter"]);
+    assert_exit_code(&r, 0);
+    let methods: serde_json::Value = serde_json::from_str(&r.stdout).expect("valid JSON");
+    let first = &methods[0];
+    assert_eq!(first["name"], "test.v1.Greeter.SayGoodbye");
+    assert_eq!(first["input_type"], "test.v1.HelloRequest");
+    assert_eq!(first["output_type"], "test.v1.HelloReply");
+    assert_eq!(first["client_streaming"], false);
+    assert_eq!(first["server_streaming"], false);
+    assert_eq!(methods[1]["name"], "test.v1.Greeter.SayHello");
+}
+
+#[test]
+fn list_methods_verbose_shows_streaming_type() {
+    let pb = testdata("test_complex.pb");
+    let r = run(&["-v", "-protoset", &pb, "list", "test.v1.ComplexService"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "test.v1.ComplexService.Unary (unary)");
+    assert_stdout_contains(&r, "test.v1.ComplexService.ServerStream (server streaming)");
+    assert_stdout_contains(&r, "test.v1.ComplexService.ClientStream (client streaming)");
+    assert_stdout_contains(&r, "test.v1.ComplexService.BidiStream (bidi streaming)");
+}
+
+#[test]
+fn list_methods_default_has_no_annotation() {
+    let pb = testdata("test_complex.pb");
+    let r = run(&["-protoset", &pb, "list", "test.v1.ComplexService"]);
+    assert_exit_code(&r, 0);
+    assert!(!r.stdout.contains("(unary)"));
+}
+
+#[test]
+fn list_all_methods() {
+    let pb = testdata("test.pb");
+    let r = run(&["-all-methods", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+    assert_eq!(
+        r.stdout.lines().collect::<Vec<_>>(),
+        [
+            "test.v1.Echo.Echo",
+            "test.v1.Greeter.SayGoodbye",
+            "test.v1.Greeter.SayHello",
+        ]
+    );
+
+    let r = run(&["-all-methods", "-list-json", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+    let methods: Vec<serde_json::Value> = serde_json::from_str(&r.stdout).unwrap();
+    assert_eq!(methods.len(), 3);
+    assert_eq!(methods[0]["input_type"], "test.v1.EchoRequest");
+}
+
+#[test]
+fn list_types() {
+    let pb = testdata("test.pb");
+    let r = run(&["-types", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+    assert_eq!(
+        r.stdout.lines().collect::<Vec<_>>(),
+        [
+            "test.v1.EchoReply",
+            "test.v1.EchoRequest",
+            "test.v1.HelloReply",
+            "test.v1.HelloRequest",
+        ]
+    );
+}
+
+#[test]
+fn list_types_filters_by_package() {
+    let pb = testdata("test.pb");
+    let r = run(&["-types", "-list-json", "-protoset", &pb, "list", "test.v1"]);
+    assert_exit_code(&r, 0);
+    let types: Vec<String> = serde_json::from_str(&r.stdout).unwrap();
+    assert_eq!(types.len(), 4);
+
+    // A package prefix must match whole name segments
+    let r = run(&["-types", "-protoset", &pb, "list", "test.v"]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout, "");
+}
+
+#[test]
+fn fail_if_empty_exits_non_zero_on_empty_list() {
+    let pb = testdata("test.pb");
+    let r = run(&[
+        "-fail-if-empty",
+        "-types",
+        "-protoset",
+        &pb,
+        "list",
+        "no.such.pkg",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_eq!(r.stdout, "");
+
+    let r = run(&[
+        "-fail-if-empty",
+        "-types",
+        "-protoset",
+        &pb,
+        "list",
+        "test.v1",
+    ]);
+    assert_exit_code(&r, 0);
+    let r = run(&["-fail-if-empty", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+}
+
+#[test]
+fn list_count() {
+    let pb = testdata("test.pb");
+    let r = run(&["-count", "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout, "2\n");
+
+    let r = run(&["-count", "-protoset", &pb, "list", "test.v1.Greeter"]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout, "2\n");
+
+    // An empty listing still prints its count; -fail-if-empty then fails it
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("empty.proto"),
+        "syntax = \"proto3\";\npackage empty;\nmessage Nothing {}\n",
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap();
+    let args = ["-count", "-import-path", root, "-proto", "empty.proto"];
+    let r = run(&[&args[..], &["list"]].concat());
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout, "0\n");
+    let r = run(&[&args[..], &["-fail-if-empty", "list"]].concat());
+    assert_exit_code(&r, 1);
+    assert_eq!(r.stdout, "0\n");
+}
+
+#[test]
+fn list_services_from_stdin_protoset() {
+    let pb = std::fs::read(testdata("test.pb")).unwrap();
+    for path in ["-", "@"] {
+        let r = run_with_stdin(&["-protoset", path, "list"], &pb);
+        assert_exit_code(&r, 0);
+        assert_stdout_contains(&r, "test.v1.Greeter");
+    }
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{assert_exit_code, assert_output_contains, assert_stdout_contains, run, testdata};
+
+/// Compile `proto` (named `dup.proto`) into a protoset at `out`.
+fn make_protoset(dir: &std::path::Path, proto: &str, out: &std::path::Path) {
+    std::fs::write(dir.join("dup.proto"), proto).unwrap();
+    let r = run(&[
+        "-import-path",
+        dir.to_str().unwrap(),
+        "-proto",
+        "dup.proto",
+        "-protoset-out",
+        out.to_str().unwrap(),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+}
+
+#[test]
+fn identical_files_across_protosets() {
+    let pb = testdata("test.pb");
+    let r = run(&["-protoset", &pb, "-protoset", &pb, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "test.v1.Greeter");
+    assert!(!r.stderr.contains("warning"));
+}
+
+#[test]
+fn conflicting_files_prefer_first_and_warn() {
+    let dir = tempfile::tempdir().unwrap();
+    let first_dir = dir.path().join("first");
+    let second_dir = dir.path().join("second");
+    std::fs::create_dir_all(&first_dir).unwrap();
+    std::fs::create_dir_all(&second_dir).unwrap();
+    let first = dir.path().join("first.pb");
+    let second = dir.path().join("second.pb");
+
+    make_protoset(
+        &first_dir,
+        "syntax = \"proto3\";\npackage dup.v1;\nmessage M {}\n\
+         service First { rpc Call (M) returns (M); }\n",
+        &first,
+    );
+    make_protoset(
+        &second_dir,
+        "syntax = \"proto3\";\npackage dup.v1;\nmessage M {}\n\
+         service Second { rpc Call (M) returns (M); }\n",
+        &second,
+    );
+
+    let r = run(&[
+        "-protoset",
+        first.to_str().unwrap(),
+        "-protoset",
+        second.to_str().unwrap(),
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "dup.v1.First");
+    assert!(!r.stdout.contains("dup.v1.Second"));
+    assert_output_contains(&r, "\"dup.proto\"");
+    assert_output_contains(&r, "using the first");
+}
+
+#[test]
+fn protoset_combined_with_proto_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("extra.proto"),
+        "syntax = \"proto3\";\npackage extra.v1;\nmessage M {}\n\
+         service Extra { rpc Call (M) returns (M); }\n",
+    )
+    .unwrap();
+
+    let r = run(&[
+        "-protoset",
+        &testdata("test.pb"),
+        "-import-path",
+        dir.path().to_str().unwrap(),
+        "-proto",
+        "extra.proto",
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "test.v1.Greeter");
+    assert_stdout_contains(&r, "extra.v1.Extra");
+}
+
+#[test]
+fn protoset_wins_over_proto_source_with_same_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let pb_dir = dir.path().join("pb");
+    let src_dir = dir.path().join("src");
+    std::fs::create_dir_all(&pb_dir).unwrap();
+    std::fs::create_dir_all(&src_dir).unwrap();
+    let pb = dir.path().join("dup.pb");
+
+    make_protoset(
+        &pb_dir,
+        "syntax = \"proto3\";\npackage dup.v1;\nmessage M {}\n\
+         service First { rpc Call (M) returns (M); }\n",
+        &pb,
+    );
+    std::fs::write(
+        src_dir.join("dup.proto"),
+        "syntax = \"proto3\";\npackage dup.v1;\nmessage M {}\n\
+         service Second { rpc Call (M) returns (M); }\n",
+    )
+    .unwrap();
+
+    let r = run(&[
+        "-protoset",
+        pb.to_str().unwrap(),
+        "-import-path",
+        src_dir.to_str().unwrap(),
+        "-proto",
+        "dup.proto",
+        "list",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "dup.v1.First");
+    assert!(!r.stdout.contains("dup.v1.Second"));
+    assert_output_contains(&r, "file \"dup.proto\" in proto sources differs");
+}
+
+#[test]
+fn proto_sources_keep_comments_when_combined_with_protoset() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("extra.proto"),
+        "syntax = \"proto3\";\npackage extra.v1;\n// An extra message.\nmessage M {}\n",
+    )
+    .unwrap();
+
+    let r = run(&[
+        "-protoset",
+        &testdata("test.pb"),
+        "-import-path",
+        dir.path().to_str().unwrap(),
+        "-proto",
+        "extra.proto",
+        "describe",
+        "extra.proto",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "// An extra message.\nmessage M {");
+    assert!(!r.stderr.contains("warning"), "{}", r.stderr);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::sync::LazyLock;
+
+use common::server::TestServer;
+use common::{
+    assert_exit_code, assert_output_contains, assert_stdout_contains, run, run_with_stdin, testdata,
+};
+
+static SERVER: LazyLock<TestServer> = LazyLock::new(TestServer::start);
+
+#[test]
+fn repl_with_protoset() {
+    let pb = testdata("test.pb");
+    let r = run_with_stdin(
+        &["-protoset", &pb, "-repl"],
+        "list\ndescribe test.v1.Greeter\n\nhelp\ntest.v1.Greeter/SayHello {}\nexit\nlist\n",
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "test.v1.Greeter");
+    assert_stdout_contains(&r, "rpc SayHello");
+    assert_stdout_contains(&r, "<method> [data]");
+    // No address: invoking fails but the session continues until `exit`
+    assert_output_contains(&r, "No host:port specified");
+    assert_eq!(
+        r.stdout.matches("test.v1.Echo\n").count(),
+        1,
+        "stdout: {}",
+        r.stdout
+    );
+}
+
+#[test]
+fn repl_reports_errors_and_continues() {
+    let pb = testdata("test.pb");
+    let r = run_with_stdin(
+        &["-protoset", &pb, "-repl"],
+        "describe no.such.Symbol\nlist\n",
+    );
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "Failed to resolve symbol \"no.such.Symbol\"");
+    assert_stdout_contains(&r, "test.v1.Greeter");
+}
+
+#[test]
+fn repl_rejects_verb_arguments() {
+    let r = run(&["-repl", "localhost:8080", "list"]);
+    assert_exit_code(&r, 2);
+    assert_output_contains(&r, "--repl argument takes only an address");
+}
+
+#[test]
+#[ignore]
+fn repl_invokes_over_shared_connection() {
+    let r = run_with_stdin(
+        &["-plaintext", "-repl", &SERVER.addr],
+        "list\n\
+         testing.TestService/UnaryCall {\"payload\":{\"body\":\"dGVzdA==\"}}\n\
+         testing.TestService/UnaryCall {\"responseStatus\":{\"code\":5,\"message\":\"missing\"}}\n\
+         testing.TestService/EmptyCall\n",
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.TestService");
+    assert_stdout_contains(&r, "dGVzdA==");
+    // A failed RPC is reported without ending the session
+    assert_output_contains(&r, "Code: NotFound");
+    assert_stdout_contains(&r, "{}");
+}
@@ -3,7 +3,10 @@ mod common;
 use std::sync::LazyLock;
 
 use common::server::TestServer;
-use common::{assert_exit_code, assert_stdout_contains, run, run_with_stdin};
+use common::{
+    assert_exit_code, assert_output_contains, assert_stdout_contains, run, run_with_env,
+    run_with_stdin,
+};
 
 static SERVER: LazyLock<TestServer> = LazyLock::new(TestServer::start);
 
@@ -34,6 +37,59 @@ fn complex_service_get_well_known() {
     assert_exit_code(&r, 0);
 }
 
+#[test]
+#[ignore]
+fn humanize_time_renders_local_timestamps() {
+    // A POSIX TZ string, so no time zone database is needed
+    let r = run_with_env(
+        &[
+            "-plaintext",
+            "-humanize-time",
+            "-d",
+            r#"{"timestampField":"2024-01-01T00:00:00.500Z"}"#,
+            &SERVER.addr,
+            "testing.ComplexService/GetWellKnown",
+        ],
+        &[("TZ", "EST5")],
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, r#""timestampField": "2023-12-31 19:00:00.500 -05:00""#);
+}
+
+#[test]
+#[ignore]
+fn max_time_sends_remaining_budget_as_deadline() {
+    let args = ["-v", "-plaintext", "-max-time", "5"];
+    let r = run(&[&args[..], &[&SERVER.addr, "testing.TestService/EmptyCall"]].concat());
+    assert_exit_code(&r, 0);
+    // The server echoes the grpc-timeout it received; reflection and
+    // connecting have used up part of the 5 seconds
+    let timeout = r
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("received-grpc-timeout: "))
+        .unwrap_or_else(|| panic!("no received-grpc-timeout header in {}", r.stdout));
+    let (value, unit) = timeout.split_at(timeout.len() - 1);
+    let micros = match unit {
+        "u" => value.parse::<u64>().unwrap(),
+        "n" => value.parse::<u64>().unwrap() / 1000,
+        _ => panic!("unexpected grpc-timeout {timeout}"),
+    };
+    assert!((1..5_000_000).contains(&micros), "{timeout}");
+
+    let r = run(&[
+        &args[..],
+        &[
+            "-no-deadline-header",
+            &SERVER.addr,
+            "testing.TestService/EmptyCall",
+        ],
+    ]
+    .concat());
+    assert_exit_code(&r, 0);
+    assert!(!r.stdout.contains("received-grpc-timeout"), "{}", r.stdout);
+}
+
 #[test]
 #[ignore]
 fn max_msg_sz_enforcement() {
@@ -68,3 +124,121 @@ fn stdin_input() {
     );
     assert_exit_code(&r, 0);
 }
+
+#[test]
+#[ignore]
+fn max_msg_sz_enforced_on_request() {
+    // The request payload alone exceeds the limit, so it must be rejected
+    // before it is sent.
+    let r = run(&[
+        "-plaintext",
+        "-max-msg-sz",
+        "16",
+        "-d",
+        r#"{"payload": {"body": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert!(
+        r.exit_code != 0,
+        "Expected non-zero exit code for oversized request.\nstdout: {}\nstderr: {}",
+        r.stdout,
+        r.stderr
+    );
+    assert_output_contains(&r, "too large");
+}
+
+#[test]
+#[ignore]
+fn grpc_web_unary() {
+    let r = run(&[
+        "-plaintext",
+        "-grpc-web",
+        "-d",
+        r#"{"payload": {"body": "AQID"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "AQID");
+}
+
+#[test]
+#[ignore]
+fn grpc_web_server_stream() {
+    let r = run(&[
+        "-plaintext",
+        "-grpc-web",
+        "-d",
+        r#"{"responseParameters": [{"size": 1}, {"size": 2}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+}
+
+#[test]
+#[ignore]
+fn grpc_web_error_status() {
+    let r = run(&[
+        "-plaintext",
+        "-grpc-web",
+        "-d",
+        r#"{"responseStatus":{"code":5,"message":"resource missing"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    // Status comes from the trailer frame in the grpc-web response body
+    assert_exit_code(&r, 64 + 5);
+    assert_output_contains(&r, "resource missing");
+}
+
+#[test]
+#[ignore]
+fn any_field_expands_with_reflection() {
+    let r = run(&[
+        "-plaintext",
+        "-format",
+        "ndjson",
+        "-d",
+        r#"{"anyField":{"@type":"type.googleapis.com/testing.Payload","body":"AQID"}}"#,
+        &SERVER.addr,
+        "testing.ComplexService/GetWellKnown",
+    ]);
+    assert_exit_code(&r, 0);
+    let value: serde_json::Value = serde_json::from_str(r.stdout.trim()).unwrap();
+    assert_eq!(
+        value["anyField"],
+        serde_json::json!({"@type": "type.googleapis.com/testing.Payload", "body": "AQID"})
+    );
+}
+
+#[test]
+#[ignore]
+fn no_resolve_any_leaves_any_raw() {
+    let r = run(&[
+        "-plaintext",
+        "-no-resolve-any",
+        "-format",
+        "ndjson",
+        "-d",
+        concat!(
+            r#"{"timestampField":"2024-01-01T00:00:00Z","#,
+            r#""anyField":{"@type":"type.googleapis.com/testing.Payload","body":"AQID"},"#,
+            r#""structField":{"k":"v"}}"#
+        ),
+        &SERVER.addr,
+        "testing.ComplexService/GetWellKnown",
+    ]);
+    assert_exit_code(&r, 0);
+    let value: serde_json::Value = serde_json::from_str(r.stdout.trim()).unwrap();
+    assert_eq!(value["timestampField"], "2024-01-01T00:00:00Z");
+    assert_eq!(value["structField"], serde_json::json!({"k": "v"}));
+    assert_eq!(
+        value["anyField"]["@type"],
+        "type.googleapis.com/testing.Payload"
+    );
+    // Payload { body: 0x010203 } encoded on the wire
+    assert_eq!(value["anyField"]["value"], "EgMBAgM=");
+    assert!(value["anyField"].get("body").is_none());
+}
@@ -28,6 +28,15 @@ fn describe_test_service() {
     assert_stdout_contains(&r, "rpc EmptyCall");
 }
 
+#[test]
+#[ignore]
+fn describe_file_by_name() {
+    let r = run(&["-plaintext", &SERVER.addr, "describe", "testserver.proto"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testserver.proto is a file:");
+    assert_stdout_contains(&r, "service TestService {");
+}
+
 #[test]
 #[ignore]
 fn describe_simple_request() {
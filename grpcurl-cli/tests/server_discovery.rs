@@ -3,7 +3,7 @@ mod common;
 use std::sync::LazyLock;
 
 use common::server::TestServer;
-use common::{assert_exit_code, assert_stdout_contains, run};
+use common::{assert_exit_code, assert_output_contains, assert_stdout_contains, run};
 
 static SERVER: LazyLock<TestServer> = LazyLock::new(TestServer::start);
 
@@ -15,6 +15,35 @@ fn list_all_services() {
     assert_stdout_contains(&r, "testing.TestService");
 }
 
+#[test]
+#[ignore]
+fn list_with_plaintext_fallback() {
+    // The test server only speaks plaintext, so the TLS attempt fails first
+    let r = run(&["-plaintext-fallback", &SERVER.addr, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.TestService");
+    assert_output_contains(&r, "retrying in plaintext");
+}
+
+#[test]
+#[ignore]
+fn list_with_dns_scheme_address() {
+    let address = format!("dns:///{}", SERVER.addr);
+    let r = run(&["-plaintext", &address, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.TestService");
+}
+
+#[test]
+#[ignore]
+fn reflection_info_reports_versions() {
+    let r = run(&["-plaintext", &SERVER.addr, "reflection-info"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "grpc.reflection.v1.ServerReflection: available");
+    assert_stdout_contains(&r, "grpc.reflection.v1alpha.ServerReflection: available");
+    assert_stdout_contains(&r, "Advertised reflection services: ");
+}
+
 #[test]
 #[ignore]
 fn list_test_service_methods() {
@@ -50,3 +79,128 @@ fn list_nonexistent_service() {
     let r = run(&["-plaintext", &SERVER.addr, "list", "no.Such.Service"]);
     assert_exit_code(&r, 1);
 }
+
+#[test]
+#[ignore]
+fn describe_with_forced_reflection_versions() {
+    for version in ["v1", "v1alpha"] {
+        let r = run(&[
+            "-plaintext",
+            "-reflect-version",
+            version,
+            &SERVER.addr,
+            "describe",
+            "testing.TestService",
+        ]);
+        assert_exit_code(&r, 0);
+        assert_stdout_contains(&r, "rpc UnaryCall");
+    }
+}
+
+#[test]
+#[ignore]
+fn list_types_via_reflection() {
+    let r = run(&["-plaintext", "-types", &SERVER.addr, "list", "testing"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.SimpleRequest\n");
+    assert_stdout_contains(&r, "testing.PayloadType\n");
+    assert_stdout_contains(&r, "testing.ComplexMessage.NestedMessage\n");
+    assert!(!r.stdout.contains("google.protobuf."));
+}
+
+#[test]
+#[ignore]
+fn list_all_methods_via_reflection() {
+    let r = run(&["-plaintext", "-all-methods", &SERVER.addr, "list"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.ComplexService.GetComplex\n");
+    assert_stdout_contains(&r, "testing.TestService.FullDuplexCall\n");
+    assert_stdout_contains(
+        &r,
+        "grpc.reflection.v1.ServerReflection.ServerReflectionInfo\n",
+    );
+}
+
+#[test]
+#[ignore]
+fn bundled_well_known_types_skip_reflection() {
+    let r = run(&[
+        "-plaintext",
+        "-trace-reflection",
+        "-no-reflect-cache-wellknown",
+        &SERVER.addr,
+        "describe",
+        "testing.WellKnownTypesMessage",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, ".google.protobuf.Timestamp timestamp_field = 1;");
+    assert!(
+        !r.stderr.contains("FileByFilename(google/protobuf/"),
+        "stderr: {}",
+        r.stderr
+    );
+}
+
+#[test]
+#[ignore]
+fn trace_reflection_logs_requests() {
+    let r = run(&[
+        "-plaintext",
+        "-trace-reflection",
+        &SERVER.addr,
+        "describe",
+        "testing.TestService",
+    ]);
+    assert_exit_code(&r, 0);
+    assert!(
+        r.stderr.contains(
+            "reflect: FileContainingSymbol(testing.TestService) -> FileDescriptorResponse("
+        ),
+        "stderr: {}",
+        r.stderr
+    );
+
+    // A failed lookup shows up in the trace too
+    let r = run(&[
+        "-plaintext",
+        "-trace-reflection",
+        &SERVER.addr,
+        "describe",
+        "no.Such",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "reflect: FileContainingSymbol(no.Such) -> ");
+}
+
+#[tokio::test]
+#[ignore]
+async fn reflection_source_returns_all_files() {
+    use grpcurl_core::connection::{self, ConnectionConfig};
+    use grpcurl_core::descriptor::DescriptorSource;
+    use grpcurl_core::reflection::ServerSource;
+
+    let config = ConnectionConfig {
+        plaintext: true,
+        ..Default::default()
+    };
+    let channel = connection::create_channel(&config, &SERVER.addr)
+        .await
+        .unwrap();
+    let files = ServerSource::new(channel).get_all_files().await.unwrap();
+
+    let mut names: Vec<&str> = files.iter().map(|f| f.name()).collect();
+    names.sort_unstable();
+    let count = names.len();
+    names.dedup();
+    assert_eq!(names.len(), count, "duplicate files: {names:?}");
+    // Every advertised service's file, with its imports
+    for name in [
+        "testserver.proto",
+        "google/protobuf/any.proto",
+        "google/protobuf/empty.proto",
+        "google/protobuf/struct.proto",
+        "google/protobuf/timestamp.proto",
+    ] {
+        assert!(names.contains(&name), "missing {name}: {names:?}");
+    }
+}
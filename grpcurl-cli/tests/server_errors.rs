@@ -1,6 +1,7 @@
 mod common;
 
 use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
 use common::server::TestServer;
 use common::{assert_exit_code, assert_output_contains, run};
@@ -83,3 +84,193 @@ fn format_error_with_error_status() {
     ]);
     assert_output_contains(&r, "test error");
 }
+
+#[test]
+#[ignore]
+fn fail_exit_code_overrides_status_offset() {
+    let r = run(&[
+        "-plaintext",
+        "-fail-exit-code",
+        "3",
+        "-d",
+        r#"{"responseStatus":{"code":5,"message":"resource missing"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 3);
+    assert_output_contains(&r, "resource missing");
+}
+
+#[test]
+#[ignore]
+fn expect_code_matches_error_status() {
+    let not_found = r#"{"responseStatus":{"code":5,"message":"resource missing"}}"#;
+    for code in ["5", "NotFound"] {
+        let r = run(&[
+            "-plaintext",
+            "-expect-code",
+            code,
+            "-d",
+            not_found,
+            &SERVER.addr,
+            "testing.TestService/UnaryCall",
+        ]);
+        assert_exit_code(&r, 0);
+        assert_output_contains(&r, "resource missing");
+    }
+
+    let r = run(&[
+        "-plaintext",
+        "-expect-code",
+        "PermissionDenied",
+        "-d",
+        not_found,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "Expected status PermissionDenied, got NotFound");
+
+    let r = run(&[
+        "-plaintext",
+        "-expect-code",
+        "NotFound",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "Expected status NotFound, got OK");
+}
+
+#[test]
+#[ignore]
+fn wait_for_ready_connects_to_late_server() {
+    let port = common::server::find_free_port();
+    let addr = format!("localhost:{port}");
+
+    // Bring the server up only after grpcurl has started retrying
+    let server = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(500));
+        TestServer::start_on(port)
+    });
+
+    let r = run(&[
+        "-plaintext",
+        "-wait-for-ready",
+        "-connect-timeout",
+        "10",
+        &addr,
+        "list",
+    ]);
+    let _server = server.join().unwrap();
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "testing.TestService");
+}
+
+#[test]
+fn wait_for_ready_gives_up_after_connect_timeout() {
+    // Nothing listens on port 1, so every attempt is refused
+    let start = Instant::now();
+    let r = run(&[
+        "-plaintext",
+        "-wait-for-ready",
+        "-connect-timeout",
+        "0.5",
+        "127.0.0.1:1",
+        "list",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "failed to connect");
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[test]
+fn wait_for_ready_gives_up_when_max_time_runs_out() {
+    let start = Instant::now();
+    let r = run(&[
+        "-plaintext",
+        "-wait-for-ready",
+        "-connect-timeout",
+        "30",
+        "-max-time",
+        "0.5",
+        "127.0.0.1:1",
+        "list",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "failed to connect");
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+#[ignore]
+fn error_format_json_status() {
+    let r = run(&[
+        "-plaintext",
+        "-error-format",
+        "json",
+        "-d",
+        r#"{"responseStatus":{"code":5,"message":"resource missing"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 64 + 5);
+    let status: serde_json::Value = serde_json::from_str(r.stderr.trim()).unwrap();
+    assert_eq!(status["code"], "NotFound");
+    assert_eq!(status["codeNumber"], 5);
+    assert_eq!(status["message"], "resource missing");
+    assert_eq!(status["details"], serde_json::json!([]));
+    assert!(!r.stderr.contains("ERROR:"));
+}
+
+#[test]
+#[ignore]
+fn status_style_upper() {
+    let data = r#"{"responseStatus":{"code":4,"message":"too slow"}}"#;
+    let r = run(&[
+        "-plaintext",
+        "-status-style",
+        "upper",
+        "-v",
+        "-d",
+        data,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 64 + 4);
+    assert_output_contains(&r, "Code: DEADLINE_EXCEEDED");
+    assert_output_contains(&r, "Finished with status DEADLINE_EXCEEDED");
+
+    let r = run(&[
+        "-plaintext",
+        "-status-style",
+        "upper",
+        "-error-format",
+        "json",
+        "-d",
+        data,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    let status: serde_json::Value = serde_json::from_str(r.stderr.trim()).unwrap();
+    assert_eq!(status["code"], "DEADLINE_EXCEEDED");
+}
+
+#[test]
+fn reflect_timeout_on_unresponsive_server() {
+    // Accepts TCP connections but never speaks HTTP/2, so reflection hangs
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        let mut conns = Vec::new();
+        for conn in listener.incoming() {
+            conns.push(conn);
+        }
+    });
+
+    let start = Instant::now();
+    let r = run(&["-plaintext", "-reflect-timeout", "0.5", &addr, "list"]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "reflection request timed out after 0.5s");
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -3,7 +3,7 @@ mod common;
 use std::sync::LazyLock;
 
 use common::server::TestServer;
-use common::{assert_exit_code, assert_output_contains, run};
+use common::{assert_exit_code, assert_output_contains, assert_output_not_contains, run};
 
 static SERVER: LazyLock<TestServer> = LazyLock::new(TestServer::start);
 
@@ -95,3 +95,51 @@ fn rpc_header_only() {
     assert_exit_code(&r, 0);
     assert_output_contains(&r, "x-rpc-only: rpc-value");
 }
+
+#[test]
+#[ignore]
+fn expect_trailer() {
+    // The test server only sends custom trailers with an error status
+    let call = |expected: &str| {
+        run(&[
+            "-plaintext",
+            "-H",
+            "reply-with-trailers: x-checksum: abc",
+            "-H",
+            "fail-late: 10",
+            "-expect-trailer",
+            expected,
+            &SERVER.addr,
+            "testing.TestService/EmptyCall",
+        ])
+    };
+
+    let r = call("x-checksum: abc");
+    assert_exit_code(&r, 74);
+    assert_output_not_contains(&r, "Expected trailer");
+    let r = call("x-checksum");
+    assert_output_not_contains(&r, "Expected trailer");
+
+    let r = call("x-checksum: abd");
+    assert_exit_code(&r, 74);
+    assert_output_contains(
+        &r,
+        "Expected trailer \"x-checksum: abd\" not found in response trailers",
+    );
+}
+
+#[test]
+#[ignore]
+fn expect_trailer_fails_ok_call() {
+    let r = run(&[
+        "-plaintext",
+        "-expect-trailer",
+        "x-missing",
+        "-d",
+        r#"{"responseParameters":[{"size":1}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "Expected trailer \"x-missing\" not found");
+}
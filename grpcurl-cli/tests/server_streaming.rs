@@ -104,3 +104,303 @@ fn bidi_streaming_via_reflection() {
     assert_exit_code(&r, 0);
     assert_stdout_contains(&r, "payload");
 }
+
+#[test]
+#[ignore]
+fn ndjson_streaming_one_line_per_response() {
+    let r = run(&[
+        "-plaintext",
+        "-format",
+        "ndjson",
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    let lines: Vec<&str> = r.stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "stdout: {}", r.stdout);
+    for line in lines {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(value.get("payload").is_some(), "line: {line}");
+    }
+    assert!(!r.stdout.contains('\u{1e}'));
+}
+
+#[test]
+#[ignore]
+fn max_recv_total_aborts_stream() {
+    let r = run(&[
+        "-plaintext",
+        "-max-recv-total",
+        "8",
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "--max-recv-total budget of 8 bytes");
+    // The first response fits in the budget and is printed before aborting
+    assert_eq!(
+        r.stdout.matches("payload").count(),
+        1,
+        "stdout: {}",
+        r.stdout
+    );
+}
+
+#[test]
+#[ignore]
+fn max_recv_total_within_budget() {
+    let r = run(&[
+        "-plaintext",
+        "-max-recv-total",
+        "1024",
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_eq!(
+        r.stdout.matches("payload").count(),
+        2,
+        "stdout: {}",
+        r.stdout
+    );
+}
+
+#[test]
+#[ignore]
+fn empty_text_stream_sends_no_messages() {
+    let args = [
+        "-plaintext",
+        "-v",
+        "-format",
+        "text",
+        &SERVER.addr,
+        "testing.TestService/StreamingInputCall",
+    ];
+
+    // Go-compatible default: empty text input is one empty message
+    let r = run(&args);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Sent 1 request and received 1 response");
+
+    let mut empty_args = vec!["-empty-text-stream"];
+    empty_args.extend_from_slice(&args);
+    let r = run(&empty_args);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "Sent 0 requests and received 1 response");
+}
+
+#[test]
+#[ignore]
+fn count_only_prints_response_count() {
+    let r = run(&[
+        "-plaintext",
+        "-count-only",
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5},{"size":1}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout.trim(), "3");
+}
+
+#[test]
+#[ignore]
+fn count_only_bidi_stream() {
+    let r = run(&[
+        "-plaintext",
+        "-count-only",
+        "-d",
+        r#"{"responseParameters":[{"size":3}]} {"responseParameters":[{"size":2}]}"#,
+        &SERVER.addr,
+        "testing.TestService/FullDuplexCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout.trim(), "2");
+}
+
+#[test]
+#[ignore]
+fn output_dir_writes_numbered_response_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("responses");
+    let r = run(&[
+        "-plaintext",
+        "-output-dir",
+        out.to_str().unwrap(),
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert!(!r.stdout.contains("payload"), "stdout: {}", r.stdout);
+    let first = std::fs::read_to_string(out.join("response-0001.json")).unwrap();
+    assert!(first.contains("payload"), "{first}");
+    assert!(out.join("response-0002.json").exists());
+    assert!(!out.join("response-0003.json").exists());
+}
+
+#[test]
+#[ignore]
+fn output_dir_text_files_have_no_record_separator() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let r = run(&[
+        "-plaintext",
+        "-format",
+        "text",
+        "-output-dir",
+        out,
+        "-d",
+        "response_parameters: <size: 3> response_parameters: <size: 5>",
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    let second = std::fs::read(dir.path().join("response-0002.txt")).unwrap();
+    assert!(second.starts_with(b"payload"), "{second:?}");
+    assert!(!second.contains(&0x1e), "{second:?}");
+}
+
+#[test]
+#[ignore]
+fn output_dir_writes_unary_response() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let r = run(&[
+        "-plaintext",
+        "-output-dir",
+        out,
+        "-d",
+        r#"{"payload":{"body":"aGk="}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert!(!r.stdout.contains("payload"), "stdout: {}", r.stdout);
+    let first = std::fs::read_to_string(dir.path().join("response-0001.json")).unwrap();
+    assert!(first.contains("payload"), "{first}");
+}
+
+#[test]
+#[ignore]
+fn require_request_rejects_empty_streams() {
+    let r = run(&[
+        "-plaintext",
+        "-require-request",
+        "-d",
+        "",
+        &SERVER.addr,
+        "testing.TestService/FullDuplexCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(
+        &r,
+        "is a bidi-streaming RPC, but request data contained no messages",
+    );
+
+    let r = run(&[
+        "-plaintext",
+        "-d",
+        "",
+        &SERVER.addr,
+        "testing.TestService/FullDuplexCall",
+    ]);
+    assert_exit_code(&r, 0);
+}
+
+#[test]
+#[ignore]
+fn bidi_stream_messages_from_repeated_data_flags() {
+    let r = run(&[
+        "-plaintext",
+        "-count-only",
+        "-d",
+        r#"{"responseParameters":[{"size":3}]}"#,
+        "-d",
+        r#"{"responseParameters":[{"size":2}]} {"responseParameters":[{"size":1}]}"#,
+        &SERVER.addr,
+        "testing.TestService/FullDuplexCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_eq!(r.stdout.trim(), "3");
+}
+
+#[test]
+#[ignore]
+fn streaming_output_call_dump_wire() {
+    let dir = tempfile::tempdir().unwrap();
+    let dump = dir.path().join("wire.bin");
+    let r = run(&[
+        "-plaintext",
+        "-dump-wire",
+        dump.to_str().unwrap(),
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "payload");
+
+    // Split the dump into (marker, length) records; they must cover the file
+    let bytes = std::fs::read(&dump).unwrap();
+    let mut records = Vec::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes(rest[1..5].try_into().unwrap()) as usize;
+        records.push((rest[0], len));
+        rest = &rest[5 + len..];
+    }
+    let markers: Vec<u8> = records.iter().map(|&(marker, _)| marker).collect();
+    assert_eq!(markers, b"><<");
+    assert!(records[2].1 > records[1].1, "records: {records:?}");
+}
+
+#[test]
+#[ignore]
+fn streaming_output_call_echo_request() {
+    let r = run(&[
+        "-plaintext",
+        "-echo-request",
+        "-format",
+        "ndjson",
+        "-d",
+        r#"{"responseParameters":[{"size":3},{"size":5}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    let lines: Vec<&str> = r.stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "stdout: {}", r.stdout);
+    assert!(
+        lines[0].starts_with(">> {\"responseParameters\""),
+        "{}",
+        lines[0]
+    );
+    assert!(lines[1].contains("payload") && lines[2].contains("payload"));
+}
+
+#[test]
+#[ignore]
+fn bidi_stream_echo_request() {
+    let r = run(&[
+        "-plaintext",
+        "-echo-request",
+        "-d",
+        r#"{"responseParameters":[{"size":3}]} {"responseParameters":[{"size":2}]}"#,
+        &SERVER.addr,
+        "testing.TestService/FullDuplexCall",
+    ]);
+    assert_exit_code(&r, 0);
+    let echoed = r.stdout.lines().filter(|l| *l == ">> {").count();
+    assert_eq!(echoed, 2, "stdout: {}", r.stdout);
+    assert_stdout_contains(&r, ">>   \"responseParameters\": [");
+}
@@ -3,7 +3,7 @@ mod common;
 use std::sync::LazyLock;
 
 use common::server::TestServer;
-use common::{assert_exit_code, assert_output_contains, assert_stdout_contains, run};
+use common::{assert_exit_code, assert_output_contains, assert_stdout_contains, run, run_with_env};
 
 static SERVER: LazyLock<TestServer> = LazyLock::new(TestServer::start);
 
@@ -15,6 +15,43 @@ fn empty_call() {
     assert_stdout_contains(&r, "{}");
 }
 
+#[test]
+#[ignore]
+fn no_default_request_requires_data() {
+    let r = run(&[
+        "-plaintext",
+        "-no-default-request",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "no request data provided");
+
+    let r = run(&[
+        "-plaintext",
+        "-no-default-request",
+        "-format",
+        "text",
+        "-d",
+        "",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "no request data provided");
+
+    // An explicit empty message is still sent
+    let r = run(&[
+        "-plaintext",
+        "-no-default-request",
+        "-d",
+        "{}",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 0);
+}
+
 #[test]
 #[ignore]
 fn unary_call_with_payload() {
@@ -69,3 +106,183 @@ fn invoke_with_custom_header() {
     assert_exit_code(&r, 0);
     assert_output_contains(&r, "x-custom: test-value");
 }
+
+/// A stale copy of the test service whose `SimpleResponse` lacks the
+/// `payload` (1) and `username` (2) fields the server sends.
+const STALE_PROTO: &str = r#"
+syntax = "proto3";
+package testing;
+message Payload { bytes body = 2; }
+message SimpleRequest { Payload payload = 3; }
+message SimpleResponse { string oauth_scope = 3; }
+service TestService { rpc UnaryCall(SimpleRequest) returns (SimpleResponse); }
+"#;
+
+#[test]
+#[ignore]
+fn warn_unknown_response_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("stale.proto"), STALE_PROTO).unwrap();
+    let import_path = dir.path().to_str().unwrap();
+    let args = [
+        "-plaintext",
+        "-import-path",
+        import_path,
+        "-proto",
+        "stale.proto",
+        "-d",
+        r#"{"payload":{"body":"dGVzdA=="}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ];
+
+    // Unknown fields are dropped silently by default
+    let r = run(&args);
+    assert_exit_code(&r, 0);
+    assert!(!r.stderr.contains("unknown fields"), "stderr: {}", r.stderr);
+
+    let mut warn_args = vec!["-warn-unknown-response-fields"];
+    warn_args.extend_from_slice(&args);
+    let r = run(&warn_args);
+    assert_exit_code(&r, 0);
+    assert!(
+        r.stderr.contains(
+            "response message 1 (testing.SimpleResponse) has unknown fields with tag numbers: 1"
+        ),
+        "stderr: {}",
+        r.stderr
+    );
+}
+
+#[test]
+#[ignore]
+fn text_legacy_brackets() {
+    let r = run(&[
+        "-plaintext",
+        "-format",
+        "text",
+        "-text-legacy-brackets",
+        "-d",
+        r#"payload: {body: "test"}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "payload: <");
+    assert!(!r.stdout.contains('{'), "stdout: {}", r.stdout);
+}
+
+#[test]
+#[ignore]
+fn authority_override_still_reaches_server() {
+    // The :authority header changes but the connection still goes to the address
+    let r = run(&[
+        "-plaintext",
+        "-authority",
+        "tenant-a.internal",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "{}");
+}
+
+#[test]
+#[ignore]
+fn address_from_env_and_flag() {
+    let method = "testing.TestService/EmptyCall";
+    let r = run_with_env(
+        &["-plaintext", method],
+        &[("GRPCURL_ADDRESS", &SERVER.addr)],
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "{}");
+
+    let r = run(&["-plaintext", "-address", &SERVER.addr, method]);
+    assert_exit_code(&r, 0);
+
+    let r = run_with_env(
+        &["-plaintext", "list"],
+        &[("GRPCURL_ADDRESS", &SERVER.addr)],
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "testing.TestService");
+}
+
+#[test]
+#[ignore]
+fn positional_address_overrides_env() {
+    let r = run_with_env(
+        &["-plaintext", &SERVER.addr, "testing.TestService/EmptyCall"],
+        &[("GRPCURL_ADDRESS", "127.0.0.1:1")],
+    );
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "{}");
+}
+
+#[test]
+#[ignore]
+fn accept_encoding_lists() {
+    for encodings in ["gzip,deflate", "deflate", "identity"] {
+        let r = run(&[
+            "-plaintext",
+            "-accept-encoding",
+            encodings,
+            &SERVER.addr,
+            "testing.TestService/EmptyCall",
+        ]);
+        assert_exit_code(&r, 0);
+        assert_stdout_contains(&r, "{}");
+    }
+}
+
+#[test]
+#[ignore]
+fn json_indent_options() {
+    let call = |indent_args: &[&str]| {
+        let mut args = vec!["-plaintext", "-d", r#"{"payload":{"body":"dGVzdA=="}}"#];
+        args.extend_from_slice(indent_args);
+        args.extend([SERVER.addr.as_str(), "testing.TestService/UnaryCall"]);
+        run(&args)
+    };
+
+    let r = call(&["-indent", "4"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\n    \"payload\": {\n        \"");
+
+    let r = call(&["-indent-tabs"]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, "\n\t\"payload\": {\n\t\t\"");
+}
+
+#[test]
+#[ignore]
+fn response_fields_projects_response() {
+    let r = run(&[
+        "-plaintext",
+        "-response-fields",
+        "payload.type",
+        "-d",
+        r#"{"payload":{"type":"RANDOM","body":"dGVzdA=="}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_stdout_contains(&r, r#""type": "RANDOM""#);
+    assert!(!r.stdout.contains("dGVzdA=="), "stdout: {}", r.stdout);
+}
+
+#[test]
+#[ignore]
+fn rpc_path_overrides_method_path() {
+    // The server only routes standard paths, so a custom one is unimplemented
+    let r = run(&[
+        "-plaintext",
+        "-rpc-path",
+        "/gateway/EmptyCall",
+        &SERVER.addr,
+        "testing.TestService/EmptyCall",
+    ]);
+    assert_exit_code(&r, 76);
+    assert_output_contains(&r, "Unimplemented");
+}
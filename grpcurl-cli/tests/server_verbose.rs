@@ -48,6 +48,109 @@ fn empty_call_very_verbose() {
     assert_output_contains(&r, "Resolved method descriptor");
 }
 
+#[test]
+#[ignore]
+fn very_verbose_stream_timing() {
+    let r = run(&[
+        "--vv",
+        "-plaintext",
+        "-d",
+        r#"{"response_parameters": [{"size": 1}, {"size": 2}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    assert!(r
+        .stderr
+        .contains("Timing: response headers received after "));
+    assert!(r.stderr.contains("Timing: response 1 received after "));
+    assert!(r.stderr.contains("Timing: response 2 received after "));
+    assert!(r.stderr.contains("Timing: RPC completed in "));
+}
+
+#[test]
+#[ignore]
+fn very_verbose_compressed_response_size() {
+    // The test server gzips ComplexService responses when the client accepts it
+    let data = r#"{"string_field": "hello hello hello hello hello hello"}"#;
+    let r = run(&[
+        "--vv",
+        "-plaintext",
+        "-d",
+        data,
+        &SERVER.addr,
+        "testing.ComplexService/GetComplex",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "Estimated response size: 37 bytes");
+    assert_output_contains(&r, "Compressed response size: 30 bytes (gzip)");
+
+    let r = run(&[
+        "--vv",
+        "-plaintext",
+        "--accept-encoding",
+        "identity",
+        "-d",
+        data,
+        &SERVER.addr,
+        "testing.ComplexService/GetComplex",
+    ]);
+    assert_exit_code(&r, 0);
+    assert_output_contains(&r, "Estimated response size: 37 bytes");
+    assert!(!r.stdout.contains("Compressed response size"));
+}
+
+#[test]
+#[ignore]
+fn summary_json_reports_counts_and_code() {
+    let r = run(&[
+        "-summary-json",
+        "-plaintext",
+        "-d",
+        r#"{"response_parameters": [{"size": 1}, {"size": 2}]}"#,
+        &SERVER.addr,
+        "testing.TestService/StreamingOutputCall",
+    ]);
+    assert_exit_code(&r, 0);
+    let line = r.stderr.lines().last().unwrap();
+    let summary: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(summary["requests"], 1);
+    assert_eq!(summary["responses"], 2);
+    assert_eq!(summary["code"], "OK");
+    assert!(summary["durationMs"].as_f64().unwrap() > 0.0);
+
+    let r = run(&[
+        "-summary-json",
+        "-plaintext",
+        "-d",
+        r#"{"response_status": {"code": 5}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 69);
+    assert_output_contains(&r, r#""responses":0,"code":"NotFound""#);
+}
+
+#[test]
+#[ignore]
+fn summary_json_printed_when_invoke_fails() {
+    let r = run(&[
+        "-summary-json",
+        "-plaintext",
+        "-d",
+        r#"{"no_such_field": 1}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 1);
+    assert_output_contains(&r, "Error invoking method");
+    let line = r.stderr.lines().last().unwrap();
+    let summary: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(summary["requests"], 0);
+    assert_eq!(summary["responses"], 0);
+    assert_eq!(summary["code"], "Unknown");
+}
+
 #[test]
 #[ignore]
 fn server_streaming_verbose() {
@@ -92,3 +195,51 @@ fn verbose_summary_counts() {
     // Verbose output should contain request/response headers info
     assert_output_contains(&r, "Resolved method descriptor");
 }
+
+#[test]
+#[ignore]
+fn verbose_summary_status_and_elapsed() {
+    let r = run(&[
+        "-v",
+        "-plaintext",
+        "-d",
+        r#"{"responseStatus":{"code":5,"message":"missing"}}"#,
+        &SERVER.addr,
+        "testing.TestService/UnaryCall",
+    ]);
+    assert_exit_code(&r, 64 + 5);
+    assert_output_contains(&r, "Sent 1 request and received 0 responses");
+    assert_output_contains(&r, "Finished with status NotFound in ");
+}
+
+#[test]
+#[ignore]
+fn list_verbose_reports_reflection_version() {
+    let r = run(&["-plaintext", "-v", &SERVER.addr, "list"]);
+    assert_exit_code(&r, 0);
+    assert!(
+        r.stderr.contains("Using server reflection API v1\n"),
+        "stderr: {}",
+        r.stderr
+    );
+
+    let r = run(&[
+        "-plaintext",
+        "-v",
+        "-reflect-version",
+        "v1alpha",
+        &SERVER.addr,
+        "describe",
+        "testing.TestService",
+    ]);
+    assert_exit_code(&r, 0);
+    assert!(
+        r.stderr.contains("Using server reflection API v1alpha"),
+        "stderr: {}",
+        r.stderr
+    );
+
+    // Only reported in verbose mode
+    let r = run(&["-plaintext", &SERVER.addr, "list"]);
+    assert!(!r.stderr.contains("Using server reflection"));
+}
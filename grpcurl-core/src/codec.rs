@@ -1,16 +1,264 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use prost::bytes::{Buf, BufMut, Bytes};
 use prost::Message;
 use prost_reflect::{DynamicMessage, MessageDescriptor};
+use tonic::client::GrpcService;
 use tonic::codec::{BufferSettings, Codec, Decoder, Encoder};
+use tonic::codegen::StdError;
 use tonic::Status;
 
+use crate::error::{GrpcurlError, Result};
+
+/// Marker byte for a request message in a wire dump.
+pub const DUMP_REQUEST: u8 = b'>';
+
+/// Marker byte for a response message in a wire dump.
+pub const DUMP_RESPONSE: u8 = b'<';
+
+/// A file that receives the encoded bytes of every message sent and received.
+///
+/// Each message is written as a record of a direction marker byte
+/// ([`DUMP_REQUEST`] or [`DUMP_RESPONSE`]), a 4-byte big-endian length, and
+/// the protobuf-encoded message, in the order the codec saw them. Clones share
+/// the same file.
+#[derive(Clone)]
+pub struct WireDump(Arc<Mutex<File>>);
+
+impl WireDump {
+    /// Create (or truncate) the dump file at `path`.
+    pub fn create(path: &str) -> Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            GrpcurlError::Io(std::io::Error::new(
+                e.kind(),
+                format!("failed to create wire dump file '{path}': {e}"),
+            ))
+        })?;
+        Ok(WireDump(Arc::new(Mutex::new(file))))
+    }
+
+    fn record(&self, marker: u8, bytes: &[u8]) -> std::result::Result<(), Status> {
+        let mut record = Vec::with_capacity(5 + bytes.len());
+        record.push(marker);
+        record.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        record.extend_from_slice(bytes);
+        let mut file = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(&record)
+            .map_err(|e| Status::internal(format!("failed to write wire dump: {e}")))
+    }
+}
+
+/// The gRPC frame of one response message, as it arrived on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireFrame {
+    /// Whether the message was compressed, per the frame's compression flag.
+    pub compressed: bool,
+    /// Length of the message as sent, after any compression.
+    pub len: usize,
+}
+
+/// Response frames seen by a [`RecordResponseFrames`] transport, in arrival
+/// order, along with the `grpc-encoding` the server declared.
+///
+/// tonic decompresses messages before the codec sees them, so this is the
+/// only place their compressed size is known. Clones share the same record.
+#[derive(Clone, Default)]
+pub struct ResponseFrames(Arc<Mutex<ResponseFramesInner>>);
+
+#[derive(Default)]
+struct ResponseFramesInner {
+    encoding: Option<String>,
+    frames: VecDeque<WireFrame>,
+}
+
+impl ResponseFrames {
+    /// Take the frame of the next response message not yet taken.
+    pub fn next_frame(&self) -> Option<WireFrame> {
+        self.lock().frames.pop_front()
+    }
+
+    /// The `grpc-encoding` response header, if the server sent one.
+    pub fn encoding(&self) -> Option<String> {
+        self.lock().encoding.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ResponseFramesInner> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A transport that records the gRPC frames of response bodies into
+/// [`ResponseFrames`]. With no record to fill, responses pass through as-is.
+#[derive(Clone)]
+pub struct RecordResponseFrames<T> {
+    inner: T,
+    frames: Option<ResponseFrames>,
+}
+
+impl<T> RecordResponseFrames<T> {
+    pub fn new(inner: T, frames: Option<ResponseFrames>) -> Self {
+        RecordResponseFrames { inner, frames }
+    }
+}
+
+impl<T, ReqBody> tower::Service<http::Request<ReqBody>> for RecordResponseFrames<T>
+where
+    T: GrpcService<ReqBody>,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError>,
+{
+    type Response = http::Response<FrameRecordingBody<T::ResponseBody>>;
+    type Error = T::Error;
+    type Future = RecordResponseFramesFuture<T::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        RecordResponseFramesFuture {
+            inner: Box::pin(self.inner.call(request)),
+            frames: self.frames.clone(),
+        }
+    }
+}
+
+/// Response future of [`RecordResponseFrames`].
+pub struct RecordResponseFramesFuture<F> {
+    inner: Pin<Box<F>>,
+    frames: Option<ResponseFrames>,
+}
+
+impl<F, B, E> Future for RecordResponseFramesFuture<F>
+where
+    F: Future<Output = std::result::Result<http::Response<B>, E>>,
+{
+    type Output = std::result::Result<http::Response<FrameRecordingBody<B>>, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let response = ready!(self.inner.as_mut().poll(cx))?;
+        if let Some(ref frames) = self.frames {
+            frames.lock().encoding = response
+                .headers()
+                .get("grpc-encoding")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+        }
+        let frames = self.frames.clone();
+        Poll::Ready(Ok(response.map(|body| FrameRecordingBody {
+            inner: Box::pin(body),
+            frames,
+            parser: FrameParser::default(),
+        })))
+    }
+}
+
+/// Response body of [`RecordResponseFrames`]: the data is passed through
+/// unchanged while frame headers are picked out of it.
+pub struct FrameRecordingBody<B> {
+    inner: Pin<Box<B>>,
+    frames: Option<ResponseFrames>,
+    parser: FrameParser,
+}
+
+impl<B: HttpBody<Data = Bytes>> HttpBody for FrameRecordingBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Bytes>, B::Error>>> {
+        let this = &mut *self;
+        let frame = ready!(this.inner.as_mut().poll_frame(cx));
+        if let (Some(Ok(frame)), Some(frames)) = (&frame, &this.frames) {
+            if let Some(data) = frame.data_ref() {
+                this.parser.feed(data, |wire_frame| {
+                    frames.lock().frames.push_back(wire_frame)
+                });
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Finds gRPC frame headers (a flags byte and a 4-byte big-endian length) in
+/// body data that may split them across chunks.
+#[derive(Default)]
+struct FrameParser {
+    header: [u8; 5],
+    header_len: usize,
+    /// Message bytes of the current frame still to skip.
+    remaining: usize,
+}
+
+impl FrameParser {
+    /// Flag bit of a grpc-web trailers frame, which carries no message.
+    const TRAILERS_FLAG: u8 = 0x80;
+
+    fn feed(&mut self, mut data: &[u8], mut on_frame: impl FnMut(WireFrame)) {
+        while !data.is_empty() {
+            if self.remaining > 0 {
+                let n = self.remaining.min(data.len());
+                self.remaining -= n;
+                data = &data[n..];
+                continue;
+            }
+            let n = (self.header.len() - self.header_len).min(data.len());
+            self.header[self.header_len..self.header_len + n].copy_from_slice(&data[..n]);
+            self.header_len += n;
+            data = &data[n..];
+            if self.header_len < self.header.len() {
+                break;
+            }
+            self.header_len = 0;
+            let flags = self.header[0];
+            let len = u32::from_be_bytes([
+                self.header[1],
+                self.header[2],
+                self.header[3],
+                self.header[4],
+            ]) as usize;
+            self.remaining = len;
+            if flags & Self::TRAILERS_FLAG == 0 {
+                on_frame(WireFrame {
+                    compressed: flags & 1 == 1,
+                    len,
+                });
+            }
+        }
+    }
+}
+
 /// A gRPC codec for prost-reflect DynamicMessage.
 ///
 /// Unlike tonic's ProstCodec which works with compile-time generated types,
 /// this codec works with runtime-resolved message descriptors, enabling
 /// dynamic RPC invocation without pre-compiled service stubs.
+///
+/// The codec only deals in uncompressed message bodies. tonic owns the gRPC
+/// framing: it writes the compression flag and length prefix, compresses
+/// outgoing messages when `send_compressed` is set, and decompresses incoming
+/// ones before they reach the decoder. The same codec therefore works with or
+/// without compression.
 pub struct DynamicCodec {
     request_desc: MessageDescriptor,
     response_desc: MessageDescriptor,
+    dump: Option<WireDump>,
 }
 
 impl DynamicCodec {
@@ -18,8 +266,15 @@ impl DynamicCodec {
         DynamicCodec {
             request_desc,
             response_desc,
+            dump: None,
         }
     }
+
+    /// Record the encoded bytes of every message to `dump`, if given.
+    pub fn with_dump(mut self, dump: Option<WireDump>) -> Self {
+        self.dump = dump;
+        self
+    }
 }
 
 impl Codec for DynamicCodec {
@@ -31,19 +286,28 @@ impl Codec for DynamicCodec {
     fn encoder(&mut self) -> Self::Encoder {
         DynamicEncoder {
             _request_desc: self.request_desc.clone(),
+            dump: self.dump.clone(),
         }
     }
 
     fn decoder(&mut self) -> Self::Decoder {
         DynamicDecoder {
             response_desc: self.response_desc.clone(),
+            dump: self.dump.clone(),
         }
     }
 }
 
 /// Encodes DynamicMessage into protobuf wire format.
+///
+/// tonic reuses one buffer per call for every message on it, starting at the
+/// `BufferSettings` size and growing as needed. `encode` appends a single
+/// message body after the space tonic has reserved for the frame header; the
+/// length tonic puts in that header is the number of bytes written here (or
+/// their compressed size).
 pub struct DynamicEncoder {
     _request_desc: MessageDescriptor,
+    dump: Option<WireDump>,
 }
 
 impl Encoder for DynamicEncoder {
@@ -54,7 +318,14 @@ impl Encoder for DynamicEncoder {
         &mut self,
         item: Self::Item,
         dst: &mut tonic::codec::EncodeBuf<'_>,
-    ) -> Result<(), Self::Error> {
+    ) -> std::result::Result<(), Self::Error> {
+        if let Some(ref dump) = self.dump {
+            // Encode once and copy, so the dump matches the sent bytes exactly
+            let bytes = item.encode_to_vec();
+            dump.record(DUMP_REQUEST, &bytes)?;
+            dst.put_slice(&bytes);
+            return Ok(());
+        }
         item.encode(dst)
             .map_err(|e| Status::internal(format!("failed to encode request: {e}")))?;
         Ok(())
@@ -66,8 +337,14 @@ impl Encoder for DynamicEncoder {
 }
 
 /// Decodes protobuf wire format into DynamicMessage.
+///
+/// `src` holds exactly one message body, already decompressed and with the
+/// frame header stripped. It borrows tonic's reusable receive buffer, so
+/// decoding copies what it keeps into the message instead of holding on to
+/// the buffer.
 pub struct DynamicDecoder {
     response_desc: MessageDescriptor,
+    dump: Option<WireDump>,
 }
 
 impl Decoder for DynamicDecoder {
@@ -77,9 +354,16 @@ impl Decoder for DynamicDecoder {
     fn decode(
         &mut self,
         src: &mut tonic::codec::DecodeBuf<'_>,
-    ) -> Result<Option<Self::Item>, Self::Error> {
-        let msg = DynamicMessage::decode(self.response_desc.clone(), src)
-            .map_err(|e| Status::internal(format!("failed to decode response: {e}")))?;
+    ) -> std::result::Result<Option<Self::Item>, Self::Error> {
+        let msg = match self.dump {
+            Some(ref dump) => {
+                let bytes = src.copy_to_bytes(src.remaining());
+                dump.record(DUMP_RESPONSE, &bytes)?;
+                DynamicMessage::decode(self.response_desc.clone(), bytes)
+            }
+            None => DynamicMessage::decode(self.response_desc.clone(), src),
+        }
+        .map_err(|e| Status::internal(format!("failed to decode response: {e}")))?;
         Ok(Some(msg))
     }
 
@@ -87,3 +371,160 @@ impl Decoder for DynamicDecoder {
         BufferSettings::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_reflect::{DescriptorPool, Value};
+    use tonic::codec::{CompressionEncoding, EncodeBody, Streaming};
+    use tonic::codegen::{Body as HttpBody, Bytes};
+
+    fn make_blob_desc() -> MessageDescriptor {
+        let fds = prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("blob.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![prost_types::DescriptorProto {
+                    name: Some("Blob".into()),
+                    field: vec![
+                        prost_types::FieldDescriptorProto {
+                            name: Some("name".into()),
+                            number: Some(1),
+                            r#type: Some(9), // TYPE_STRING
+                            label: Some(1),
+                            json_name: Some("name".into()),
+                            ..Default::default()
+                        },
+                        prost_types::FieldDescriptorProto {
+                            name: Some("chunks".into()),
+                            number: Some(2),
+                            r#type: Some(12), // TYPE_BYTES
+                            label: Some(3),   // LABEL_REPEATED
+                            json_name: Some("chunks".into()),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".into()),
+                ..Default::default()
+            }],
+        };
+        DescriptorPool::from_file_descriptor_set(fds)
+            .unwrap()
+            .get_message_by_name("test.v1.Blob")
+            .unwrap()
+    }
+
+    /// A message of about 1 MiB, much larger than tonic's 8 KiB buffer.
+    fn make_large_blob(desc: &MessageDescriptor) -> DynamicMessage {
+        let chunks = (0..64u8)
+            .map(|i| Value::Bytes(Bytes::from(vec![i; 16 * 1024])))
+            .collect();
+        let mut msg = DynamicMessage::new(desc.clone());
+        msg.set_field_by_name("name", Value::String("large".into()));
+        msg.set_field_by_name("chunks", Value::List(chunks));
+        msg
+    }
+
+    fn encode_body(
+        desc: &MessageDescriptor,
+        msg: DynamicMessage,
+        encoding: Option<CompressionEncoding>,
+    ) -> impl HttpBody<Data = Bytes, Error = Status> + Send + 'static {
+        let mut codec = DynamicCodec::new(desc.clone(), desc.clone());
+        let source = tokio_stream::iter([Ok(msg)]);
+        EncodeBody::new_client(codec.encoder(), source, encoding, None)
+    }
+
+    /// Collect the framed bytes tonic would send for `msg`.
+    async fn encode_frames(
+        desc: &MessageDescriptor,
+        msg: DynamicMessage,
+        encoding: Option<CompressionEncoding>,
+    ) -> Vec<u8> {
+        let mut body = std::pin::pin!(encode_body(desc, msg, encoding));
+        let mut out = Vec::new();
+        while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+            if let Ok(data) = frame.unwrap().into_data() {
+                out.extend_from_slice(&data);
+            }
+        }
+        out
+    }
+
+    async fn round_trip(
+        desc: &MessageDescriptor,
+        msg: DynamicMessage,
+        encoding: Option<CompressionEncoding>,
+    ) -> DynamicMessage {
+        let mut codec = DynamicCodec::new(desc.clone(), desc.clone());
+        let body = encode_body(desc, msg, encoding);
+        let mut stream = Streaming::new_request(codec.decoder(), body, encoding, None);
+        let decoded = stream.message().await.unwrap().unwrap();
+        assert!(stream.message().await.unwrap().is_none());
+        decoded
+    }
+
+    #[tokio::test]
+    async fn large_message_round_trip() {
+        let desc = make_blob_desc();
+        let msg = make_large_blob(&desc);
+        assert_eq!(round_trip(&desc, msg.clone(), None).await, msg);
+    }
+
+    #[tokio::test]
+    async fn large_message_round_trip_compressed() {
+        let desc = make_blob_desc();
+        let msg = make_large_blob(&desc);
+        for encoding in [CompressionEncoding::Gzip, CompressionEncoding::Deflate] {
+            assert_eq!(round_trip(&desc, msg.clone(), Some(encoding)).await, msg);
+        }
+    }
+
+    #[tokio::test]
+    async fn frame_length_matches_encoded_size() {
+        let desc = make_blob_desc();
+        let msg = make_large_blob(&desc);
+        let encoded_len = msg.encoded_len();
+
+        let frame = encode_frames(&desc, msg.clone(), None).await;
+        assert_eq!(frame[0], 0, "uncompressed flag");
+        let len = u32::from_be_bytes(frame[1..5].try_into().unwrap()) as usize;
+        assert_eq!(len, encoded_len);
+        assert_eq!(frame.len(), 5 + encoded_len);
+
+        // Compressed frames carry the compressed length, which for this
+        // repetitive payload is far smaller than the message
+        let frame = encode_frames(&desc, msg, Some(CompressionEncoding::Gzip)).await;
+        assert_eq!(frame[0], 1, "compressed flag");
+        let len = u32::from_be_bytes(frame[1..5].try_into().unwrap()) as usize;
+        assert_eq!(frame.len(), 5 + len);
+        assert!(len < encoded_len / 10, "compressed {len} of {encoded_len}");
+    }
+
+    #[tokio::test]
+    async fn frame_parser_reads_headers_split_across_chunks() {
+        let desc = make_blob_desc();
+        let msg = make_large_blob(&desc);
+        let mut data = encode_frames(&desc, msg.clone(), None).await;
+        data.extend(encode_frames(&desc, msg, Some(CompressionEncoding::Gzip)).await);
+        // A grpc-web trailers frame carries no message and is not recorded
+        data.extend([0x80, 0, 0, 0, 2, b'o', b'k']);
+
+        let mut parser = FrameParser::default();
+        let mut frames = Vec::new();
+        for chunk in data.chunks(3) {
+            parser.feed(chunk, |frame| frames.push(frame));
+        }
+
+        assert_eq!(frames.len(), 2);
+        assert!(!frames[0].compressed);
+        assert!(frames[1].compressed);
+        assert_eq!(
+            5 + frames[0].len + 5 + frames[1].len + 7,
+            data.len(),
+            "frame lengths cover the whole body"
+        );
+    }
+}
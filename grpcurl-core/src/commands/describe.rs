@@ -2,44 +2,125 @@ use crate::descriptor::{DescriptorSource, SymbolDescriptor};
 use crate::descriptor_text;
 use crate::format::{self, FormatOptions};
 
+/// Describe `symbol`, or every service when no symbol is given.
+///
+/// Elements are sorted (fields by number, methods by name) unless
+/// `preserve_order` is set, in which case they keep their source order.
+///
+/// Returns how many non-empty results were described: the number of services
+/// without a symbol, or 1 for a symbol unless it is a service with no methods.
 pub async fn run_describe(
     source: &dyn DescriptorSource,
     symbol: Option<&str>,
     format_options: &FormatOptions,
     msg_template: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+    preserve_order: bool,
+) -> Result<usize, Box<dyn std::error::Error>> {
     match symbol {
         Some(sym) => {
             let desc = source.find_symbol(sym).await?;
-            let text = descriptor_text::get_descriptor_text(&desc);
+            let text = symbol_text(&desc, preserve_order);
             println!("{sym} is {}:", desc.type_label());
             println!("{text}");
 
-            // If --msg-template and the symbol is a message, show a JSON template
+            // If --msg-template, show a JSON template for a message, or for
+            // both the request and response messages of a method
             if msg_template {
-                if let SymbolDescriptor::Message(msg_desc) = &desc {
-                    print_msg_template(msg_desc, format_options)?;
+                match &desc {
+                    SymbolDescriptor::Message(msg_desc) => {
+                        print_msg_template("Message template", msg_desc, format_options)?;
+                    }
+                    SymbolDescriptor::Method(method_desc) => {
+                        print_msg_template(
+                            "Request template",
+                            &method_desc.input(),
+                            format_options,
+                        )?;
+                        print_msg_template(
+                            "Response template",
+                            &method_desc.output(),
+                            format_options,
+                        )?;
+                    }
+                    _ => {}
                 }
             }
+            let empty_service = desc
+                .as_service()
+                .is_some_and(|svc| svc.methods().len() == 0);
+            Ok(usize::from(!empty_service))
         }
         None => {
             // Describe all services in declaration order (not sorted).
             let services = source.list_services().await?;
             for service in &services {
                 let desc = source.find_symbol(service).await?;
-                let text = descriptor_text::get_descriptor_text(&desc);
+                let text = symbol_text(&desc, preserve_order);
                 println!("{service} is {}:", desc.type_label());
                 println!("{text}");
             }
+            Ok(services.len())
         }
     }
+}
+
+/// Print a JSON Schema (draft-07) for the JSON accepted as `symbol`: a
+/// message, or the request message of a method.
+pub async fn run_json_schema(
+    source: &dyn DescriptorSource,
+    symbol: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let msg_desc = match source.find_symbol(symbol).await? {
+        SymbolDescriptor::Message(msg_desc) => msg_desc,
+        SymbolDescriptor::Method(method_desc) => method_desc.input(),
+        other => {
+            return Err(format!(
+                "{symbol} is {}, not a message or method",
+                other.type_label()
+            )
+            .into())
+        }
+    };
+    let schema = format::make_json_schema(&msg_desc);
+    println!("{}", serde_json::to_string_pretty(&schema)?);
     Ok(())
 }
 
-/// Print a JSON template for a message type.
+/// Describe every known extension of the message `symbol`, in field number
+/// order.
+///
+/// Returns how many extensions were described.
+pub async fn run_extensions(
+    source: &dyn DescriptorSource,
+    symbol: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let msg_desc = match source.find_symbol(symbol).await? {
+        SymbolDescriptor::Message(msg_desc) => msg_desc,
+        other => return Err(format!("{symbol} is {}, not a message", other.type_label()).into()),
+    };
+    let mut extensions = source.all_extensions_for_type(msg_desc.full_name()).await?;
+    extensions.sort_by_key(|ext| ext.number());
+    for ext in &extensions {
+        let desc = SymbolDescriptor::Extension(ext.clone());
+        println!("{} is {}:", ext.full_name(), desc.type_label());
+        println!("{}", descriptor_text::get_descriptor_text(&desc));
+    }
+    Ok(extensions.len())
+}
+
+fn symbol_text(desc: &SymbolDescriptor, preserve_order: bool) -> String {
+    if preserve_order {
+        descriptor_text::get_descriptor_text_preserving_order(desc)
+    } else {
+        descriptor_text::get_descriptor_text(desc)
+    }
+}
+
+/// Print a JSON template for a message type under the given heading.
 ///
 /// Uses emit_defaults=true to show all fields with their default values.
 fn print_msg_template(
+    heading: &str,
     desc: &prost_reflect::MessageDescriptor,
     _format_options: &FormatOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -48,12 +129,12 @@ fn print_msg_template(
     // Always use emit_defaults=true for templates to show all fields
     let template_options = FormatOptions {
         emit_defaults: true,
-        allow_unknown_fields: false,
+        ..Default::default()
     };
-    let formatter = format::json_formatter(&template_options);
+    let formatter = format::json_formatter(&template_options, None);
     let output = (formatter)(&template)?;
 
-    println!("\nMessage template:");
+    println!("\n{heading}:");
     println!("{output}");
     Ok(())
 }
@@ -1,16 +1,27 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use http::uri::PathAndQuery;
 use prost::Message;
 use prost_reflect::DynamicMessage;
-use tonic::client::Grpc;
+use tonic::client::{Grpc, GrpcService};
+use tonic::codec::CompressionEncoding;
+use tonic::codegen::{Body as HttpBody, Bytes, StdError};
 use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 
-use crate::codec::DynamicCodec;
+use crate::codec::{DynamicCodec, RecordResponseFrames, ResponseFrames, WireDump};
+use crate::connection;
 use crate::descriptor::{self, DescriptorSource, SymbolDescriptor};
 use crate::descriptor_text;
 use crate::error::GrpcurlError;
 use crate::format::{
-    self, Format, FormatOptions, JsonRequestParser, ParseError, RequestParser, TextRequestParser,
+    self, Base64RequestParser, Format, FormatOptions, JsonRequestParser, NdjsonRequestParser,
+    ParseError, RequestParser, TextRequestParser,
 };
 use crate::metadata;
 
@@ -21,21 +32,61 @@ use crate::metadata;
 /// and passes it to `run_invoke()`.
 #[derive(Debug, Clone)]
 pub struct InvokeConfig {
-    /// The format of request/response data ('json' or 'text').
+    /// The format of request/response data ('json', 'text' or 'ndjson').
     pub format: Format,
 
     /// Emit default values for JSON-encoded responses.
     pub emit_defaults: bool,
 
+    /// Write text-format submessages with Go's legacy `<...>` brackets.
+    pub text_legacy_brackets: bool,
+
     /// Allow unknown fields in JSON input.
     pub allow_unknown_fields: bool,
 
+    /// Leave `google.protobuf.Any` fields in JSON responses as their type URL
+    /// and base64-encoded value instead of expanding them.
+    pub no_resolve_any: bool,
+
+    /// Indentation for each level of pretty-printed JSON responses; `None`
+    /// uses two spaces.
+    pub json_indent: Option<String>,
+
+    /// Write set oneofs in JSON responses as `{"which": ..., "value": ...}`
+    /// wrapper objects keyed by the oneof name.
+    pub oneof_wrapped: bool,
+
+    /// Write Duration and Timestamp values in JSON responses for reading
+    /// instead of as proto3 JSON strings.
+    pub humanize_time: bool,
+
+    /// Treat empty text-format input as zero messages for client and bidi
+    /// streaming, instead of one empty message.
+    pub empty_text_stream: bool,
+
+    /// Fail unary and server-streaming calls with no request data instead
+    /// of sending one empty message.
+    pub no_default_request: bool,
+
+    /// Fail client and bidi streaming calls with no request messages instead
+    /// of opening an empty stream.
+    pub require_request: bool,
+
     /// When a non-zero status is returned, format the error using --format.
     pub format_error: bool,
 
-    /// Data for request contents. "@" means read from stdin.
+    /// Data for request contents. "@" or "@-" means read from stdin.
     pub data: Option<String>,
 
+    /// Parse JSON request data as one message per line, rather than as
+    /// whitespace-separated values.
+    pub ndjson_input: bool,
+
+    /// Read request data as whitespace-separated base64 blobs, each the
+    /// binary encoding of a message of this fully-qualified type, instead of
+    /// parsing it in `format`.
+    pub request_type: Option<String>,
+
     /// Additional headers in 'name: value' format (sent with all requests).
     pub headers: Vec<String>,
 
@@ -45,9 +96,37 @@ pub struct InvokeConfig {
     /// If set, headers may use '${NAME}' syntax to reference env variables.
     pub expand_headers: bool,
 
-    /// Maximum encoded size of a response message, in bytes.
+    /// Header to send a freshly generated request ID (a random UUID) in, for
+    /// correlating the call with server logs. Streams share one ID.
+    pub request_id_header: Option<String>,
+
+    /// If set, request data may use '${NAME}' syntax to reference env
+    /// variables. '$${NAME}' produces a literal '${NAME}'.
+    pub expand_data: bool,
+
+    /// Maximum encoded size of a request or response message, in bytes.
     pub max_msg_sz: Option<i32>,
 
+    /// Maximum total encoded size of all messages received on a response
+    /// stream, in bytes. The call is aborted once the budget is exceeded.
+    pub max_recv_total: Option<u64>,
+
+    /// If set, keep retrying a channel that is not ready yet with backoff for
+    /// up to this long before sending the RPC.
+    pub wait_for_ready: Option<Duration>,
+
+    /// Delays between the `wait_for_ready` retries.
+    pub retry_backoff: connection::BackoffPolicy,
+
+    /// When the operation's time budget runs out. `wait_for_ready` retries
+    /// stop at it, and the time left when the RPC is sent goes to the server
+    /// in the `grpc-timeout` header. Enforcing a timeout on the client is
+    /// separate, via `ConnectionConfig::max_time`.
+    pub deadline: Option<Instant>,
+
+    /// Do not send `deadline` to the server in the `grpc-timeout` header.
+    pub no_deadline_header: bool,
+
     /// Verbosity level: 0 = default, 1 = verbose, 2 = very verbose.
     pub verbosity: u8,
 
@@ -56,6 +135,101 @@ pub struct InvokeConfig {
 
     /// Directory to write generated .proto files to.
     pub proto_out_dir: Option<String>,
+
+    /// Leave comments out of the files written to `proto_out_dir`.
+    pub strip_comments: bool,
+
+    /// How deep a chain of imports is followed when writing `protoset_out`
+    /// or `proto_out_dir`.
+    pub max_depth: usize,
+
+    /// Use the grpc-web protocol instead of native gRPC for the RPC.
+    pub grpc_web: bool,
+
+    /// Print the requests that would be sent without invoking the RPC.
+    pub dry_run: bool,
+
+    /// Print each request message, with every line prefixed `>> `, as it is
+    /// sent, so streamed requests can be matched up with their responses.
+    pub echo_request: bool,
+
+    /// Report fields in responses that are not in the local descriptor.
+    pub warn_unknown_response_fields: bool,
+
+    /// Count server and bidi streaming responses instead of formatting and
+    /// printing them; the total is printed once the stream ends.
+    pub count_only: bool,
+
+    /// Directory to write each server and bidi streaming response to, one
+    /// numbered file per message (`response-0001.json`, ...), instead of
+    /// printing it. Created if it does not exist.
+    pub output_dir: Option<String>,
+
+    /// Compression encodings accepted for responses.
+    pub accept_encoding: AcceptEncoding,
+
+    /// Field paths to keep in each response, FieldMask style (`a.b`); all
+    /// other fields are cleared before formatting. Empty keeps everything.
+    pub response_fields: Vec<String>,
+
+    /// HTTP/2 path to send the RPC to instead of `/package.Service/Method`,
+    /// for gateways that route on a custom path. The resolved method still
+    /// determines how messages are encoded.
+    pub rpc_path: Option<String>,
+
+    /// File to write the encoded bytes of each request and response message
+    /// to, as length-prefixed records with a direction marker.
+    pub dump_wire: Option<String>,
+}
+
+/// The response compression encodings a client advertises in
+/// `grpc-accept-encoding`.
+///
+/// Parsed from a comma-separated list such as `gzip,deflate`. `identity`
+/// (no compression) is always accepted and may be given alone to turn off
+/// compressed responses. Defaults to gzip, like Go grpcurl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptEncoding(Vec<CompressionEncoding>);
+
+impl AcceptEncoding {
+    /// Encoding names accepted by `from_str`.
+    pub const SUPPORTED: &'static [&'static str] = &["gzip", "deflate", "identity"];
+
+    /// The compressed encodings to accept, in the order given.
+    pub fn encodings(&self) -> &[CompressionEncoding] {
+        &self.0
+    }
+}
+
+impl Default for AcceptEncoding {
+    fn default() -> Self {
+        AcceptEncoding(vec![CompressionEncoding::Gzip])
+    }
+}
+
+impl FromStr for AcceptEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut encodings = Vec::new();
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let encoding = match name.to_ascii_lowercase().as_str() {
+                "gzip" => CompressionEncoding::Gzip,
+                "deflate" => CompressionEncoding::Deflate,
+                "identity" => continue,
+                _ => {
+                    return Err(format!(
+                        "Unsupported encoding '{name}'; supported encodings are {}.",
+                        Self::SUPPORTED.join(", ")
+                    ))
+                }
+            };
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding);
+            }
+        }
+        Ok(AcceptEncoding(encodings))
+    }
 }
 
 /// Callback trait for RPC invocation events.
@@ -154,15 +328,44 @@ impl InvocationEventHandler for DefaultEventHandler {
 
 /// Common context for all RPC invocation types, grouping parameters
 /// shared by unary, server-streaming, client-streaming, and bidi calls.
-struct InvokeContext<'a> {
-    client: &'a mut Grpc<Channel>,
+struct InvokeContext<'a, T> {
+    client: &'a mut Grpc<T>,
     parser: &'a mut RequestParser,
     request_desc: &'a prost_reflect::MessageDescriptor,
     response_desc: &'a prost_reflect::MessageDescriptor,
     path: PathAndQuery,
     formatter: &'a format::Formatter,
+    /// Set with `--echo-request`; formats requests as they are sent.
+    echo_formatter: Option<&'a format::Formatter>,
     request_metadata: &'a MetadataMap,
     verbosity: u8,
+    warn_unknown_fields: bool,
+    max_recv_total: Option<u64>,
+    empty_text_stream: bool,
+    no_default_request: bool,
+    require_request: bool,
+    wait_for_ready: Option<Duration>,
+    retry_backoff: connection::BackoffPolicy,
+    deadline: Option<Instant>,
+    /// `deadline`, unless `--no-deadline-header` keeps it from the server.
+    header_deadline: Option<Instant>,
+    count_only: bool,
+    /// Set with `--output-dir`; where responses are written.
+    response_dir: Option<ResponseDir>,
+    wire_dump: Option<WireDump>,
+    /// Set when bidi requests are read from a terminal line by line.
+    typed_requests: Option<TypedRequests>,
+    /// Set at `-vv`; frames of responses as received, for compressed sizes.
+    response_frames: Option<ResponseFrames>,
+}
+
+/// The formatters a call prints messages with.
+struct Formatters<'a> {
+    response: &'a format::Formatter,
+    /// Set with `--echo-request`.
+    echo_request: Option<&'a format::Formatter>,
+    /// Set with `--output-dir`; formats each response file.
+    response_file: Option<format::Formatter>,
 }
 
 /// Result of an RPC invocation, carrying status and count information
@@ -170,10 +373,38 @@ struct InvokeContext<'a> {
 pub struct InvokeResult {
     /// The gRPC status from the response (None if the call failed before getting a status).
     pub status: Option<tonic::Status>,
+    /// The final gRPC status code of the call.
+    pub code: tonic::Code,
     /// Number of request messages sent.
     pub num_requests: usize,
     /// Number of response messages received.
     pub num_responses: usize,
+    /// The response trailers. For unary and client-streaming calls tonic
+    /// merges them with the response headers.
+    pub trailers: MetadataMap,
+    /// Wall-clock time spent on the RPC itself, from just before it was sent
+    /// until the final status was received. Method resolution and reflection
+    /// are not included.
+    pub elapsed: Duration,
+}
+
+impl InvokeResult {
+    fn new(status: tonic::Status, num_requests: usize, num_responses: usize) -> Self {
+        InvokeResult {
+            code: status.code(),
+            // An error status carries the trailers it was received with
+            trailers: status.metadata().clone(),
+            status: Some(status),
+            num_requests,
+            num_responses,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn with_trailers(mut self, trailers: MetadataMap) -> Self {
+        self.trailers = trailers;
+        self
+    }
 }
 
 pub async fn run_invoke(
@@ -189,10 +420,28 @@ pub async fn run_invoke(
 
     // Export protoset/protos if requested (before RPC, matching Go)
     if let Some(ref protoset_out) = config.protoset_out {
-        descriptor::write_protoset(protoset_out, source, &[symbol.to_string()]).await?;
+        descriptor::write_protoset(
+            protoset_out,
+            source,
+            &[symbol.to_string()],
+            config.max_depth,
+        )
+        .await?;
     }
     if let Some(ref proto_out_dir) = config.proto_out_dir {
-        descriptor::write_proto_files(proto_out_dir, source, &[symbol.to_string()]).await?;
+        let symbols = [symbol.to_string()];
+        if config.strip_comments {
+            descriptor::write_proto_files_without_comments(
+                proto_out_dir,
+                source,
+                &symbols,
+                config.max_depth,
+            )
+            .await?;
+        } else {
+            descriptor::write_proto_files(proto_out_dir, source, &symbols, config.max_depth)
+                .await?;
+        }
     }
 
     // Verbose: print resolved method descriptor (Go sends to stdout)
@@ -202,27 +451,63 @@ pub async fn run_invoke(
         print!("\nResolved method descriptor:\n{txt}\n");
     }
 
-    let request_desc = method_desc.input();
-    let response_desc = method_desc.output();
-
     // Build format options from config
     let format_options = FormatOptions {
         emit_defaults: config.emit_defaults,
         allow_unknown_fields: config.allow_unknown_fields,
+        raw_any: config.no_resolve_any,
+        json_indent: config.json_indent.clone(),
+        oneof_wrapped: config.oneof_wrapped,
+        humanize_time: config.humanize_time,
     };
 
-    // Parse request data and create response formatter based on --format flag
-    let mut parser = match config.format {
-        Format::Json => RequestParser::Json(JsonRequestParser::new(
-            config.data.as_deref(),
-            &format_options,
+    // Expand env var references in the request data before it is parsed.
+    // Data from stdin is read up front so it is expanded too, unless requests
+    // are typed interactively, in which case the bidi call reads stdin itself.
+    let data = match config.data.as_deref() {
+        _ if reads_typed_requests(config, &method_desc) => None,
+        Some(data) if config.expand_data => Some(metadata::expand_data(
+            &format::read_request_data(Some(data))?,
         )?),
-        Format::Text => RequestParser::Text(TextRequestParser::new(config.data.as_deref())?),
+        data => data.map(str::to_string),
+    };
+
+    // Parse request data and create response formatter based on --format
+    // flag; base64 requests of a --request-type ignore the format
+    let request_type = match config.request_type.as_deref() {
+        Some(type_name) => Some(resolve_request_type(source, type_name).await?),
+        None => None,
+    };
+    let mut parser = match (request_type, config.format) {
+        (Some(request_type), _) => {
+            RequestParser::Base64(Base64RequestParser::new(data.as_deref(), request_type)?)
+        }
+        (None, Format::Json | Format::Ndjson) if config.ndjson_input => {
+            RequestParser::Ndjson(NdjsonRequestParser::new(data.as_deref(), &format_options)?)
+        }
+        (None, Format::Json | Format::Ndjson) => {
+            RequestParser::Json(JsonRequestParser::new(data.as_deref(), &format_options)?)
+        }
+        (None, Format::Text) => {
+            RequestParser::Text(TextRequestParser::new(data.as_deref(), &format_options)?)
+        }
     };
 
-    let formatter = match config.format {
-        Format::Json => format::json_formatter(&format_options),
-        Format::Text => format::text_formatter(config.verbosity == 0),
+    let formatter = new_formatter(config, &format_options, source, true);
+    // Requests are echoed in full, without the --response-fields mask
+    let echo_formatter = config
+        .echo_request
+        .then(|| new_formatter(config, &format_options, source, true));
+
+    // Check --response-fields against the response type up front, so a bad
+    // path fails even in a dry run, where only requests are formatted
+    let response_mask = if config.response_fields.is_empty() {
+        None
+    } else {
+        Some(format::FieldMask::new(
+            &method_desc.output(),
+            &config.response_fields,
+        )?)
     };
 
     // Build request metadata from headers
@@ -235,6 +520,15 @@ pub async fn run_invoke(
         all_headers = metadata::expand_headers(&all_headers)?;
     }
 
+    // One generated ID tags the whole call, however many messages it streams
+    if let Some(header) = &config.request_id_header {
+        let request_id = metadata::new_request_id();
+        if verbosity > 0 {
+            eprintln!("Request ID ({header}): {request_id}");
+        }
+        all_headers.push(format!("{header}: {request_id}"));
+    }
+
     let request_metadata = metadata::metadata_from_headers(&all_headers);
 
     // Verbose: print request metadata (Go sends to stdout)
@@ -245,71 +539,351 @@ pub async fn run_invoke(
         );
     }
 
-    // Build the gRPC method path: /package.Service/Method
-    let service_name = method_desc.parent_service().full_name();
-    let method_name = method_desc.name();
-    let path: PathAndQuery = format!("/{service_name}/{method_name}")
-        .parse()
-        .map_err(|e| GrpcurlError::InvalidArgument(format!("invalid method path: {e}")))?;
+    // Build the gRPC method path: /package.Service/Method, unless overridden
+    let path: PathAndQuery = match config.rpc_path.as_deref() {
+        Some(rpc_path) => rpc_path.parse().map_err(|e| {
+            GrpcurlError::InvalidArgument(format!("invalid --rpc-path \"{rpc_path}\": {e}"))
+        })?,
+        None => {
+            let service_name = method_desc.parent_service().full_name();
+            let method_name = method_desc.name();
+            format!("/{service_name}/{method_name}")
+                .parse()
+                .map_err(|e| GrpcurlError::InvalidArgument(format!("invalid method path: {e}")))?
+        }
+    };
 
-    // Create the gRPC client with gzip decompression support.
-    // Matches Go's `_ "google.golang.org/grpc/encoding/gzip"` import which
-    // registers gzip as an available encoding (accept compressed responses).
-    let mut grpc_client =
-        Grpc::new(channel).accept_compressed(tonic::codec::CompressionEncoding::Gzip);
+    if config.dry_run {
+        return dry_run(
+            &method_desc,
+            &mut parser,
+            &path,
+            &formatter,
+            &request_metadata,
+            config,
+        );
+    }
 
-    // Set max message size if specified
-    if let Some(max_sz) = config.max_msg_sz {
-        grpc_client = grpc_client.max_decoding_message_size(max_sz as usize);
+    // Each --output-dir file holds one response, so its formatter never
+    // separates messages
+    let file_formatter = config
+        .output_dir
+        .as_ref()
+        .map(|_| new_formatter(config, &format_options, source, false));
+    let (formatter, file_formatter) = match response_mask {
+        Some(mask) => (
+            format::masked_formatter(mask.clone(), formatter),
+            file_formatter.map(|formatter| format::masked_formatter(mask, formatter)),
+        ),
+        None => (formatter, file_formatter),
+    };
+
+    // Timed from here, so method resolution and reflection are not counted
+    let start = Instant::now();
+    // grpc-web reuses the same connection and only changes how each call is
+    // framed, so the channel is wrapped rather than re-dialed.
+    let result = if config.grpc_web {
+        dispatch(
+            connection::grpc_web_channel(channel),
+            &method_desc,
+            &mut parser,
+            path,
+            Formatters {
+                response: &formatter,
+                echo_request: echo_formatter.as_ref(),
+                response_file: file_formatter,
+            },
+            &request_metadata,
+            config,
+        )
+        .await
+    } else {
+        dispatch(
+            channel,
+            &method_desc,
+            &mut parser,
+            path,
+            Formatters {
+                response: &formatter,
+                echo_request: echo_formatter.as_ref(),
+                response_file: file_formatter,
+            },
+            &request_metadata,
+            config,
+        )
+        .await
+    };
+
+    // Handle gRPC status errors: convert to InvokeResult instead of propagating.
+    // When verbose, show any trailers attached to the error status (matching Go
+    // which shows headers/trailers even on error responses).
+    let mut invoke_result = match result {
+        Ok(invoke_result) => invoke_result,
+        Err(e) => {
+            let status = extract_grpc_status(e)?;
+            if config.verbosity > 0 {
+                print_response_trailers(status.metadata(), config.verbosity);
+            }
+            InvokeResult::new(status, parser.num_requests().max(1), 0)
+        }
+    };
+    invoke_result.elapsed = start.elapsed();
+    Ok(invoke_result)
+}
+
+/// Create the message formatter selected by `--format`. Unless
+/// `separate_messages` is false, text messages after the first are preceded
+/// by a record separator when not verbose.
+fn new_formatter(
+    config: &InvokeConfig,
+    format_options: &FormatOptions,
+    source: &dyn DescriptorSource,
+    separate_messages: bool,
+) -> format::Formatter {
+    match config.format {
+        Format::Json => format::json_formatter(format_options, source.descriptor_pool_snapshot()),
+        Format::Text => format::text_formatter(
+            separate_messages && config.verbosity == 0,
+            config.text_legacy_brackets,
+        ),
+        Format::Ndjson => {
+            format::ndjson_formatter(format_options, source.descriptor_pool_snapshot())
+        }
+    }
+}
+
+/// Print the fully-formed request(s) for a method without sending them.
+///
+/// Applies the same message-count rules as a real invocation, so invalid
+/// payloads are reported exactly as they would be when calling the server.
+fn dry_run(
+    method_desc: &prost_reflect::MethodDescriptor,
+    parser: &mut RequestParser,
+    path: &PathAndQuery,
+    formatter: &format::Formatter,
+    request_metadata: &MetadataMap,
+    config: &InvokeConfig,
+) -> Result<InvokeResult, Box<dyn std::error::Error>> {
+    let request_desc = method_desc.input();
+    let mut messages = collect_all_messages(parser, &request_desc, config.empty_text_stream)?;
+
+    if !method_desc.is_client_streaming() {
+        let kind = if method_desc.is_server_streaming() {
+            "server-streaming"
+        } else {
+            "unary"
+        };
+        if messages.len() > 1 {
+            return Err(format!(
+                "method {:?} is a {kind} RPC, but request data contained more than 1 message",
+                path.path()
+            )
+            .into());
+        }
+        if config.no_default_request && (messages.is_empty() || parser.is_empty_text()) {
+            return Err(NO_REQUEST_DATA.into());
+        }
+        if messages.is_empty() {
+            messages.push(DynamicMessage::new(request_desc.clone()));
+        }
+    } else if config.require_request && messages.is_empty() {
+        let kind = if method_desc.is_server_streaming() {
+            "bidi-streaming"
+        } else {
+            "client-streaming"
+        };
+        return Err(no_requests_error(path, kind));
+    }
+
+    println!("Method path: {}", path.path());
+
+    // Verbose mode has already printed the metadata
+    if config.verbosity == 0 {
+        print!(
+            "\nRequest metadata to send:\n{}\n",
+            metadata::metadata_to_string(request_metadata)
+        );
     }
 
-    // Dispatch based on streaming type
-    let is_client_stream = method_desc.is_client_streaming();
-    let is_server_stream = method_desc.is_server_streaming();
+    for (i, msg) in messages.iter().enumerate() {
+        print!("\nRequest contents:\n");
+        match (formatter)(msg) {
+            Ok(output) => println!("{output}"),
+            Err(e) => eprintln!("Failed to format request message {}: {e}", i + 1),
+        }
+    }
+
+    let noun = if messages.len() == 1 {
+        "message"
+    } else {
+        "messages"
+    };
+    println!(
+        "\nDry run: {} request {noun} would be sent; RPC not invoked.",
+        messages.len()
+    );
+
+    Ok(InvokeResult::new(tonic::Status::ok(""), messages.len(), 0))
+}
+
+/// Create the gRPC client over the given transport.
+fn new_client<T>(transport: T, config: &InvokeConfig) -> Grpc<T> {
+    // Accept compressed responses. The default of gzip matches Go's
+    // `_ "google.golang.org/grpc/encoding/gzip"` import which registers gzip
+    // as an available encoding.
+    let mut client = Grpc::new(transport);
+    for &encoding in config.accept_encoding.encodings() {
+        client = client.accept_compressed(encoding);
+    }
+
+    // Set max message size if specified. Like Go, the limit governs both
+    // directions: oversized requests fail locally instead of on the server.
+    if let Some(max_sz) = config.max_msg_sz {
+        client = client
+            .max_decoding_message_size(max_sz as usize)
+            .max_encoding_message_size(max_sz as usize);
+    }
+    client
+}
 
+/// Dispatch the call to the invoker matching the method's streaming type.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(path = %path))
+)]
+async fn dispatch<T>(
+    transport: T,
+    method_desc: &prost_reflect::MethodDescriptor,
+    parser: &mut RequestParser,
+    path: PathAndQuery,
+    formatters: Formatters<'_>,
+    request_metadata: &MetadataMap,
+    config: &InvokeConfig,
+) -> Result<InvokeResult, Box<dyn std::error::Error>>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: HttpBody<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+{
+    let request_desc = method_desc.input();
+    let response_desc = method_desc.output();
+    // tonic decompresses before the codec, so compressed sizes for -vv have
+    // to be read off the response frames by the transport
+    let response_frames = (config.verbosity > 1).then(ResponseFrames::default);
+    let mut client = new_client(
+        RecordResponseFrames::new(transport, response_frames.clone()),
+        config,
+    );
+    let wire_dump = config
+        .dump_wire
+        .as_deref()
+        .map(WireDump::create)
+        .transpose()?;
+    let response_dir = match (config.output_dir.as_deref(), formatters.response_file) {
+        (Some(dir), Some(formatter)) => Some(ResponseDir::create(dir, config.format, formatter)?),
+        _ => None,
+    };
     let mut ctx = InvokeContext {
-        client: &mut grpc_client,
-        parser: &mut parser,
+        client: &mut client,
+        parser,
         request_desc: &request_desc,
         response_desc: &response_desc,
         path,
-        formatter: &formatter,
-        request_metadata: &request_metadata,
-        verbosity,
+        formatter: formatters.response,
+        echo_formatter: formatters.echo_request,
+        request_metadata,
+        verbosity: config.verbosity,
+        warn_unknown_fields: config.warn_unknown_response_fields,
+        max_recv_total: config.max_recv_total,
+        empty_text_stream: config.empty_text_stream,
+        no_default_request: config.no_default_request,
+        require_request: config.require_request,
+        wait_for_ready: config.wait_for_ready,
+        retry_backoff: config.retry_backoff,
+        deadline: config.deadline,
+        header_deadline: config.deadline.filter(|_| !config.no_deadline_header),
+        count_only: config.count_only,
+        response_dir,
+        wire_dump,
+        typed_requests: reads_typed_requests(config, method_desc).then_some(TypedRequests {
+            format: config.format,
+            allow_unknown_fields: config.allow_unknown_fields,
+            expand_data: config.expand_data,
+        }),
+        response_frames,
     };
 
-    let result = match (is_client_stream, is_server_stream) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        client_streaming = method_desc.is_client_streaming(),
+        server_streaming = method_desc.is_server_streaming(),
+        "RPC started"
+    );
+    let result = match (
+        method_desc.is_client_streaming(),
+        method_desc.is_server_streaming(),
+    ) {
         (false, false) => invoke_unary(&mut ctx).await,
         (false, true) => invoke_server_stream(&mut ctx).await,
         (true, false) => invoke_client_stream(&mut ctx).await,
         (true, true) => invoke_bidi_stream(&mut ctx).await,
     };
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(result) => tracing::debug!(
+            code = ?result.code,
+            requests = result.num_requests,
+            responses = result.num_responses,
+            "RPC finished"
+        ),
+        Err(e) => tracing::debug!(error = %e, "RPC failed"),
+    }
+    result
+}
 
-    // Handle gRPC status errors: convert to InvokeResult instead of propagating.
-    // When verbose, show any trailers attached to the error status (matching Go
-    // which shows headers/trailers even on error responses).
-    match result {
-        Ok(invoke_result) => Ok(invoke_result),
-        Err(e) => match extract_grpc_status(e) {
-            Ok(status) => {
-                if config.verbosity > 0 {
-                    print_response_trailers(status.metadata(), config.verbosity);
-                }
-                Ok(InvokeResult {
-                    status: Some(status),
-                    num_requests: parser.num_requests().max(1),
-                    num_responses: 0,
-                })
-            }
-            Err(e) => Err(e),
-        },
+/// Wait for the client's channel to accept a request.
+///
+/// Without `wait_for_ready` a channel that is not ready fails the call
+/// straight away; with it, `ready()` is retried with backoff until that
+/// timeout or the operation's `deadline` passes, whichever is first.
+async fn wait_ready<T>(
+    client: &mut Grpc<T>,
+    wait_for_ready: Option<Duration>,
+    retry_backoff: connection::BackoffPolicy,
+    deadline: Option<Instant>,
+) -> Result<(), GrpcurlError>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+{
+    let mut backoff = wait_for_ready
+        .map(|timeout| connection::Backoff::new(timeout, retry_backoff).until(deadline));
+    loop {
+        let err: StdError = match client.ready().await {
+            Ok(()) => return Ok(()),
+            Err(e) => e.into(),
+        };
+        let retry = match backoff.as_mut() {
+            Some(backoff) => backoff.wait().await,
+            None => false,
+        };
+        if !retry {
+            return Err(GrpcurlError::Other(
+                format!("service not ready: {err}").into(),
+            ));
+        }
     }
 }
 
-/// Build a tonic Request with metadata attached.
-fn build_request<T>(msg: T, md: &MetadataMap) -> tonic::Request<T> {
+/// Build a tonic Request with metadata attached, and the time left until the
+/// deadline, if any, as its `grpc-timeout` header.
+fn build_request<T>(msg: T, md: &MetadataMap, deadline: Option<Instant>) -> tonic::Request<T> {
     let mut req = tonic::Request::new(msg);
     *req.metadata_mut() = md.clone();
+    if let Some(deadline) = deadline {
+        req.set_timeout(deadline.saturating_duration_since(Instant::now()));
+    }
     req
 }
 
@@ -360,16 +934,66 @@ fn print_response_trailers(md: &MetadataMap, verbosity: u8) {
     }
 }
 
+/// Print the wire size of the next response message when it arrived
+/// compressed. Best-effort: the size comes from frame headers recorded by the
+/// transport, and when none was seen only the decoded size above is shown.
+fn print_compressed_size(frames: Option<&ResponseFrames>) {
+    let Some(frames) = frames else {
+        return;
+    };
+    match frames.next_frame() {
+        Some(frame) if frame.compressed => {
+            let encoding = frames.encoding().unwrap_or_else(|| "unknown".to_string());
+            println!("Compressed response size: {} bytes ({encoding})", frame.len);
+        }
+        Some(_) => {}
+        None => println!("(compressed size unavailable; showing decoded size only)"),
+    }
+}
+
+impl<T> InvokeContext<'_, T> {
+    /// Print response `response_num`, or write it to its file with
+    /// `--output-dir`.
+    fn emit_response(
+        &self,
+        msg: &DynamicMessage,
+        response_num: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.response_dir {
+            Some(dir) => {
+                if self.warn_unknown_fields {
+                    warn_unknown_response_fields(msg, response_num);
+                }
+                dir.write(msg, response_num)
+            }
+            None => print_response(
+                msg,
+                self.formatter,
+                self.verbosity,
+                self.response_frames.as_ref(),
+                response_num,
+                self.warn_unknown_fields,
+            ),
+        }
+    }
+}
+
 /// Print a single response message with appropriate verbose headers.
 /// Go sends all of this to stdout (h.Out), errors to stderr.
 fn print_response(
     msg: &DynamicMessage,
     formatter: &format::Formatter,
     verbosity: u8,
+    frames: Option<&ResponseFrames>,
     response_num: usize,
+    warn_unknown_fields: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if warn_unknown_fields {
+        warn_unknown_response_fields(msg, response_num);
+    }
     if verbosity > 1 {
         print!("\nEstimated response size: {} bytes\n", msg.encoded_len());
+        print_compressed_size(frames);
     }
     if verbosity > 0 {
         print!("\nResponse contents:\n");
@@ -383,12 +1007,221 @@ fn print_response(
     Ok(())
 }
 
+/// Directory that streamed responses are written to for `--output-dir`.
+struct ResponseDir {
+    dir: PathBuf,
+    extension: &'static str,
+    formatter: format::Formatter,
+}
+
+impl ResponseDir {
+    /// Create the directory, and any missing parents. Each response is
+    /// formatted with `formatter` on its own.
+    fn create(
+        dir: &str,
+        format: Format,
+        formatter: format::Formatter,
+    ) -> Result<Self, GrpcurlError> {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            GrpcurlError::Io(std::io::Error::new(
+                e.kind(),
+                format!("failed to create directory '{dir}': {e}"),
+            ))
+        })?;
+        let extension = match format {
+            Format::Json => "json",
+            Format::Ndjson => "ndjson",
+            Format::Text => "txt",
+        };
+        Ok(ResponseDir {
+            dir: PathBuf::from(dir),
+            extension,
+            formatter,
+        })
+    }
+
+    /// The file response `response_num` (1-based) is written to.
+    fn path(&self, response_num: usize) -> PathBuf {
+        self.dir
+            .join(format!("response-{response_num:04}.{}", self.extension))
+    }
+
+    /// Format one response and write it to its numbered file.
+    fn write(
+        &self,
+        msg: &DynamicMessage,
+        response_num: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let output = match (self.formatter)(msg) {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Failed to format response message {response_num}: {e}");
+                return Ok(());
+            }
+        };
+        let path = self.path(response_num);
+        std::fs::write(&path, output + "\n").map_err(|e| {
+            GrpcurlError::Io(std::io::Error::new(
+                e.kind(),
+                format!("failed to write response file '{}': {e}", path.display()),
+            ))
+        })?;
+        Ok(())
+    }
+}
+
+/// Print each request message for `--echo-request`, if it is enabled.
+fn echo_requests(formatter: Option<&format::Formatter>, messages: &[DynamicMessage]) {
+    let Some(formatter) = formatter else {
+        return;
+    };
+    for (i, msg) in (1..).zip(messages) {
+        if let Some(echo) = echo_text(formatter, msg, i) {
+            println!("{echo}");
+        }
+    }
+}
+
+/// Format a request message with every line prefixed `>> `. A message that
+/// fails to format is reported to stderr and not echoed.
+fn echo_text(
+    formatter: &format::Formatter,
+    msg: &DynamicMessage,
+    request_num: usize,
+) -> Option<String> {
+    match (formatter)(msg) {
+        Ok(output) => Some(
+            output
+                .lines()
+                .map(|line| format!(">> {line}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Err(e) => {
+            eprintln!("Failed to format request message {request_num}: {e}");
+            None
+        }
+    }
+}
+
+/// Report the tag numbers of fields the response carried that the local
+/// descriptor does not define, which usually means the server's schema is
+/// newer than the client's.
+fn warn_unknown_response_fields(msg: &DynamicMessage, response_num: usize) {
+    use prost_reflect::ReflectMessage;
+
+    let mut tags: Vec<u32> = msg.unknown_fields().map(|f| f.number()).collect();
+    if tags.is_empty() {
+        return;
+    }
+    tags.sort_unstable();
+    tags.dedup();
+    let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+    eprintln!(
+        "warning: response message {response_num} ({}) has unknown fields with tag numbers: {}",
+        msg.descriptor().full_name(),
+        tags.join(", ")
+    );
+}
+
+/// Add a streamed response's encoded size to the running total, failing once
+/// the total exceeds the `max_recv_total` budget.
+fn check_recv_budget(
+    received_bytes: &mut u64,
+    msg: &DynamicMessage,
+    max_recv_total: Option<u64>,
+    response_num: usize,
+) -> Result<(), GrpcurlError> {
+    *received_bytes += msg.encoded_len() as u64;
+    match max_recv_total {
+        Some(limit) if *received_bytes > limit => Err(GrpcurlError::Other(
+            format!(
+                "response stream exceeded the --max-recv-total budget of {limit} bytes \
+                 ({} bytes received in {response_num} messages); aborting the call",
+                *received_bytes
+            )
+            .into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Timing data for `--vv`, printed to stderr as the call progresses.
+///
+/// The clock starts when the call is sent, after any `--wait-for-ready`
+/// wait. Does nothing below verbosity 2.
+struct RpcTimer {
+    enabled: bool,
+    start: Instant,
+    last: Instant,
+}
+
+impl RpcTimer {
+    fn start(verbosity: u8) -> Self {
+        let now = Instant::now();
+        RpcTimer {
+            enabled: verbosity > 1,
+            start: now,
+            last: now,
+        }
+    }
+
+    /// Record the arrival of the response headers (time to first byte).
+    fn headers(&mut self) {
+        if self.enabled {
+            self.last = Instant::now();
+            eprintln!(
+                "Timing: response headers received after {}",
+                format_ms(self.start.elapsed())
+            );
+        }
+    }
+
+    /// Record the arrival of a streamed response message.
+    fn response(&mut self, response_num: usize) {
+        if self.enabled {
+            let now = Instant::now();
+            eprintln!(
+                "Timing: response {response_num} received after {} (+{} since previous)",
+                format_ms(now - self.start),
+                format_ms(now - self.last)
+            );
+            self.last = now;
+        }
+    }
+
+    /// Record the end of the call, once the trailers are in.
+    fn finish(&self) {
+        if self.enabled {
+            eprintln!(
+                "Timing: RPC completed in {}",
+                format_ms(self.start.elapsed())
+            );
+        }
+    }
+}
+
+fn format_ms(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
 /// Invoke a unary RPC: single request, single response.
-async fn invoke_unary(
-    ctx: &mut InvokeContext<'_>,
-) -> Result<InvokeResult, Box<dyn std::error::Error>> {
+async fn invoke_unary<T>(
+    ctx: &mut InvokeContext<'_, T>,
+) -> Result<InvokeResult, Box<dyn std::error::Error>>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: HttpBody<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+{
     let request_msg = match ctx.parser.next(ctx.request_desc) {
+        // Empty text input parses as one empty message
+        Ok(_) if ctx.no_default_request && ctx.parser.is_empty_text() => {
+            return Err(NO_REQUEST_DATA.into());
+        }
         Ok(msg) => msg,
+        Err(ParseError::Eof) if ctx.no_default_request => return Err(NO_REQUEST_DATA.into()),
         Err(ParseError::Eof) => DynamicMessage::new(ctx.request_desc.clone()),
         Err(ParseError::Error(e)) => return Err(e.into()),
     };
@@ -407,22 +1240,29 @@ async fn invoke_unary(
     }
 
     let num_requests = ctx.parser.num_requests();
-
-    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone());
-    ctx.client
-        .ready()
-        .await
-        .map_err(|e| GrpcurlError::Other(format!("service not ready: {e}").into()))?;
+    echo_requests(ctx.echo_formatter, std::slice::from_ref(&request_msg));
+
+    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone())
+        .with_dump(ctx.wire_dump.clone());
+    wait_ready(
+        ctx.client,
+        ctx.wait_for_ready,
+        ctx.retry_backoff,
+        ctx.deadline,
+    )
+    .await?;
 
     let path = std::mem::replace(&mut ctx.path, PathAndQuery::from_static("/"));
+    let mut timer = RpcTimer::start(ctx.verbosity);
     let response = ctx
         .client
         .unary(
-            build_request(request_msg, ctx.request_metadata),
+            build_request(request_msg, ctx.request_metadata, ctx.header_deadline),
             path,
             codec,
         )
         .await?;
+    timer.headers();
 
     // For unary RPCs, tonic merges headers and trailers into response.metadata().
     // We filter out gRPC pseudo-headers for the "headers" display, and show the
@@ -431,24 +1271,33 @@ async fn invoke_unary(
     print_response_headers(response.metadata(), ctx.verbosity);
 
     // Response body
-    print_response(response.get_ref(), ctx.formatter, ctx.verbosity, 1)?;
+    ctx.emit_response(response.get_ref(), 1)?;
 
     // Show trailers (same metadata, since tonic merges them for unary)
     print_response_trailers(response.metadata(), ctx.verbosity);
+    timer.finish();
 
-    Ok(InvokeResult {
-        status: Some(tonic::Status::ok("")),
-        num_requests,
-        num_responses: 1,
-    })
+    Ok(InvokeResult::new(tonic::Status::ok(""), num_requests, 1)
+        .with_trailers(response.metadata().clone()))
 }
 
 /// Invoke a server-streaming RPC: single request, stream of responses.
-async fn invoke_server_stream(
-    ctx: &mut InvokeContext<'_>,
-) -> Result<InvokeResult, Box<dyn std::error::Error>> {
+async fn invoke_server_stream<T>(
+    ctx: &mut InvokeContext<'_, T>,
+) -> Result<InvokeResult, Box<dyn std::error::Error>>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: HttpBody<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+{
     let request_msg = match ctx.parser.next(ctx.request_desc) {
+        // Empty text input parses as one empty message
+        Ok(_) if ctx.no_default_request && ctx.parser.is_empty_text() => {
+            return Err(NO_REQUEST_DATA.into());
+        }
         Ok(msg) => msg,
+        Err(ParseError::Eof) if ctx.no_default_request => return Err(NO_REQUEST_DATA.into()),
         Err(ParseError::Eof) => DynamicMessage::new(ctx.request_desc.clone()),
         Err(ParseError::Error(e)) => return Err(e.into()),
     };
@@ -457,63 +1306,211 @@ async fn invoke_server_stream(
     match ctx.parser.next(ctx.request_desc) {
         Ok(_) => {
             return Err(format!(
-                "method {:?} is a server-streaming RPC, but request data contained more than 1 message",
+                "method {:?} is a server-streaming RPC, but request data contained more than 1 \
+                 message",
                 ctx.path.path()
-            ).into());
+            )
+            .into());
         }
         Err(ParseError::Error(e)) => return Err(e.into()),
         Err(ParseError::Eof) => {} // expected
     }
 
     let num_requests = ctx.parser.num_requests();
-
-    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone());
-    ctx.client
-        .ready()
-        .await
-        .map_err(|e| GrpcurlError::Other(format!("service not ready: {e}").into()))?;
+    echo_requests(ctx.echo_formatter, std::slice::from_ref(&request_msg));
+
+    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone())
+        .with_dump(ctx.wire_dump.clone());
+    wait_ready(
+        ctx.client,
+        ctx.wait_for_ready,
+        ctx.retry_backoff,
+        ctx.deadline,
+    )
+    .await?;
 
     let path = std::mem::replace(&mut ctx.path, PathAndQuery::from_static("/"));
+    let mut timer = RpcTimer::start(ctx.verbosity);
     let response = ctx
         .client
         .server_streaming(
-            build_request(request_msg, ctx.request_metadata),
+            build_request(request_msg, ctx.request_metadata, ctx.header_deadline),
             path,
             codec,
         )
         .await?;
+    timer.headers();
 
     // Response headers from the initial frame
     print_response_headers(response.metadata(), ctx.verbosity);
 
     let mut stream = response.into_inner();
     let mut num_responses = 0;
+    let mut received_bytes = 0;
     while let Some(msg) = stream.message().await? {
         num_responses += 1;
-        print_response(&msg, ctx.formatter, ctx.verbosity, num_responses)?;
+        timer.response(num_responses);
+        check_recv_budget(&mut received_bytes, &msg, ctx.max_recv_total, num_responses)?;
+        if ctx.count_only {
+            continue;
+        }
+        ctx.emit_response(&msg, num_responses)?;
+    }
+    if ctx.count_only {
+        println!("{num_responses}");
     }
 
     // Response trailers (available after stream ends)
-    if let Some(trailers) = stream.trailers().await? {
-        print_response_trailers(&trailers, ctx.verbosity);
+    let trailers = stream.trailers().await?;
+    if let Some(trailers) = &trailers {
+        print_response_trailers(trailers, ctx.verbosity);
     } else if ctx.verbosity > 0 {
         let empty = MetadataMap::new();
         print_response_trailers(&empty, ctx.verbosity);
     }
+    timer.finish();
+
+    Ok(
+        InvokeResult::new(tonic::Status::ok(""), num_requests, num_responses)
+            .with_trailers(trailers.unwrap_or_default()),
+    )
+}
+
+/// Whether a bidi call should read its requests from a terminal as they are
+/// typed: `-d @` with stdin attached to a TTY.
+///
+/// Piped input is still read up front, so scripts behave as before.
+fn reads_typed_requests(config: &InvokeConfig, method: &prost_reflect::MethodDescriptor) -> bool {
+    matches!(config.data.as_deref(), Some("@" | "@-"))
+        && config.request_type.is_none()
+        && method.is_client_streaming()
+        && method.is_server_streaming()
+        && !config.dry_run
+        && std::io::stdin().is_terminal()
+}
+
+/// Parses request messages from lines typed on stdin during a bidi call.
+#[derive(Debug, Clone)]
+struct TypedRequests {
+    format: Format,
+    allow_unknown_fields: bool,
+    expand_data: bool,
+}
+
+impl TypedRequests {
+    /// Parse every message on one input line.
+    fn parse_line(
+        &self,
+        line: &str,
+        desc: &prost_reflect::MessageDescriptor,
+    ) -> Result<Vec<DynamicMessage>, Box<dyn std::error::Error>> {
+        let line = if self.expand_data {
+            metadata::expand_data(line)?
+        } else {
+            line.to_string()
+        };
+        let options = FormatOptions {
+            allow_unknown_fields: self.allow_unknown_fields,
+            ..Default::default()
+        };
+        let mut parser = match self.format {
+            Format::Json | Format::Ndjson => {
+                RequestParser::Json(JsonRequestParser::new(Some(&line), &options)?)
+            }
+            Format::Text => RequestParser::Text(TextRequestParser::new(Some(&line), &options)?),
+        };
+        collect_all_messages(&mut parser, desc, false)
+    }
+
+    /// Send each typed line's messages as soon as it is entered, on a plain
+    /// thread so a read blocked on the terminal never holds up the runtime.
+    /// End of input (Ctrl-D) or a line that fails to parse drops `tx`,
+    /// closing the request stream; how input ended is reported before that,
+    /// so it is known by the time the call completes.
+    fn spawn_reader(
+        self,
+        desc: prost_reflect::MessageDescriptor,
+        tx: tokio::sync::mpsc::Sender<DynamicMessage>,
+        num_sent: Arc<AtomicUsize>,
+    ) -> TypedReader {
+        let (outcome_tx, outcome) = std::sync::mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let result = self.read_lines(&desc, &tx, &num_sent);
+            let _ = outcome_tx.send(result);
+            // tx drops here, signaling end-of-stream
+        });
+        TypedReader { handle, outcome }
+    }
+
+    /// Read lines until end of input, the first line that fails to parse, or
+    /// the call closing the request stream.
+    fn read_lines(
+        &self,
+        desc: &prost_reflect::MessageDescriptor,
+        tx: &tokio::sync::mpsc::Sender<DynamicMessage>,
+        num_sent: &AtomicUsize,
+    ) -> Result<(), GrpcurlError> {
+        use std::io::BufRead;
+
+        for line in std::io::stdin().lock().lines() {
+            let line = line.map_err(GrpcurlError::Io)?;
+            if tx.is_closed() {
+                return Ok(()); // call ended while waiting for this line
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let messages = self.parse_line(&line, desc).map_err(|e| {
+                GrpcurlError::InvalidArgument(format!("failed to parse request: {e}"))
+            })?;
+            for msg in messages {
+                if tx.blocking_send(msg).is_err() {
+                    return Ok(()); // receiver dropped (server closed stream)
+                }
+                num_sent.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+}
 
-    Ok(InvokeResult {
-        status: Some(tonic::Status::ok("")),
-        num_requests,
-        num_responses,
-    })
+/// The thread reading typed requests for a bidi call.
+struct TypedReader {
+    handle: std::thread::JoinHandle<()>,
+    outcome: std::sync::mpsc::Receiver<Result<(), GrpcurlError>>,
+}
+
+impl TypedReader {
+    /// Join the reader once the call has completed, returning the error that
+    /// ended input, if any.
+    ///
+    /// When the server ends the call before input does, the thread is still
+    /// blocked on the terminal and cannot be interrupted; it exits without
+    /// sending once the next line arrives, finding the request stream closed.
+    fn finish(self) -> Result<(), GrpcurlError> {
+        match self.outcome.try_recv() {
+            Ok(outcome) => {
+                let _ = self.handle.join();
+                outcome
+            }
+            Err(_) => Ok(()),
+        }
+    }
 }
 
 /// Collect all request messages from the parser, with empty-input default.
+///
+/// When `empty_text_stream` is set, empty text-format input yields no
+/// messages rather than the single empty message the text parser produces.
 fn collect_all_messages(
     parser: &mut RequestParser,
     request_desc: &prost_reflect::MessageDescriptor,
+    empty_text_stream: bool,
 ) -> Result<Vec<DynamicMessage>, Box<dyn std::error::Error>> {
     let mut messages = Vec::new();
+    if empty_text_stream && parser.is_empty_text() {
+        return Ok(messages);
+    }
     loop {
         match parser.next(request_desc) {
             Ok(msg) => messages.push(msg),
@@ -524,113 +1521,194 @@ fn collect_all_messages(
     Ok(messages)
 }
 
+/// Error for a unary or server-streaming call with no request data under
+/// `no_default_request`.
+const NO_REQUEST_DATA: &str = "no request data provided";
+
+/// Error for a client or bidi streaming call with no request messages under
+/// `require_request`.
+fn no_requests_error(path: &PathAndQuery, kind: &str) -> Box<dyn std::error::Error> {
+    format!(
+        "method {:?} is a {kind} RPC, but request data contained no messages",
+        path.path()
+    )
+    .into()
+}
+
 /// Invoke a client-streaming RPC: stream of requests, single response.
-async fn invoke_client_stream(
-    ctx: &mut InvokeContext<'_>,
-) -> Result<InvokeResult, Box<dyn std::error::Error>> {
-    let messages = collect_all_messages(ctx.parser, ctx.request_desc)?;
+async fn invoke_client_stream<T>(
+    ctx: &mut InvokeContext<'_, T>,
+) -> Result<InvokeResult, Box<dyn std::error::Error>>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: HttpBody<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+{
+    let messages = collect_all_messages(ctx.parser, ctx.request_desc, ctx.empty_text_stream)?;
+    if ctx.require_request && messages.is_empty() {
+        return Err(no_requests_error(&ctx.path, "client-streaming"));
+    }
     let num_requests = ctx.parser.num_requests();
+    echo_requests(ctx.echo_formatter, &messages);
     let request_stream = tokio_stream::iter(messages);
 
-    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone());
-    ctx.client
-        .ready()
-        .await
-        .map_err(|e| GrpcurlError::Other(format!("service not ready: {e}").into()))?;
+    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone())
+        .with_dump(ctx.wire_dump.clone());
+    wait_ready(
+        ctx.client,
+        ctx.wait_for_ready,
+        ctx.retry_backoff,
+        ctx.deadline,
+    )
+    .await?;
 
     let path = std::mem::replace(&mut ctx.path, PathAndQuery::from_static("/"));
+    let mut timer = RpcTimer::start(ctx.verbosity);
     let response = ctx
         .client
         .client_streaming(
-            build_request(request_stream, ctx.request_metadata),
+            build_request(request_stream, ctx.request_metadata, ctx.header_deadline),
             path,
             codec,
         )
         .await?;
+    timer.headers();
 
     // For client-streaming with unary response, same trailer behavior as unary
     print_response_headers(response.metadata(), ctx.verbosity);
 
     // Response body
-    print_response(response.get_ref(), ctx.formatter, ctx.verbosity, 1)?;
+    ctx.emit_response(response.get_ref(), 1)?;
 
     // Show trailers (same metadata, since tonic merges them for unary response)
     print_response_trailers(response.metadata(), ctx.verbosity);
+    timer.finish();
 
-    Ok(InvokeResult {
-        status: Some(tonic::Status::ok("")),
-        num_requests,
-        num_responses: 1,
-    })
+    Ok(InvokeResult::new(tonic::Status::ok(""), num_requests, 1)
+        .with_trailers(response.metadata().clone()))
 }
 
 /// Invoke a bidirectional streaming RPC: stream of requests, stream of responses.
 ///
 /// Uses a channel-based approach to send requests concurrently with receiving
 /// responses, matching Go's goroutine-based concurrent send/receive pattern.
-async fn invoke_bidi_stream(
-    ctx: &mut InvokeContext<'_>,
-) -> Result<InvokeResult, Box<dyn std::error::Error>> {
-    let messages = collect_all_messages(ctx.parser, ctx.request_desc)?;
-    let num_requests = ctx.parser.num_requests();
-
+async fn invoke_bidi_stream<T>(
+    ctx: &mut InvokeContext<'_, T>,
+) -> Result<InvokeResult, Box<dyn std::error::Error>>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: HttpBody<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+{
     // Use a channel so messages are fed concurrently with response reading.
     // This matches Go's pattern where a goroutine sends messages while the
     // main goroutine reads responses.
     let (tx, rx) = tokio::sync::mpsc::channel::<DynamicMessage>(16);
-    let send_handle = tokio::spawn(async move {
-        for msg in messages {
-            if tx.send(msg).await.is_err() {
-                break; // receiver dropped (server closed stream)
+    let num_typed = Arc::new(AtomicUsize::new(0));
+    let mut typed_reader = None;
+    let send_handle = match ctx.typed_requests.clone() {
+        Some(typed) => {
+            typed_reader =
+                Some(typed.spawn_reader(ctx.request_desc.clone(), tx, Arc::clone(&num_typed)));
+            None
+        }
+        None => {
+            let messages =
+                collect_all_messages(ctx.parser, ctx.request_desc, ctx.empty_text_stream)?;
+            if ctx.require_request && messages.is_empty() {
+                return Err(no_requests_error(&ctx.path, "bidi-streaming"));
             }
+            // Formatters are not Send, so echoed text is prepared up front
+            // and printed by the sender as each message goes out
+            let echoes: Vec<Option<String>> = (1..)
+                .zip(&messages)
+                .map(|(i, msg)| ctx.echo_formatter.and_then(|f| echo_text(f, msg, i)))
+                .collect();
+            Some(tokio::spawn(async move {
+                for (msg, echo) in messages.into_iter().zip(echoes) {
+                    if let Some(echo) = echo {
+                        println!("{echo}");
+                    }
+                    if tx.send(msg).await.is_err() {
+                        break; // receiver dropped (server closed stream)
+                    }
+                }
+                // tx drops here, signaling end-of-stream
+            }))
         }
-        // tx drops here, signaling end-of-stream
-    });
+    };
 
     let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
 
-    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone());
-    ctx.client
-        .ready()
-        .await
-        .map_err(|e| GrpcurlError::Other(format!("service not ready: {e}").into()))?;
+    let codec = DynamicCodec::new(ctx.request_desc.clone(), ctx.response_desc.clone())
+        .with_dump(ctx.wire_dump.clone());
+    wait_ready(
+        ctx.client,
+        ctx.wait_for_ready,
+        ctx.retry_backoff,
+        ctx.deadline,
+    )
+    .await?;
 
     let path = std::mem::replace(&mut ctx.path, PathAndQuery::from_static("/"));
+    let mut timer = RpcTimer::start(ctx.verbosity);
     let response = ctx
         .client
         .streaming(
-            build_request(request_stream, ctx.request_metadata),
+            build_request(request_stream, ctx.request_metadata, ctx.header_deadline),
             path,
             codec,
         )
         .await?;
+    timer.headers();
 
     // Response headers from the initial frame
     print_response_headers(response.metadata(), ctx.verbosity);
 
     let mut stream = response.into_inner();
     let mut num_responses = 0;
+    let mut received_bytes = 0;
     while let Some(msg) = stream.message().await? {
         num_responses += 1;
-        print_response(&msg, ctx.formatter, ctx.verbosity, num_responses)?;
+        timer.response(num_responses);
+        check_recv_budget(&mut received_bytes, &msg, ctx.max_recv_total, num_responses)?;
+        if ctx.count_only {
+            continue;
+        }
+        ctx.emit_response(&msg, num_responses)?;
+    }
+    if ctx.count_only {
+        println!("{num_responses}");
     }
 
     // Wait for sender to finish (should already be done by now)
-    let _ = send_handle.await;
+    if let Some(send_handle) = send_handle {
+        let _ = send_handle.await;
+    }
+    if let Some(typed_reader) = typed_reader {
+        typed_reader.finish()?;
+    }
+    let num_requests = match ctx.typed_requests {
+        Some(_) => num_typed.load(Ordering::Relaxed),
+        None => ctx.parser.num_requests(),
+    };
 
     // Response trailers
-    if let Some(trailers) = stream.trailers().await? {
-        print_response_trailers(&trailers, ctx.verbosity);
+    let trailers = stream.trailers().await?;
+    if let Some(trailers) = &trailers {
+        print_response_trailers(trailers, ctx.verbosity);
     } else if ctx.verbosity > 0 {
         let empty = MetadataMap::new();
         print_response_trailers(&empty, ctx.verbosity);
     }
+    timer.finish();
 
-    Ok(InvokeResult {
-        status: Some(tonic::Status::ok("")),
-        num_requests,
-        num_responses,
-    })
+    Ok(
+        InvokeResult::new(tonic::Status::ok(""), num_requests, num_responses)
+            .with_trailers(trailers.unwrap_or_default()),
+    )
 }
 
 /// Extract a gRPC status from a boxed error, if it contains one.
@@ -655,14 +1733,31 @@ fn extract_grpc_status(
     }
 }
 
+/// Resolve the `--request-type` message that base64 request data encodes.
+async fn resolve_request_type(
+    source: &dyn DescriptorSource,
+    type_name: &str,
+) -> Result<prost_reflect::MessageDescriptor, Box<dyn std::error::Error>> {
+    match source.find_symbol(type_name).await? {
+        SymbolDescriptor::Message(desc) => Ok(desc),
+        other => Err(Box::new(GrpcurlError::InvalidArgument(format!(
+            "request type \"{type_name}\" is {}, not a message",
+            other.type_label()
+        )))),
+    }
+}
+
 /// Resolve a fully-qualified method name to a MethodDescriptor.
 ///
-/// Accepts both "package.Service/Method" and "package.Service.Method" formats.
+/// Accepts both "package.Service/Method" and "package.Service.Method" formats,
+/// as well as the raw HTTP/2 path "/package.Service/Method".
 /// Matches Go's approach: resolve the service first, then find the method within it.
 async fn resolve_method(
     source: &dyn DescriptorSource,
     symbol: &str,
 ) -> Result<prost_reflect::MethodDescriptor, Box<dyn std::error::Error>> {
+    let symbol = symbol.strip_prefix('/').unwrap_or(symbol);
+
     // Split into service and method parts
     // "package.Service/Method" or "package.Service.Method"
     let (service_name, method_name) = if let Some(slash_pos) = symbol.rfind('/') {
@@ -691,3 +1786,133 @@ async fn resolve_method(
 
     Ok(method)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::{descriptor_source_from_file_descriptor_set, FileSource};
+
+    #[test]
+    fn parse_accept_encoding() {
+        assert_eq!(
+            AcceptEncoding::default().encodings(),
+            [CompressionEncoding::Gzip]
+        );
+        let parsed: AcceptEncoding = "deflate, GZIP,deflate".parse().unwrap();
+        assert_eq!(
+            parsed.encodings(),
+            [CompressionEncoding::Deflate, CompressionEncoding::Gzip]
+        );
+        let parsed: AcceptEncoding = "identity".parse().unwrap();
+        assert!(parsed.encodings().is_empty());
+
+        let err = "gzip,zstd".parse::<AcceptEncoding>().unwrap_err();
+        assert!(err.contains("'zstd'"), "{err}");
+        assert!(err.contains("gzip, deflate, identity"), "{err}");
+    }
+
+    #[test]
+    fn build_request_sends_deadline_header() {
+        let mut md = MetadataMap::new();
+        md.insert("x-test", "1".parse().unwrap());
+
+        // Only the time left is sent
+        let deadline = Instant::now() + Duration::from_millis(1500);
+        let req = build_request((), &md, Some(deadline));
+        let timeout = req
+            .metadata()
+            .get("grpc-timeout")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let micros: u64 = timeout.strip_suffix('u').unwrap().parse().unwrap();
+        assert!((1_000_000..1_500_000).contains(&micros), "{timeout}");
+        assert_eq!(req.metadata().get("x-test").unwrap(), "1");
+
+        // A deadline already passed is sent as no time at all
+        let req = build_request((), &md, Some(Instant::now()));
+        assert_eq!(req.metadata().get("grpc-timeout").unwrap(), "0n");
+
+        let req = build_request((), &md, None);
+        assert!(req.metadata().get("grpc-timeout").is_none());
+    }
+
+    fn make_source() -> FileSource {
+        let fds = prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("test.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![prost_types::DescriptorProto {
+                    name: Some("HelloRequest".into()),
+                    ..Default::default()
+                }],
+                service: vec![prost_types::ServiceDescriptorProto {
+                    name: Some("Greeter".into()),
+                    method: vec![prost_types::MethodDescriptorProto {
+                        name: Some("SayHello".into()),
+                        input_type: Some(".test.v1.HelloRequest".into()),
+                        output_type: Some(".test.v1.HelloRequest".into()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".into()),
+                ..Default::default()
+            }],
+        };
+        descriptor_source_from_file_descriptor_set(fds).unwrap()
+    }
+
+    #[test]
+    fn typed_request_lines() {
+        let source = make_source();
+        let desc = source
+            .descriptor_pool()
+            .unwrap()
+            .get_message_by_name("test.v1.HelloRequest")
+            .unwrap();
+        let typed = TypedRequests {
+            format: Format::Json,
+            allow_unknown_fields: false,
+            expand_data: false,
+        };
+        assert_eq!(typed.parse_line("{} {}", &desc).unwrap().len(), 2);
+        assert!(typed.parse_line(r#"{"nope": 1}"#, &desc).is_err());
+
+        let typed = TypedRequests {
+            allow_unknown_fields: true,
+            ..typed
+        };
+        assert_eq!(typed.parse_line(r#"{"nope": 1}"#, &desc).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn resolve_method_slash_and_dot_forms() {
+        let source = make_source();
+        for symbol in ["test.v1.Greeter/SayHello", "test.v1.Greeter.SayHello"] {
+            let method = resolve_method(&source, symbol).await.unwrap();
+            assert_eq!(method.full_name(), "test.v1.Greeter.SayHello");
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_method_leading_slash_path() {
+        let source = make_source();
+        let plain = resolve_method(&source, "test.v1.Greeter/SayHello")
+            .await
+            .unwrap();
+        let path = resolve_method(&source, "/test.v1.Greeter/SayHello")
+            .await
+            .unwrap();
+        assert_eq!(path, plain);
+    }
+
+    #[tokio::test]
+    async fn resolve_method_unknown_method() {
+        let source = make_source();
+        let err = resolve_method(&source, "/test.v1.Greeter/SayGoodbye")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("SayGoodbye"), "{err}");
+    }
+}
@@ -1,32 +1,180 @@
+use prost_reflect::MethodDescriptor;
+use serde_json::json;
+
 use crate::descriptor::{self, DescriptorSource};
+use crate::error::GrpcurlError;
 
+/// List services, or the methods of `symbol`.
+///
+/// Returns the number of services or methods listed, so callers can treat an
+/// empty listing specially.
 pub async fn run_list(
     source: &dyn DescriptorSource,
     symbol: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    list_json: bool,
+    verbosity: u8,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    if list_json {
+        return print_list_json(source, symbol).await;
+    }
+
     match symbol {
+        Some(service) if verbosity > 0 => {
+            // Verbose: annotate each method with its streaming type
+            let methods = sorted_methods(source, service).await?;
+            for method in &methods {
+                println!("{} ({})", method.full_name(), streaming_kind(method));
+            }
+            Ok(methods.len())
+        }
         Some(service) => {
-            // List all methods of the given service
+            // List all methods of the given service. Like Go, an empty
+            // service prints nothing.
             let methods = descriptor::list_methods(source, service).await?;
-            if methods.is_empty() {
-                // Match Go behavior: empty service prints nothing
-            } else {
-                for method in &methods {
-                    println!("{method}");
-                }
+            for method in &methods {
+                println!("{method}");
             }
+            Ok(methods.len())
         }
         None => {
-            // List all services
+            // List all services. Like Go, no services prints nothing.
             let services = descriptor::list_services(source).await?;
-            if services.is_empty() {
-                // Match Go behavior: no services prints nothing
+            for service in &services {
+                println!("{service}");
+            }
+            Ok(services.len())
+        }
+    }
+}
+
+/// Print the number of services, or of the methods of `symbol`, instead of
+/// their names.
+///
+/// Returns the number printed, so callers can treat an empty listing
+/// specially.
+pub async fn run_count(
+    source: &dyn DescriptorSource,
+    symbol: Option<&str>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let count = match symbol {
+        Some(service) => descriptor::list_methods(source, service).await?.len(),
+        None => descriptor::list_services(source).await?.len(),
+    };
+    println!("{count}");
+    Ok(count)
+}
+
+/// List every method of every service, by fully-qualified name and sorted.
+///
+/// Each service is resolved in turn, so over reflection this populates the
+/// pool with every service's files. With `list_json` the output is an array
+/// of method objects as for a single service; with `verbosity > 0` each
+/// method is annotated with its streaming type.
+///
+/// Returns the number of methods listed.
+pub async fn run_list_all_methods(
+    source: &dyn DescriptorSource,
+    list_json: bool,
+    verbosity: u8,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut methods = Vec::new();
+    for service in descriptor::list_services(source).await? {
+        methods.extend(sorted_methods(source, &service).await?);
+    }
+    methods.sort_by(|a, b| a.full_name().cmp(b.full_name()));
+
+    if list_json {
+        let value: Vec<_> = methods.iter().map(method_json).collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        for method in &methods {
+            if verbosity > 0 {
+                println!("{} ({})", method.full_name(), streaming_kind(method));
             } else {
-                for service in &services {
-                    println!("{service}");
-                }
+                println!("{}", method.full_name());
             }
         }
     }
-    Ok(())
+    Ok(methods.len())
+}
+
+/// List every message and enum type, optionally within a package.
+///
+/// With `list_json` the names are printed as a JSON array, otherwise one per
+/// line. Returns the number of types listed.
+pub async fn run_list_types(
+    source: &dyn DescriptorSource,
+    package: Option<&str>,
+    list_json: bool,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let types = descriptor::list_types(source, package).await?;
+    if list_json {
+        println!("{}", serde_json::to_string_pretty(&types)?);
+    } else {
+        for name in &types {
+            println!("{name}");
+        }
+    }
+    Ok(types.len())
+}
+
+/// Print the listing as pretty-printed JSON for machine consumption.
+///
+/// Without a symbol this is an array of service names. With a service, it is
+/// an array of method objects carrying the request/response types and
+/// streaming flags, sorted by name like the plain-text listing.
+async fn print_list_json(
+    source: &dyn DescriptorSource,
+    symbol: Option<&str>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let value = match symbol {
+        Some(service) => {
+            let methods = sorted_methods(source, service)
+                .await?
+                .iter()
+                .map(method_json)
+                .collect();
+            serde_json::Value::Array(methods)
+        }
+        None => json!(descriptor::list_services(source).await?),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(value.as_array().map_or(0, Vec::len))
+}
+
+/// A method's entry in the JSON listing.
+fn method_json(method: &MethodDescriptor) -> serde_json::Value {
+    json!({
+        "name": method.full_name(),
+        "input_type": method.input().full_name(),
+        "output_type": method.output().full_name(),
+        "client_streaming": method.is_client_streaming(),
+        "server_streaming": method.is_server_streaming(),
+    })
+}
+
+/// Look up a service and return its methods sorted by full name.
+async fn sorted_methods(
+    source: &dyn DescriptorSource,
+    service: &str,
+) -> Result<Vec<MethodDescriptor>, Box<dyn std::error::Error>> {
+    let desc = source.find_symbol(service).await?;
+    let svc = desc
+        .as_service()
+        .ok_or_else(|| GrpcurlError::Other(format!("Service not found: {service}").into()))?;
+
+    let mut methods: Vec<MethodDescriptor> = svc.methods().collect();
+    methods.sort_by(|a, b| a.full_name().cmp(b.full_name()));
+    Ok(methods)
+}
+
+/// Describe a method's streaming type for the verbose listing.
+fn streaming_kind(method: &MethodDescriptor) -> &'static str {
+    match (method.is_client_streaming(), method.is_server_streaming()) {
+        (false, false) => "unary",
+        (false, true) => "server streaming",
+        (true, false) => "client streaming",
+        (true, true) => "bidi streaming",
+    }
 }
@@ -1,3 +1,4 @@
 pub mod describe;
 pub mod invoke;
 pub mod list;
+pub mod reflection_info;
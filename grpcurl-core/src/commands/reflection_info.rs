@@ -0,0 +1,48 @@
+use crate::error::GrpcurlError;
+use crate::reflection::ServerSource;
+
+/// Report which server reflection API versions respond.
+///
+/// Each version is probed with a ListServices request and its outcome printed
+/// on one line, followed by the reflection services the server lists among
+/// its own services. The listing can differ from what answers: some servers
+/// only advertise one version but implement both, or the other way around.
+///
+/// Returns the number of versions that responded.
+pub async fn run_reflection_info(source: &ServerSource) -> usize {
+    let mut available = 0;
+    let mut advertised: Vec<String> = Vec::new();
+
+    for probe in source.probe_versions().await {
+        let name = probe.version.service_name().unwrap_or_default();
+        match probe.result {
+            Ok(services) => {
+                available += 1;
+                println!("{name}: available ({} services listed)", services.len());
+                for service in services {
+                    if service.starts_with("grpc.reflection.") && !advertised.contains(&service) {
+                        advertised.push(service);
+                    }
+                }
+            }
+            Err(e) if is_unimplemented(&e) => println!("{name}: not implemented"),
+            Err(e) => println!("{name}: failed: {e}"),
+        }
+    }
+
+    if available > 0 {
+        advertised.sort();
+        let advertised = if advertised.is_empty() {
+            "(none)".to_string()
+        } else {
+            advertised.join(", ")
+        };
+        println!("Advertised reflection services: {advertised}");
+    }
+    available
+}
+
+fn is_unimplemented(err: &GrpcurlError) -> bool {
+    matches!(err, GrpcurlError::ReflectionNotSupported)
+        || matches!(err, GrpcurlError::GrpcStatus(s) if s.code() == tonic::Code::Unimplemented)
+}
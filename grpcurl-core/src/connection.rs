@@ -1,7 +1,13 @@
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
+use tokio::time::Instant;
+use tonic::body::Body;
 use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic_web::{GrpcWebCall, GrpcWebClientLayer, GrpcWebClientService};
+use tower::util::MapRequest;
+use tower::Layer;
 
 use crate::error::{GrpcurlError, Result};
 
@@ -21,21 +27,46 @@ pub struct ConnectionConfig {
     /// Skip server certificate and domain verification.
     pub insecure: bool,
 
-    /// The authoritative name of the remote server.
+    /// Verify the server's certificate chain but not that it covers the
+    /// server name, e.g. when connecting by IP address.
+    pub skip_hostname_verify: bool,
+
+    /// The authoritative name of the remote server, sent as the `:authority`
+    /// pseudo-header. Also the TLS server name when `servername` is not set.
     pub authority: Option<String>,
 
-    /// Override server name when validating TLS certificate.
+    /// Server name for TLS (SNI and certificate verification). Takes precedence
+    /// over `authority` for TLS, and never changes the `:authority` header.
     pub servername: Option<String>,
 
+    /// Leave the SNI extension out of the TLS handshake, for servers that
+    /// reject it. The certificate is still verified against the server name.
+    pub disable_sni: bool,
+
     /// Maximum time, in seconds, to wait for connection to be established.
     pub connect_timeout: Option<f64>,
 
+    /// Keep retrying a failed connection with backoff until `connect_timeout`
+    /// elapses, instead of giving up on the first attempt.
+    pub wait_for_ready: bool,
+
+    /// Delay, in seconds, before the first `wait_for_ready` retry.
+    pub retry_base: Option<f64>,
+
+    /// Longest delay, in seconds, between `wait_for_ready` retries, before
+    /// jitter is added. Defaults to 1 second, or `retry_base` if longer.
+    pub retry_max: Option<f64>,
+
     /// If present, the maximum idle time in seconds for keepalive.
     pub keepalive_time: Option<f64>,
 
     /// Maximum total time the operation can take, in seconds.
     pub max_time: Option<f64>,
 
+    /// When a TLS connection attempt fails because the server does not speak
+    /// TLS, warn and retry it in plaintext.
+    pub plaintext_fallback: bool,
+
     /// Whether the server address is a Unix domain socket path.
     pub unix: bool,
 
@@ -61,21 +92,206 @@ pub struct ConnectionConfig {
 /// Build a tonic Channel from connection configuration and address.
 ///
 /// Handles:
-/// - Address parsing (host:port or socket path)
+/// - Address parsing (host:port, socket path, `unix://` / `unix:` URI, or a
+///   `dns:`, `ipv4:`, or `ipv6:` name-resolver address)
 /// - TLS configuration (system CAs, custom CA, client certs, insecure)
 /// - Unix domain socket connections
 /// - Connection timeout and keepalive
 /// - User-Agent header
+/// - Retrying with backoff when `wait_for_ready` is set
+/// - Retrying in plaintext after a failed TLS attempt when `plaintext_fallback`
+///   is set
 ///
 /// Equivalent to Go's BlockingDial() + ClientTLSConfig() in grpcurl.go.
 pub async fn create_channel(config: &ConnectionConfig, address: &str) -> Result<Channel> {
+    if !config.wait_for_ready {
+        return connect_with_fallback(config, address).await;
+    }
+
+    // Only connection failures are retried; bad flags or unreadable
+    // certificate files will not fix themselves. Connecting comes first, so
+    // the whole of max_time is still left.
+    let max_time = config
+        .max_time
+        .and_then(duration_from_secs)
+        .and_then(|max_time| std::time::Instant::now().checked_add(max_time));
+    let mut backoff = Backoff::new(connect_timeout(config), backoff_policy(config)).until(max_time);
+    loop {
+        match connect_with_fallback(config, address).await {
+            Err(e @ GrpcurlError::Other(_)) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(error = %e, "connection attempt failed; retrying");
+                if !backoff.wait().await {
+                    return Err(e);
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+/// The connection timeout from `config`, or the default when unset or not a
+/// valid duration.
+pub fn connect_timeout(config: &ConnectionConfig) -> Duration {
+    config
+        .connect_timeout
+        .and_then(duration_from_secs)
+        .unwrap_or(Duration::from_secs_f64(DEFAULT_CONNECT_TIMEOUT_SECS))
+}
+
+/// The retry delays from `config`, or the defaults where unset or not a valid
+/// duration. Without a valid `retry_max`, the cap is never below the base.
+pub fn backoff_policy(config: &ConnectionConfig) -> BackoffPolicy {
+    let default = BackoffPolicy::default();
+    let base = config
+        .retry_base
+        .and_then(duration_from_secs)
+        .unwrap_or(default.base);
+    BackoffPolicy {
+        base,
+        max: config
+            .retry_max
+            .and_then(duration_from_secs)
+            .unwrap_or(default.max.max(base)),
+    }
+}
+
+/// A duration of `secs` seconds, or `None` when it is negative, not finite,
+/// or too large, which `ConnectionConfig` built outside the CLI may contain.
+pub fn duration_from_secs(secs: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// How long to wait between retries: `base` doubled after every attempt,
+/// up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// Delay before the first retry.
+    pub base: Duration,
+    /// Cap on the delay, before jitter is added.
+    pub max: Duration,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        BackoffPolicy {
+            base: Duration::from_millis(50),
+            max: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Exponential backoff between retries, bounded by an overall deadline.
+///
+/// Used by `--wait-for-ready` both when connecting and when waiting for the
+/// channel to become ready before an RPC. Attempt `n` sleeps for
+/// `min(base * 2^n, max)` plus up to 20% random jitter, so many clients
+/// started together do not retry in lockstep. Both the delays and the
+/// deadline use tokio's clock, so tests can run it deterministically with
+/// `tokio::time::pause()`.
+pub(crate) struct Backoff {
+    policy: BackoffPolicy,
+    attempt: u32,
+    deadline: Instant,
+}
+
+impl Backoff {
+    /// Largest jitter, as a fraction of the delay.
+    const JITTER: f64 = 0.2;
+
+    pub(crate) fn new(timeout: Duration, policy: BackoffPolicy) -> Self {
+        Backoff {
+            policy,
+            attempt: 0,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    /// Stop at `deadline` as well, if it comes first: the end of the
+    /// operation's time budget.
+    pub(crate) fn until(mut self, deadline: Option<std::time::Instant>) -> Self {
+        if let Some(deadline) = deadline {
+            self.deadline = self.deadline.min(Instant::from_std(deadline));
+        }
+        self
+    }
+
+    /// The delay for the current attempt, before jitter.
+    fn delay(&self) -> Duration {
+        let factor = 2u32.saturating_pow(self.attempt);
+        self.policy.base.saturating_mul(factor).min(self.policy.max)
+    }
+
+    /// Sleep before the next attempt. Returns `false` without sleeping once
+    /// the deadline has passed; the final sleep is cut short at the deadline.
+    pub(crate) async fn wait(&mut self) -> bool {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return false;
+        }
+        let delay = self.delay().mul_f64(1.0 + Self::JITTER * fastrand::f64());
+        tokio::time::sleep(delay.min(self.deadline - now)).await;
+        self.attempt = self.attempt.saturating_add(1);
+        true
+    }
+}
+
+/// Make a connection attempt, redialing in plaintext if TLS fails and
+/// `plaintext_fallback` is set.
+///
+/// A server that only speaks plaintext answers the TLS ClientHello with an
+/// HTTP/2 frame, which surfaces as a handshake error rather than a clear
+/// "wrong transport" message. Only that failure is retried: any other, such as
+/// a rejected server certificate, may mean someone is in the way, and
+/// retrying would send headers in the clear. If the plaintext attempt fails
+/// too, the original TLS error is returned since it describes what the user
+/// asked for.
+async fn connect_with_fallback(config: &ConnectionConfig, address: &str) -> Result<Channel> {
+    let tls_error = match connect(config, address).await {
+        Err(GrpcurlError::Other(e))
+            if config.plaintext_fallback && !config.plaintext && e.is::<PeerNotTls>() =>
+        {
+            GrpcurlError::Other(e)
+        }
+        result => return result,
+    };
+
+    eprintln!("Warning: TLS connection to {address} failed: {tls_error}; retrying in plaintext");
+    let plaintext_config = ConnectionConfig {
+        plaintext: true,
+        insecure: false,
+        ..config.clone()
+    };
+    connect(&plaintext_config, address)
+        .await
+        .map_err(|_| tls_error)
+}
+
+/// Make a single connection attempt.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip(config),
+        fields(plaintext = config.plaintext, insecure = config.insecure),
+        err(level = "debug")
+    )
+)]
+async fn connect(config: &ConnectionConfig, address: &str) -> Result<Channel> {
+    #[cfg(feature = "tracing")]
+    tracing::debug!("connecting");
     if config.alts {
         return Err(GrpcurlError::InvalidArgument(
             "ALTS is not yet supported in grpcurl.".into(),
         ));
     }
 
-    // Unix domain socket
+    let address = target_address(address)?;
+
+    // Unix domain socket, either via --unix or a unix:// / unix: address
+    if let Some(path) = unix_socket_path(address) {
+        return create_unix_channel(config, path).await;
+    }
     if config.unix {
         return create_unix_channel(config, address).await;
     }
@@ -85,9 +301,12 @@ pub async fn create_channel(config: &ConnectionConfig, address: &str) -> Result<
         return create_insecure_channel(config, address).await;
     }
 
-    // If SSLKEYLOGFILE is set, use custom rustls connector for key logging support
-    // (tonic's ClientTlsConfig doesn't expose rustls key_log)
-    if !config.plaintext && std::env::var("SSLKEYLOGFILE").is_ok() {
+    // If SSLKEYLOGFILE is set, SNI is disabled, or hostnames are not verified,
+    // use a custom rustls connector (tonic's ClientTlsConfig exposes neither
+    // rustls key_log, enable_sni, nor a custom certificate verifier)
+    let custom_tls =
+        config.disable_sni || config.skip_hostname_verify || std::env::var("SSLKEYLOGFILE").is_ok();
+    if !config.plaintext && custom_tls {
         return create_custom_tls_channel(config, address).await;
     }
 
@@ -106,34 +325,202 @@ pub async fn create_channel(config: &ConnectionConfig, address: &str) -> Result<
     }
 
     // Connect eagerly (matching Go's BlockingDial behavior)
+    let server_name = tls_server_name(config).unwrap_or_else(|| address_host(address));
     let channel = endpoint
         .connect()
         .await
-        .map_err(|e| GrpcurlError::Other(format!("failed to connect to {address}: {e}").into()))?;
+        .map_err(|e| connect_error(address, (!config.plaintext).then_some(server_name), &e))?;
 
     Ok(channel)
 }
 
+/// Describe a failed connection attempt.
+///
+/// A rejected server certificate otherwise surfaces only as "transport
+/// error", so when `server_name` was verified over TLS the error also names
+/// it, gives rustls's reason, and suggests a fix.
+fn connect_error(
+    target: &str,
+    server_name: Option<&str>,
+    err: &(dyn std::error::Error + 'static),
+) -> GrpcurlError {
+    let mut message = format!("failed to connect to {target}: {err}");
+    if let (Some(server_name), Some(cert_err)) = (server_name, certificate_error(err)) {
+        let hint = match cert_err {
+            rustls::CertificateError::NotValidForName
+            | rustls::CertificateError::NotValidForNameContext { .. } => {
+                "use --servername with a name the certificate covers, \
+                 --skip-hostname-verify to check only the issuer"
+            }
+            _ => "use --cacert with the CA that issued it",
+        };
+        message.push_str(&format!(
+            ": server certificate rejected for server name {server_name:?}: {cert_err}; \
+             {hint}, or --insecure to skip verification"
+        ));
+    }
+    if let Some(rustls::Error::InvalidMessage(_)) = rustls_error(err) {
+        return GrpcurlError::Other(Box::new(PeerNotTls(message)));
+    }
+    GrpcurlError::Other(message.into())
+}
+
+/// A TLS connection attempt that failed because the peer answered with
+/// something other than TLS, which is what `plaintext_fallback` retries.
+#[derive(Debug)]
+struct PeerNotTls(String);
+
+impl fmt::Display for PeerNotTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PeerNotTls {}
+
+/// Find a certificate verification failure in an error's source chain.
+fn certificate_error<'a>(
+    err: &'a (dyn std::error::Error + 'static),
+) -> Option<&'a rustls::CertificateError> {
+    match rustls_error(err)? {
+        rustls::Error::InvalidCertificate(cert_err) => Some(cert_err),
+        _ => None,
+    }
+}
+
+/// Find a TLS handshake failure in an error's source chain.
+///
+/// tokio-rustls reports handshake failures as an `io::Error` wrapping the
+/// rustls error, and `io::Error::source` skips over the wrapped error, so
+/// each `io::Error` is also looked inside.
+fn rustls_error<'a>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a rustls::Error> {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        let wrapped = err
+            .downcast_ref::<std::io::Error>()
+            .and_then(|io_err| io_err.get_ref())
+            .map(|inner| inner as &(dyn std::error::Error + 'static));
+        for candidate in std::iter::once(err).chain(wrapped) {
+            if let Some(rustls_err) = candidate.downcast_ref() {
+                return Some(rustls_err);
+            }
+        }
+        next = err.source();
+    }
+    None
+}
+
+/// Extract the socket path from a `unix://` or `unix:` address.
+///
+/// Accepts the same forms as gRPC name resolution: `unix:///abs/path.sock`,
+/// `unix:relative/path.sock`, and `unix:/abs/path.sock`. Returns `None` for
+/// addresses without a unix scheme. A `unix://` path that is not absolute,
+/// as in `unix://relative/path.sock`, is returned as-is; [`target_address`]
+/// rejects it.
+pub fn unix_socket_path(address: &str) -> Option<&str> {
+    address
+        .strip_prefix("unix://")
+        .or_else(|| address.strip_prefix("unix:"))
+}
+
+/// Strip a gRPC name-resolver scheme from an address, leaving `host:port`.
+///
+/// Accepts `dns:///host:port` (or `dns:host:port`), `ipv4:1.2.3.4:50051`, and
+/// `ipv6:[::1]:50051`. Addresses without a scheme and `unix` addresses are
+/// returned unchanged, except that `unix://` must be followed by an absolute
+/// path; gRPC reads `unix://relative/path` as an authority, not a path. The
+/// DNS server in `dns://server/host:port` is ignored, since names are always
+/// looked up with the system resolver. Other schemes,
+/// and `ipv4:`/`ipv6:` lists of several addresses, are rejected.
+pub fn target_address(address: &str) -> Result<&str> {
+    if let Some(path) = address.strip_prefix("unix://") {
+        if !path.is_empty() && !path.starts_with('/') {
+            return Err(GrpcurlError::InvalidArgument(format!(
+                "unix:// must be followed by an absolute path; use unix:{path} for a \
+                 relative one"
+            )));
+        }
+    }
+    if unix_socket_path(address).is_some() {
+        return Ok(address);
+    }
+
+    if let Some(rest) = address.strip_prefix("dns:") {
+        let host = match rest.strip_prefix("//") {
+            Some(authority_and_host) => authority_and_host
+                .split_once('/')
+                .map_or("", |(_, host)| host),
+            None => rest,
+        };
+        if host.is_empty() {
+            return Err(GrpcurlError::InvalidArgument(format!(
+                "no host in {address:?}; use dns:///host:port"
+            )));
+        }
+        return Ok(host);
+    }
+
+    for scheme in ["ipv4:", "ipv6:"] {
+        if let Some(rest) = address.strip_prefix(scheme) {
+            if rest.contains(',') {
+                return Err(GrpcurlError::InvalidArgument(format!(
+                    "{address:?} lists several addresses; only one is supported"
+                )));
+            }
+            return Ok(rest);
+        }
+    }
+
+    if let Some((scheme, _)) = address.split_once("://") {
+        return Err(GrpcurlError::InvalidArgument(format!(
+            "unsupported address scheme {scheme:?}; use host:port or a dns:, ipv4:, ipv6: \
+             or unix: address"
+        )));
+    }
+    Ok(address)
+}
+
+/// A channel that speaks the grpc-web protocol instead of native gRPC.
+///
+/// Produced by [`grpc_web_channel`]. Requests are framed as binary grpc-web
+/// (`application/grpc-web`) and trailers are decoded from the response body.
+pub type GrpcWebChannel = GrpcWebClientService<
+    MapRequest<Channel, fn(http::Request<GrpcWebCall<Body>>) -> http::Request<Body>>,
+>;
+
+/// Wrap an established channel so RPCs use grpc-web framing.
+///
+/// The connection itself (TLS, Unix sockets, timeouts) is set up as usual by
+/// `create_channel()`; only the wire framing of each call changes. This lets
+/// grpcurl talk to servers that sit behind a grpc-web proxy such as Envoy.
+///
+/// Limitations: only the binary `application/grpc-web` encoding is supported
+/// (not `grpc-web-text`), and since grpc-web has no full-duplex streaming,
+/// client-streaming and bidi calls only work if the proxy accepts the whole
+/// request stream before responding.
+pub fn grpc_web_channel(channel: Channel) -> GrpcWebChannel {
+    let to_body: fn(http::Request<GrpcWebCall<Body>>) -> http::Request<Body> =
+        |req| req.map(Body::new);
+    GrpcWebClientLayer::new().layer(MapRequest::new(channel, to_body))
+}
+
 /// Build common Endpoint configuration (timeout, keepalive, user-agent).
 fn build_endpoint(uri: &str, config: &ConnectionConfig) -> Result<Endpoint> {
     let mut endpoint: Endpoint = Channel::from_shared(uri.to_string())
         .map_err(|e| GrpcurlError::InvalidArgument(format!("invalid address: {e}")))?;
 
     // Connection timeout (default 10s, matching Go's default)
-    let connect_timeout = config
-        .connect_timeout
-        .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
-    endpoint = endpoint.connect_timeout(Duration::from_secs_f64(connect_timeout));
+    endpoint = endpoint.connect_timeout(connect_timeout(config));
 
     // Per-request timeout (--max-time)
-    if let Some(max_time_secs) = config.max_time {
-        endpoint = endpoint.timeout(Duration::from_secs_f64(max_time_secs));
+    if let Some(max_time) = config.max_time.and_then(duration_from_secs) {
+        endpoint = endpoint.timeout(max_time);
     }
 
     // Keepalive
-    if let Some(keepalive_secs) = config.keepalive_time {
+    if let Some(keepalive) = config.keepalive_time.and_then(duration_from_secs) {
         endpoint = endpoint
-            .keep_alive_timeout(Duration::from_secs_f64(keepalive_secs))
+            .keep_alive_timeout(keepalive)
             .keep_alive_while_idle(true);
     }
 
@@ -143,9 +530,48 @@ fn build_endpoint(uri: &str, config: &ConnectionConfig) -> Result<Endpoint> {
         .user_agent(ua.as_str())
         .map_err(|e| GrpcurlError::Other(format!("failed to set user-agent: {e}").into()))?;
 
+    // :authority override, independent of the TLS server name
+    if let Some(ref authority) = config.authority {
+        let origin = authority_origin(endpoint.uri(), authority)?;
+        endpoint = endpoint.origin(origin);
+    }
+
     Ok(endpoint)
 }
 
+/// Build the origin URI that carries an `--authority` override, keeping the
+/// scheme of the endpoint being dialed.
+fn authority_origin(uri: &http::Uri, authority: &str) -> Result<http::Uri> {
+    let scheme = uri.scheme_str().unwrap_or("http");
+    format!("{scheme}://{authority}")
+        .parse()
+        .map_err(|e| GrpcurlError::InvalidArgument(format!("invalid authority '{authority}': {e}")))
+}
+
+/// The server name to use for TLS: `--servername` if set, else `--authority`.
+///
+/// `None` means the host from the dialed address is used.
+fn tls_server_name(config: &ConnectionConfig) -> Option<&str> {
+    config.servername.as_deref().or(config.authority.as_deref())
+}
+
+/// The host part of a `host:port` address, without the port.
+///
+/// Bracketed IPv6 literals such as `[2001:db8::1]:50051` yield the bare
+/// address, `2001:db8::1`, which rustls accepts as an IP server name. An
+/// unbracketed IPv6 literal has no port and is returned whole.
+fn address_host(address: &str) -> &str {
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, _)) = rest.split_once(']') {
+            return host;
+        }
+    }
+    match address.split_once(':') {
+        Some((host, port)) if !port.contains(':') => host,
+        _ => address,
+    }
+}
+
 /// Create a channel over a Unix domain socket.
 ///
 /// Handles both plaintext and TLS-over-Unix connections.
@@ -186,13 +612,9 @@ async fn create_unix_channel(config: &ConnectionConfig, socket_path: &str) -> Re
         };
         let tls_connector = tokio_rustls::TlsConnector::from(Arc::new(rustls_config));
 
-        // For server name, use --authority or --servername, default to "localhost"
-        let server_name = config
-            .authority
-            .as_deref()
-            .or(config.servername.as_deref())
-            .unwrap_or("localhost")
-            .to_string();
+        // For server name, use --servername or --authority, default to "localhost"
+        let server_name = tls_server_name(config).unwrap_or("localhost").to_string();
+        let checked_name = (!config.insecure).then(|| server_name.clone());
 
         let channel = endpoint
             .connect_with_connector(service_fn(move |_: http::Uri| {
@@ -210,8 +632,10 @@ async fn create_unix_channel(config: &ConnectionConfig, socket_path: &str) -> Re
             }))
             .await
             .map_err(|e| {
-                GrpcurlError::Other(
-                    format!("failed to connect to Unix socket '{socket_path}': {e}").into(),
+                connect_error(
+                    &format!("Unix socket '{socket_path}'"),
+                    checked_name.as_deref(),
+                    &e,
                 )
             })?;
 
@@ -236,16 +660,13 @@ async fn create_channel_with_rustls(
 
     let tls_connector = tokio_rustls::TlsConnector::from(Arc::new(rustls_config));
 
-    // Extract host for SNI; --authority/--servername overrides
-    let host = address.split(':').next().unwrap_or(address).to_string();
-    let server_name = config
-        .authority
-        .as_deref()
-        .or(config.servername.as_deref())
-        .unwrap_or(&host)
+    // Extract host for SNI; --servername/--authority overrides
+    let server_name = tls_server_name(config)
+        .unwrap_or_else(|| address_host(address))
         .to_string();
 
     let addr = address.to_string();
+    let checked_name = (!config.insecure).then(|| server_name.clone());
 
     let channel = endpoint
         .connect_with_connector(service_fn(move |_: http::Uri| {
@@ -262,7 +683,7 @@ async fn create_channel_with_rustls(
             }
         }))
         .await
-        .map_err(|e| GrpcurlError::Other(format!("failed to connect to {address}: {e}").into()))?;
+        .map_err(|e| connect_error(address, checked_name.as_deref(), &e))?;
 
     Ok(channel)
 }
@@ -277,8 +698,9 @@ async fn create_insecure_channel(config: &ConnectionConfig, address: &str) -> Re
 
 /// Create a channel with standard TLS using a custom rustls connector.
 ///
-/// Used when SSLKEYLOGFILE is set, since tonic's ClientTlsConfig doesn't
-/// expose rustls's key_log field.
+/// Used when SSLKEYLOGFILE is set, SNI is disabled, or hostnames are not
+/// verified, since tonic's ClientTlsConfig doesn't expose rustls's key_log or
+/// enable_sni fields or a custom certificate verifier.
 async fn create_custom_tls_channel(config: &ConnectionConfig, address: &str) -> Result<Channel> {
     let rustls_config = build_standard_rustls_config(config)?;
     create_channel_with_rustls(config, address, rustls_config).await
@@ -305,19 +727,12 @@ fn build_tonic_tls_config(config: &ConnectionConfig) -> Result<ClientTlsConfig>
     }
 
     // Server name override for TLS verification
-    if let Some(ref authority) = config.authority {
-        tls = tls.domain_name(authority.clone());
-    } else if let Some(ref servername) = config.servername {
-        tls = tls.domain_name(servername.clone());
+    if let Some(server_name) = tls_server_name(config) {
+        tls = tls.domain_name(server_name);
     }
 
     // Client certificate for mTLS
-    if let Some(ref cert_path) = config.cert {
-        let key_path = config
-            .key
-            .as_ref()
-            .ok_or_else(|| GrpcurlError::InvalidArgument("--key is required with --cert".into()))?;
-
+    if let Some((cert_path, key_path)) = client_cert_and_key(config)? {
         let cert_pem = std::fs::read(cert_path).map_err(|e| {
             GrpcurlError::Io(std::io::Error::new(
                 e.kind(),
@@ -337,6 +752,23 @@ fn build_tonic_tls_config(config: &ConnectionConfig) -> Result<ClientTlsConfig>
     Ok(tls)
 }
 
+/// The client certificate and key paths for mTLS, if configured.
+///
+/// The CLI already rejects one without the other during argument validation;
+/// this check stays as a backstop for library callers.
+fn client_cert_and_key(config: &ConnectionConfig) -> Result<Option<(&str, &str)>> {
+    match (&config.cert, &config.key) {
+        (Some(cert), Some(key)) => Ok(Some((cert, key))),
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(GrpcurlError::InvalidArgument(
+            "--key is required with --cert".into(),
+        )),
+        (None, Some(_)) => Err(GrpcurlError::InvalidArgument(
+            "--cert is required with --key".into(),
+        )),
+    }
+}
+
 /// Build a rustls ClientConfig that skips all certificate verification.
 ///
 /// This matches Go's `InsecureSkipVerify: true` behavior.
@@ -349,11 +781,7 @@ fn build_insecure_rustls_config(config: &ConnectionConfig) -> Result<rustls::Cli
         .dangerous()
         .with_custom_certificate_verifier(Arc::new(InsecureServerCertVerifier));
 
-    let mut rustls_config = if let Some(ref cert_path) = config.cert {
-        let key_path = config
-            .key
-            .as_ref()
-            .ok_or_else(|| GrpcurlError::InvalidArgument("--key is required with --cert".into()))?;
+    let mut rustls_config = if let Some((cert_path, key_path)) = client_cert_and_key(config)? {
         let certs = load_certs(cert_path)?;
         let key = load_private_key(key_path)?;
         builder.with_client_auth_cert(certs, key).map_err(|e| {
@@ -363,6 +791,7 @@ fn build_insecure_rustls_config(config: &ConnectionConfig) -> Result<rustls::Cli
         builder.with_no_client_auth()
     };
 
+    rustls_config.enable_sni = !config.disable_sni;
     apply_key_log(&mut rustls_config);
     Ok(rustls_config)
 }
@@ -390,16 +819,19 @@ fn build_standard_rustls_config(config: &ConnectionConfig) -> Result<rustls::Cli
         }
     }
 
-    let builder = rustls::ClientConfig::builder_with_provider(provider)
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
         .with_safe_default_protocol_versions()
-        .map_err(|e| GrpcurlError::Other(format!("failed to configure TLS: {e}").into()))?
-        .with_root_certificates(root_store);
+        .map_err(|e| GrpcurlError::Other(format!("failed to configure TLS: {e}").into()))?;
+    let builder = if config.skip_hostname_verify {
+        let verifier = HostnameSkippingVerifier::new(root_store, provider)?;
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+    } else {
+        builder.with_root_certificates(root_store)
+    };
 
-    let mut rustls_config = if let Some(ref cert_path) = config.cert {
-        let key_path = config
-            .key
-            .as_ref()
-            .ok_or_else(|| GrpcurlError::InvalidArgument("--key is required with --cert".into()))?;
+    let mut rustls_config = if let Some((cert_path, key_path)) = client_cert_and_key(config)? {
         let certs = load_certs(cert_path)?;
         let key = load_private_key(key_path)?;
         builder.with_client_auth_cert(certs, key).map_err(|e| {
@@ -409,6 +841,7 @@ fn build_standard_rustls_config(config: &ConnectionConfig) -> Result<rustls::Cli
         builder.with_no_client_auth()
     };
 
+    rustls_config.enable_sni = !config.disable_sni;
     apply_key_log(&mut rustls_config);
     Ok(rustls_config)
 }
@@ -502,6 +935,79 @@ impl rustls::client::danger::ServerCertVerifier for InsecureServerCertVerifier {
     }
 }
 
+// -- Hostname-Skipping TLS Verifier -------------------------------------------
+
+/// A certificate verifier that validates the server's certificate chain
+/// against the root store, like the default verifier, but accepts a
+/// certificate that does not cover the server name.
+///
+/// Used with --skip-hostname-verify, e.g. for servers reached by IP address.
+#[derive(Debug)]
+struct HostnameSkippingVerifier(Arc<rustls::client::WebPkiServerVerifier>);
+
+impl HostnameSkippingVerifier {
+    fn new(
+        root_store: rustls::RootCertStore,
+        provider: Arc<rustls::crypto::CryptoProvider>,
+    ) -> Result<Self> {
+        let verifier = rustls::client::WebPkiServerVerifier::builder_with_provider(
+            Arc::new(root_store),
+            provider,
+        )
+        .build()
+        .map_err(|e| {
+            GrpcurlError::Other(format!("failed to configure TLS verifier: {e}").into())
+        })?;
+        Ok(HostnameSkippingVerifier(verifier))
+    }
+}
+
+impl rustls::client::danger::ServerCertVerifier for HostnameSkippingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::pki_types::CertificateDer<'_>,
+        intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        server_name: &rustls::pki_types::ServerName<'_>,
+        ocsp: &[u8],
+        now: rustls::pki_types::UnixTime,
+    ) -> std::result::Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        // The name is only checked once the chain has been verified, so a
+        // name mismatch means the chain itself is trusted
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                rustls::CertificateError::NotValidForName
+                | rustls::CertificateError::NotValidForNameContext { .. },
+            )) => Ok(rustls::client::danger::ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
 /// Build the User-Agent string.
 ///
 /// Format: "grpcurl/<version>" prepended with custom user-agent if specified.
@@ -526,6 +1032,138 @@ mod tests {
         config
     }
 
+    #[test]
+    fn unix_socket_path_schemes() {
+        assert_eq!(
+            unix_socket_path("unix:///var/run/app.sock"),
+            Some("/var/run/app.sock")
+        );
+        assert_eq!(
+            unix_socket_path("unix:/var/run/app.sock"),
+            Some("/var/run/app.sock")
+        );
+        assert_eq!(unix_socket_path("unix:app.sock"), Some("app.sock"));
+        assert_eq!(unix_socket_path("localhost:50051"), None);
+        assert_eq!(unix_socket_path("/var/run/app.sock"), None);
+    }
+
+    #[test]
+    fn target_address_schemes() {
+        assert_eq!(
+            target_address("localhost:50051").unwrap(),
+            "localhost:50051"
+        );
+        assert_eq!(target_address("[::1]:50051").unwrap(), "[::1]:50051");
+        assert_eq!(
+            target_address("dns:///example.com:443").unwrap(),
+            "example.com:443"
+        );
+        assert_eq!(
+            target_address("dns://8.8.8.8/example.com:443").unwrap(),
+            "example.com:443"
+        );
+        assert_eq!(
+            target_address("dns:example.com:443").unwrap(),
+            "example.com:443"
+        );
+        assert_eq!(
+            target_address("ipv4:10.0.0.1:50051").unwrap(),
+            "10.0.0.1:50051"
+        );
+        assert_eq!(target_address("ipv6:[::1]:50051").unwrap(), "[::1]:50051");
+        assert_eq!(
+            target_address("unix:///var/run/app.sock").unwrap(),
+            "unix:///var/run/app.sock"
+        );
+
+        assert_eq!(target_address("unix:app.sock").unwrap(), "unix:app.sock");
+
+        let err = target_address("unix://run/app.sock").unwrap_err();
+        assert!(err.to_string().contains("use unix:run/app.sock"), "{err}");
+        assert!(target_address("dns:///").is_err());
+        assert!(target_address("dns://example.com:443").is_err());
+        assert!(target_address("ipv4:10.0.0.1:1,10.0.0.2:1").is_err());
+        let err = target_address("xds:///my-service").unwrap_err();
+        assert!(err.to_string().contains("\"xds\""), "{err}");
+    }
+
+    #[test]
+    fn address_host_handles_ipv6_literals() {
+        assert_eq!(address_host("host:50051"), "host");
+        assert_eq!(address_host("host"), "host");
+        assert_eq!(address_host("127.0.0.1:50051"), "127.0.0.1");
+        assert_eq!(address_host("[::1]:50051"), "::1");
+        assert_eq!(address_host("[2001:db8::1]:50051"), "2001:db8::1");
+        assert_eq!(address_host("2001:db8::1"), "2001:db8::1");
+
+        // Both forms make a valid rustls server name
+        for address in ["[::1]:50051", "host:50051"] {
+            let host = address_host(address);
+            assert!(
+                rustls::pki_types::ServerName::try_from(host).is_ok(),
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn connect_error_explains_rejected_certificates() {
+        let wrong_name = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            rustls::Error::InvalidCertificate(rustls::CertificateError::NotValidForName),
+        );
+        let message = connect_error("host:443", Some("other.example"), &wrong_name).to_string();
+        assert!(
+            message.contains("server certificate rejected for server name \"other.example\""),
+            "{message}"
+        );
+        assert!(message.contains("--servername"), "{message}");
+        assert!(message.contains("--insecure"), "{message}");
+
+        let unknown_ca = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer),
+        );
+        let message = connect_error("host:443", Some("host"), &unknown_ca).to_string();
+        assert!(message.contains("--cacert"), "{message}");
+
+        // Without TLS verification, or for other failures, only the error is shown
+        let message = connect_error("host:443", None, &unknown_ca).to_string();
+        assert!(!message.contains("rejected"), "{message}");
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        let message = connect_error("host:443", Some("host"), &refused).to_string();
+        assert!(!message.contains("rejected"), "{message}");
+    }
+
+    #[test]
+    fn tls_server_name_precedence() {
+        assert_eq!(tls_server_name(&ConnectionConfig::default()), None);
+
+        let config = make_config(|c| c.authority = Some("api.example.com".into()));
+        assert_eq!(tls_server_name(&config), Some("api.example.com"));
+
+        // --servername wins for TLS when both are set
+        let config = make_config(|c| {
+            c.authority = Some("api.example.com".into());
+            c.servername = Some("tls.example.com".into());
+        });
+        assert_eq!(tls_server_name(&config), Some("tls.example.com"));
+    }
+
+    #[test]
+    fn authority_origin_keeps_scheme() {
+        let uri: http::Uri = "https://10.0.0.1:443".parse().unwrap();
+        let origin = authority_origin(&uri, "api.example.com").unwrap();
+        assert_eq!(origin.to_string(), "https://api.example.com/");
+        assert_eq!(origin.authority().unwrap().as_str(), "api.example.com");
+
+        let uri: http::Uri = "http://[::]:0".parse().unwrap();
+        let origin = authority_origin(&uri, "svc.local:8080").unwrap();
+        assert_eq!(origin.authority().unwrap().as_str(), "svc.local:8080");
+
+        assert!(authority_origin(&uri, "bad authority").is_err());
+    }
+
     #[test]
     fn user_agent_default() {
         let config = ConnectionConfig::default();
@@ -568,6 +1206,17 @@ mod tests {
         assert!(tls.is_err());
     }
 
+    #[test]
+    fn tls_config_requires_cert_and_key_together() {
+        let config = make_config(|c| c.cert = Some("client.pem".to_string()));
+        let err = build_tonic_tls_config(&config).unwrap_err();
+        assert!(err.to_string().contains("--key is required with --cert"));
+
+        let config = make_config(|c| c.key = Some("client.key".to_string()));
+        let err = build_standard_rustls_config(&config).unwrap_err();
+        assert!(err.to_string().contains("--cert is required with --key"));
+    }
+
     #[test]
     fn insecure_rustls_config_builds_successfully() {
         let config = make_config(|c| {
@@ -583,4 +1232,227 @@ mod tests {
         let result = build_standard_rustls_config(&config);
         assert!(result.is_ok());
     }
+
+    /// A test CA, and a certificate it issued for `server.test` only.
+    const TEST_CA_PEM: &str = "\
+-----BEGIN CERTIFICATE-----\n\
+MIIBmjCCAUGgAwIBAgIUDloEV+pGcXATsJIH9exorbcNHmswCgYIKoZIzj0EAwIw\n\
+GjEYMBYGA1UEAwwPZ3JwY3VybCB0ZXN0IENBMCAXDTI2MTAxNjE0NTg0MVoYDzIx\n\
+MjYwOTIyMTQ1ODQxWjAaMRgwFgYDVQQDDA9ncnBjdXJsIHRlc3QgQ0EwWTATBgcq\n\
+hkjOPQIBBggqhkjOPQMBBwNCAAQcqouU3LmH2hOD+8KsaufRYX3fvIh13FeAFUnC\n\
+jrvTgMCM0nX8/xFXdQiRxU7LIWadZm/9VIA5eSQJ20+4zphzo2MwYTAdBgNVHQ4E\n\
+FgQUCWBfvtSvDRWMa8ODstkWpZHCDG4wHwYDVR0jBBgwFoAUCWBfvtSvDRWMa8OD\n\
+stkWpZHCDG4wDwYDVR0TAQH/BAUwAwEB/zAOBgNVHQ8BAf8EBAMCAgQwCgYIKoZI\n\
+zj0EAwIDRwAwRAIga5apaEJOasfRwddANGWe10QrvSo4pA+HwtANhMFM4o4CICp2\n\
+BEc8SSP331F7euhVCOYq2i070fztov2R7igz6FQH\n\
+-----END CERTIFICATE-----\n\
+";
+    const TEST_LEAF_PEM: &str = "\
+-----BEGIN CERTIFICATE-----\n\
+MIIBrzCCAVSgAwIBAgIUOwxfqGcKkBkxMLj2IffzpZLlp8EwCgYIKoZIzj0EAwIw\n\
+GjEYMBYGA1UEAwwPZ3JwY3VybCB0ZXN0IENBMCAXDTI2MTAxNjE0NTg0MVoYDzIx\n\
+MjYwOTIyMTQ1ODQxWjAWMRQwEgYDVQQDDAtzZXJ2ZXIudGVzdDBZMBMGByqGSM49\n\
+AgEGCCqGSM49AwEHA0IABBMV0vlN/foIfKKlWPgYuEyPZuqmgCpcHGSGOapFtA2j\n\
+Hum0x0RiXg6Wg9bcQGbLMRdIzy8dYUlH6V6rSw3UPVajejB4MBYGA1UdEQQPMA2C\n\
+C3NlcnZlci50ZXN0MAkGA1UdEwQCMAAwEwYDVR0lBAwwCgYIKwYBBQUHAwEwHQYD\n\
+VR0OBBYEFDPxHqoXV/Qu8PNI1IzGwEaJqz/nMB8GA1UdIwQYMBaAFAlgX77Urw0V\n\
+jGvDg7LZFqWRwgxuMAoGCCqGSM49BAMCA0kAMEYCIQCACG5kP8tbTlwZSIvPiL1O\n\
+cnA3Yxbk0lXxSNRyWouMUQIhAIJwQ4TbU18PtuFJ7Ez2qMcSK8PFwb+OOxnQNQvl\n\
+UjFI\n\
+-----END CERTIFICATE-----\n\
+";
+
+    #[test]
+    fn hostname_skipping_verifier_checks_chain_only() {
+        use rustls::client::danger::ServerCertVerifier;
+        use rustls::pki_types::{ServerName, UnixTime};
+
+        let mut root_store = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut TEST_CA_PEM.as_bytes()) {
+            root_store.add(cert.unwrap()).unwrap();
+        }
+        let leaf = rustls_pemfile::certs(&mut TEST_LEAF_PEM.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier = HostnameSkippingVerifier::new(root_store, provider).unwrap();
+        let verify = |name: &str, now| {
+            let name = ServerName::try_from(name.to_string()).unwrap();
+            verifier.verify_server_cert(&leaf, &[], &name, &[], now)
+        };
+
+        assert!(verify("server.test", UnixTime::now()).is_ok());
+        assert!(verify("other.test", UnixTime::now()).is_ok());
+        assert!(verify("10.0.0.1", UnixTime::now()).is_ok());
+        // The default verifier rejects the name the wrapper accepts
+        let name = ServerName::try_from("other.test").unwrap();
+        assert!(verifier
+            .0
+            .verify_server_cert(&leaf, &[], &name, &[], UnixTime::now())
+            .is_err());
+        // Chain errors still fail, e.g. a certificate that is not yet valid
+        let epoch = UnixTime::since_unix_epoch(Duration::from_secs(0));
+        assert!(verify("server.test", epoch).is_err());
+    }
+
+    #[tokio::test]
+    async fn plaintext_fallback_skips_rejected_certificates() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // A TLS server whose certificate is issued by a CA nobody trusts
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../testing/tls");
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let server_config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(
+                load_certs(&format!("{dir}/server.crt")).unwrap(),
+                load_private_key(&format!("{dir}/server.key")).unwrap(),
+            )
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server_config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let _ = acceptor.accept(stream).await;
+                });
+            }
+        });
+
+        let config = make_config(|c| c.plaintext_fallback = true);
+        let err = connect_with_fallback(&config, &address).await.unwrap_err();
+        assert!(
+            err.to_string().contains("server certificate rejected"),
+            "{err}"
+        );
+        // The TLS attempt only; no second connection in plaintext
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn rustls_config_disable_sni() {
+        let config = ConnectionConfig::default();
+        assert!(build_standard_rustls_config(&config).unwrap().enable_sni);
+
+        let config = make_config(|c| c.disable_sni = true);
+        assert!(!build_standard_rustls_config(&config).unwrap().enable_sni);
+        let config = make_config(|c| {
+            c.disable_sni = true;
+            c.insecure = true;
+        });
+        assert!(!build_insecure_rustls_config(&config).unwrap().enable_sni);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_stops_at_deadline() {
+        let start = Instant::now();
+        let mut backoff = Backoff::new(Duration::from_millis(200), BackoffPolicy::default());
+        let mut attempts = 0;
+        while backoff.wait().await {
+            attempts += 1;
+        }
+        // Sleeps of 50-60ms and 100-120ms, then the third is cut to the deadline
+        assert_eq!(attempts, 3);
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_stops_at_earlier_budget() {
+        let start = Instant::now();
+        let budget = start.into_std() + Duration::from_millis(120);
+        let mut backoff =
+            Backoff::new(Duration::from_secs(10), BackoffPolicy::default()).until(Some(budget));
+        while backoff.wait().await {}
+        assert_eq!(start.elapsed(), Duration::from_millis(120));
+
+        // A later budget leaves the timeout in charge
+        let start = Instant::now();
+        let budget = start.into_std() + Duration::from_secs(10);
+        let mut backoff =
+            Backoff::new(Duration::from_millis(200), BackoffPolicy::default()).until(Some(budget));
+        while backoff.wait().await {}
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+    }
+
+    /// Sleep through `n` attempts, returning each delay in milliseconds.
+    async fn backoff_delays(policy: BackoffPolicy, n: usize) -> Vec<u128> {
+        let mut backoff = Backoff::new(Duration::from_secs(60), policy);
+        let mut delays = Vec::new();
+        for _ in 0..n {
+            let before = Instant::now();
+            assert!(backoff.wait().await);
+            delays.push(before.elapsed().as_millis());
+        }
+        delays
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_delay_is_capped() {
+        let delays = backoff_delays(BackoffPolicy::default(), 7).await;
+        let expected = [50, 100, 200, 400, 800, 1000, 1000];
+        for (delay, base) in delays.iter().zip(expected) {
+            assert!(
+                (base..=base * 6 / 5).contains(delay),
+                "delays {delays:?} not within 20% above {expected:?}"
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_policy_is_configurable() {
+        let config = make_config(|c| {
+            c.retry_base = Some(0.5);
+            c.retry_max = Some(1.5);
+        });
+        let policy = backoff_policy(&config);
+        assert_eq!(policy.base, Duration::from_millis(500));
+        assert_eq!(policy.max, Duration::from_millis(1500));
+
+        let delays = backoff_delays(policy, 4).await;
+        let expected = [500, 1000, 1500, 1500];
+        for (delay, base) in delays.iter().zip(expected) {
+            assert!((base..=base * 6 / 5).contains(delay), "delays {delays:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn default_backoff_cap_is_not_below_base() {
+        let config = make_config(|c| c.retry_base = Some(5.0));
+        let policy = backoff_policy(&config);
+        assert_eq!(policy.base, Duration::from_secs(5));
+        assert_eq!(policy.max, Duration::from_secs(5));
+
+        let delays = backoff_delays(policy, 2).await;
+        assert!(delays.iter().all(|&d| d >= 5000), "delays {delays:?}");
+    }
+
+    #[test]
+    fn invalid_durations_fall_back_to_defaults() {
+        let config = make_config(|c| {
+            c.connect_timeout = Some(f64::NAN);
+            c.retry_base = Some(-1.0);
+            c.retry_max = Some(f64::INFINITY);
+        });
+        assert_eq!(connect_timeout(&config), Duration::from_secs(10));
+        assert_eq!(backoff_policy(&config), BackoffPolicy::default());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_adds_jitter() {
+        // Without jitter, every capped delay would be exactly 100ms
+        let policy = BackoffPolicy {
+            base: Duration::from_millis(100),
+            max: Duration::from_millis(100),
+        };
+        let delays = backoff_delays(policy, 20).await;
+        assert!(delays.iter().any(|&d| d != 100), "delays {delays:?}");
+    }
 }
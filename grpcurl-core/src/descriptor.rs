@@ -1,12 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use prost::Message;
 use prost_reflect::{DescriptorPool, ExtensionDescriptor, FieldDescriptor, MessageDescriptor};
 
 use crate::error::{GrpcurlError, Result};
+use crate::reflection::ReflectionVersion;
 
 /// Abstraction over different sources of protobuf descriptors.
 ///
@@ -43,8 +46,8 @@ pub trait DescriptorSource: Send + Sync {
     /// Return all file descriptors known to this source.
     ///
     /// Equivalent to Go's `sourceWithFiles.GetAllFiles()`. Not all sources
-    /// support this (server reflection does not), so the default returns
-    /// an error.
+    /// support this, so the default returns an error. Server reflection
+    /// resolves every listed service to discover its files.
     async fn get_all_files(&self) -> Result<Vec<prost_types::FileDescriptorProto>> {
         Err(GrpcurlError::Other(
             "this descriptor source does not support listing all files".into(),
@@ -58,6 +61,23 @@ pub trait DescriptorSource: Send + Sync {
     fn descriptor_pool(&self) -> Option<&DescriptorPool> {
         None
     }
+
+    /// Return a copy of the descriptor pool as it is now, if available.
+    ///
+    /// Unlike `descriptor_pool()`, this also works for sources that build
+    /// their pool up behind a lock, like server reflection, where it holds
+    /// every file fetched so far. Defaults to cloning `descriptor_pool()`.
+    fn descriptor_pool_snapshot(&self) -> Option<DescriptorPool> {
+        self.descriptor_pool().cloned()
+    }
+
+    /// Return the server reflection API version in use, if any.
+    ///
+    /// `None` for sources that do not use reflection, and for reflection
+    /// sources that have not completed a request yet.
+    fn reflection_version(&self) -> Option<ReflectionVersion> {
+        None
+    }
 }
 
 /// A resolved protobuf symbol descriptor.
@@ -194,6 +214,60 @@ pub async fn list_methods(source: &dyn DescriptorSource, service: &str) -> Resul
     Ok(methods)
 }
 
+/// List all message and enum types known to a source, sorted, optionally
+/// restricted to a package (and its subpackages).
+///
+/// Every service is resolved first, which makes reflection sources fetch its
+/// files and their dependencies. Then the source's own pool is walked once,
+/// along with the pool behind any service it does not hold, such as one a
+/// composite source found through reflection. Synthetic map entry messages
+/// are skipped.
+pub async fn list_types(
+    source: &dyn DescriptorSource,
+    package: Option<&str>,
+) -> Result<Vec<String>> {
+    let mut services = Vec::new();
+    for service in source.list_services().await? {
+        // Services that fail to resolve simply contribute no types
+        if let Ok(SymbolDescriptor::Service(svc)) = source.find_symbol(&service).await {
+            services.push(svc);
+        }
+    }
+
+    let mut pools: Vec<DescriptorPool> = source.descriptor_pool_snapshot().into_iter().collect();
+    // A reflection pool only grows, so the last service resolved has the
+    // largest one; going backwards, earlier services are usually covered
+    for svc in services.iter().rev() {
+        let file = svc.parent_file();
+        if !pools
+            .iter()
+            .any(|pool| pool.get_file_by_name(file.name()).is_some())
+        {
+            pools.push(svc.parent_pool().clone());
+        }
+    }
+
+    let in_package = |name: &str| match package {
+        Some(pkg) => {
+            name == pkg
+                || name
+                    .strip_prefix(pkg)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        }
+        None => true,
+    };
+    let mut types = std::collections::BTreeSet::new();
+    for pool in &pools {
+        let messages = pool
+            .all_messages()
+            .filter(|m| !m.is_map_entry())
+            .map(|m| m.full_name().to_string());
+        let enums = pool.all_enums().map(|e| e.full_name().to_string());
+        types.extend(messages.chain(enums).filter(|name| in_package(name)));
+    }
+    Ok(types.into_iter().collect())
+}
+
 /// Retrieve all file descriptors from a source, with fallback.
 ///
 /// Equivalent to Go's `GetAllFiles()`. Tries `get_all_files()` first
@@ -209,7 +283,7 @@ pub async fn get_all_files(
     }
 
     // Fallback: iterate services, collect file descriptors
-    let pool = source.descriptor_pool().ok_or_else(|| {
+    let pool = source.descriptor_pool_snapshot().ok_or_else(|| {
         GrpcurlError::Other("cannot retrieve files: no descriptor pool available".into())
     })?;
 
@@ -220,14 +294,22 @@ pub async fn get_all_files(
     Ok(files)
 }
 
+/// Default limit on how deep a chain of imports `write_protoset` and
+/// `write_proto_files` follow from a symbol's file.
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
 /// Write a FileDescriptorSet containing the descriptors for the given symbols
 /// and their transitive dependencies to the specified file.
 ///
+/// Imports are followed at most `max_depth` levels deep from each symbol's
+/// file; a deeper chain is an error naming the file where it stopped.
+///
 /// Equivalent to Go's `WriteProtoset()` in desc_source.go.
 pub async fn write_protoset(
     path: &str,
     source: &dyn DescriptorSource,
     symbols: &[String],
+    max_depth: usize,
 ) -> Result<()> {
     if symbols.is_empty() {
         return Ok(());
@@ -248,6 +330,8 @@ pub async fn write_protoset(
         }
     }
 
+    check_depth(file_names.iter().map(|name| &files[name]), max_depth)?;
+
     // Expand to include transitive dependencies (topologically sorted:
     // each file appears after all its dependencies)
     let mut expanded = HashSet::new();
@@ -273,17 +357,16 @@ pub async fn write_protoset(
 /// Recursively collect a file descriptor and its dependencies.
 ///
 /// Dependencies are added before the file itself (topological order),
-/// matching Go's `addFilesToSet()`.
+/// matching Go's `addFilesToSet()`. Files already in `expanded` are skipped,
+/// so cycles end there.
 fn collect_transitive_deps(
     all_files: &mut Vec<prost_types::FileDescriptorProto>,
     expanded: &mut HashSet<String>,
     fd: &prost_reflect::FileDescriptor,
 ) {
-    let name = fd.name().to_string();
-    if expanded.contains(&name) {
+    if !expanded.insert(fd.name().to_string()) {
         return;
     }
-    expanded.insert(name);
 
     // Add all dependencies first
     for dep in fd.dependencies() {
@@ -302,11 +385,9 @@ fn collect_transitive_file_descriptors(
     expanded: &mut HashSet<String>,
     fd: &prost_reflect::FileDescriptor,
 ) {
-    let name = fd.name().to_string();
-    if expanded.contains(&name) {
+    if !expanded.insert(fd.name().to_string()) {
         return;
     }
-    expanded.insert(name);
 
     for dep in fd.dependencies() {
         collect_transitive_file_descriptors(all_files, expanded, &dep);
@@ -315,16 +396,77 @@ fn collect_transitive_file_descriptors(
     all_files.push(fd.clone());
 }
 
+/// Fail if any file imported from `roots` is more than `max_depth` imports
+/// away from the nearest of them, naming the first such file.
+///
+/// Files are visited breadth-first, so each is measured along its shortest
+/// chain of imports, whatever order the imports are listed in.
+fn check_depth<'a>(
+    roots: impl IntoIterator<Item = &'a prost_reflect::FileDescriptor>,
+    max_depth: usize,
+) -> Result<()> {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    for fd in roots {
+        if seen.insert(fd.name().to_string()) {
+            queue.push_back((fd.clone(), 0));
+        }
+    }
+    while let Some((fd, depth)) = queue.pop_front() {
+        for dep in fd.dependencies() {
+            if !seen.insert(dep.name().to_string()) {
+                continue;
+            }
+            if depth + 1 > max_depth {
+                return Err(GrpcurlError::InvalidArgument(format!(
+                    "dependency depth limit of {max_depth} exceeded at file '{}'",
+                    dep.name()
+                )));
+            }
+            queue.push_back((dep, depth + 1));
+        }
+    }
+    Ok(())
+}
+
 /// Write .proto source files for the given symbols and their transitive
 /// dependencies to the specified directory.
 ///
 /// Matches Go's `WriteProtoFiles()` in desc_source.go.
 /// Each file is named using its proto file name (e.g., "google/protobuf/empty.proto")
-/// and nested directories are created as needed.
+/// and nested directories are created as needed. Leading comments from the
+/// descriptors' source info are kept; see [`write_proto_files_without_comments`].
+/// Imports are followed at most `max_depth` levels deep, as in `write_protoset`.
 pub async fn write_proto_files(
     dir: &str,
     source: &dyn DescriptorSource,
     symbols: &[String],
+    max_depth: usize,
+) -> Result<()> {
+    let format = crate::descriptor_text::format_proto_file;
+    write_proto_files_with(dir, source, symbols, max_depth, format).await
+}
+
+/// Write .proto source files like [`write_proto_files`], but leave all
+/// comments out, even when the descriptors carry source info.
+pub async fn write_proto_files_without_comments(
+    dir: &str,
+    source: &dyn DescriptorSource,
+    symbols: &[String],
+    max_depth: usize,
+) -> Result<()> {
+    let format = crate::descriptor_text::format_proto_file_without_comments;
+    write_proto_files_with(dir, source, symbols, max_depth, format).await
+}
+
+/// Write the files for `symbols` and their dependencies, rendering each with
+/// `format`.
+async fn write_proto_files_with(
+    dir: &str,
+    source: &dyn DescriptorSource,
+    symbols: &[String],
+    max_depth: usize,
+    format: fn(&prost_reflect::FileDescriptor) -> String,
 ) -> Result<()> {
     if symbols.is_empty() {
         return Ok(());
@@ -345,6 +487,8 @@ pub async fn write_proto_files(
         }
     }
 
+    check_depth(file_names.iter().map(|name| &files[name]), max_depth)?;
+
     // Expand to include transitive dependencies (topologically sorted)
     let mut expanded = HashSet::new();
     let mut all_files: Vec<prost_reflect::FileDescriptor> = Vec::new();
@@ -365,8 +509,7 @@ pub async fn write_proto_files(
                 ))
             })?;
         }
-        let content = crate::descriptor_text::format_proto_file(fd);
-        fs::write(&out_path, content).map_err(|e| {
+        fs::write(&out_path, format(fd)).map_err(|e| {
             GrpcurlError::Io(std::io::Error::new(
                 e.kind(),
                 format!("failed to write proto file '{}': {e}", out_path.display()),
@@ -430,19 +573,72 @@ impl DescriptorSource for FileSource {
 
 // -- CompositeSource implementation -------------------------------------------
 
+/// Which source a `CompositeSource` consults first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourcePreference {
+    /// Server reflection first, as Go grpcurl does.
+    #[default]
+    Reflection,
+    /// The local proto or protoset files first.
+    File,
+}
+
+impl FromStr for SourcePreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "reflection" => Ok(SourcePreference::Reflection),
+            "file" => Ok(SourcePreference::File),
+            other => Err(format!(
+                "The --prefer option must be 'file' or 'reflection', got '{other}'."
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SourcePreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourcePreference::Reflection => write!(f, "reflection"),
+            SourcePreference::File => write!(f, "file"),
+        }
+    }
+}
+
 /// Descriptor source combining server reflection with a file-based fallback.
 ///
 /// Equivalent to Go's `compositeSource` (cmd/grpcurl/grpcurl.go:248-287).
-/// Uses reflection as the primary source for listing services, and falls
-/// back to the file source for symbol resolution when reflection fails.
+/// Uses reflection as the primary source for listing services. Symbols are
+/// resolved from the preferred source, reflection by default, falling back
+/// to the other when it fails.
 pub struct CompositeSource {
     reflection: Box<dyn DescriptorSource>,
     file: Box<dyn DescriptorSource>,
+    prefer: SourcePreference,
 }
 
 impl CompositeSource {
     pub fn new(reflection: Box<dyn DescriptorSource>, file: Box<dyn DescriptorSource>) -> Self {
-        CompositeSource { reflection, file }
+        CompositeSource {
+            reflection,
+            file,
+            prefer: SourcePreference::default(),
+        }
+    }
+
+    /// Set which source symbols and extensions are looked up in first.
+    pub fn with_preference(mut self, prefer: SourcePreference) -> Self {
+        self.prefer = prefer;
+        self
+    }
+
+    /// The preferred source, then the fallback.
+    fn ordered(&self) -> (&dyn DescriptorSource, &dyn DescriptorSource) {
+        match self.prefer {
+            SourcePreference::Reflection => (self.reflection.as_ref(), self.file.as_ref()),
+            SourcePreference::File => (self.file.as_ref(), self.reflection.as_ref()),
+        }
     }
 }
 
@@ -454,25 +650,28 @@ impl DescriptorSource for CompositeSource {
     }
 
     async fn find_symbol(&self, fully_qualified_name: &str) -> Result<SymbolDescriptor> {
-        // Try reflection first, fall back to file source
-        match self.reflection.find_symbol(fully_qualified_name).await {
+        // Try the preferred source first, fall back to the other
+        let (primary, fallback) = self.ordered();
+        match primary.find_symbol(fully_qualified_name).await {
             Ok(desc) => Ok(desc),
-            Err(_) => self.file.find_symbol(fully_qualified_name).await,
+            Err(_) => fallback.find_symbol(fully_qualified_name).await,
         }
     }
 
     async fn all_extensions_for_type(&self, type_name: &str) -> Result<Vec<ExtensionDescriptor>> {
-        // Try reflection first
-        match self.reflection.all_extensions_for_type(type_name).await {
-            Ok(ref_exts) => {
-                // Merge with file source extensions (reflection takes priority)
+        // Try the preferred source first
+        let (primary, fallback) = self.ordered();
+        match primary.all_extensions_for_type(type_name).await {
+            Ok(primary_exts) => {
+                // Merge with the fallback's extensions (the preferred source
+                // takes priority)
                 let mut tags: HashSet<u32> = HashSet::new();
-                for ext in &ref_exts {
+                for ext in &primary_exts {
                     tags.insert(ext.number());
                 }
-                let mut all_exts = ref_exts;
-                if let Ok(file_exts) = self.file.all_extensions_for_type(type_name).await {
-                    for ext in file_exts {
+                let mut all_exts = primary_exts;
+                if let Ok(fallback_exts) = fallback.all_extensions_for_type(type_name).await {
+                    for ext in fallback_exts {
                         if !tags.contains(&ext.number()) {
                             all_exts.push(ext);
                         }
@@ -480,9 +679,18 @@ impl DescriptorSource for CompositeSource {
                 }
                 Ok(all_exts)
             }
-            Err(_) => self.file.all_extensions_for_type(type_name).await,
+            Err(_) => fallback.all_extensions_for_type(type_name).await,
         }
     }
+
+    fn descriptor_pool(&self) -> Option<&DescriptorPool> {
+        // Reflection cannot lend out its pool, so expose the file source's
+        self.file.descriptor_pool()
+    }
+
+    fn reflection_version(&self) -> Option<ReflectionVersion> {
+        self.reflection.reflection_version()
+    }
 }
 
 // -- Factory functions --------------------------------------------------------
@@ -490,32 +698,172 @@ impl DescriptorSource for CompositeSource {
 /// Create a descriptor source from one or more protoset files.
 ///
 /// Each file must contain a binary-encoded `FileDescriptorSet` (as produced by
-/// `protoc --descriptor_set_out`).
+/// `protoc --descriptor_set_out`). Protosets commonly bundle the same
+/// dependencies, so files are deduplicated by name across all inputs, keeping
+/// the first occurrence. A warning is printed if a later copy differs.
+/// Imported well-known type files (`google/protobuf/*.proto`) that no
+/// protoset includes are filled in from bundled copies.
+///
+/// A path of `-` or `@` reads the protoset from stdin, e.g. from
+/// `buf build -o -`.
 ///
 /// Equivalent to Go's `DescriptorSourceFromProtoSets()`.
 pub fn descriptor_source_from_protosets(paths: &[String]) -> Result<FileSource> {
-    let mut pool = DescriptorPool::new();
+    let mut merged = MergedFiles::default();
+    add_protosets(&mut merged, paths)?;
+    merged.into_source("protoset files")
+}
+
+/// Create a descriptor source from protoset files and .proto source files
+/// together, so compiled and source schemas can be mixed.
+///
+/// All protosets are loaded first, in the order given, and then the compiled
+/// proto sources. Files are merged by name: the first copy of a file wins,
+/// so a protoset's copy takes precedence over a source file with the same
+/// name. A warning is printed when a later copy differs.
+pub fn descriptor_source_from_protosets_and_proto_files(
+    protosets: &[String],
+    import_paths: &[String],
+    proto_files: &[String],
+) -> Result<FileSource> {
+    let mut merged = MergedFiles::default();
+    add_protosets(&mut merged, protosets)?;
+
+    // Source info is kept for comments; `MergedFiles` ignores it when
+    // comparing, as protoc leaves it out of protosets by default
+    let compiler = compile_proto_files(import_paths, proto_files, true)?;
+    let fds = EncodedFileDescriptorSet::decode(compiler.encode_file_descriptor_set().as_slice())
+        .map_err(|e| GrpcurlError::Proto(format!("failed to encode proto files: {e}")))?;
+    for file_bytes in fds.file {
+        merged.add(file_bytes, "proto sources")?;
+    }
+
+    merged.into_source("protoset and proto files")
+}
+
+/// Files collected from several descriptor inputs, deduplicated by name.
+#[derive(Default)]
+struct MergedFiles {
+    files: Vec<prost_types::FileDescriptorProto>,
+    /// Encoded copies of `files`, which keep any custom options
+    encoded: EncodedFileDescriptorSet,
+    /// File name -> (index into `files`, input it came from)
+    seen: HashMap<String, (usize, String)>,
+}
+
+impl MergedFiles {
+    /// Add one encoded `FileDescriptorProto` from the input named `origin`,
+    /// keeping the first copy of each file name. Copies that differ only in
+    /// source info are the same file; the first one that has source info is
+    /// kept, so comments survive.
+    fn add(&mut self, file_bytes: Vec<u8>, origin: &str) -> Result<()> {
+        let file = prost_types::FileDescriptorProto::decode(file_bytes.as_slice())
+            .map_err(|e| GrpcurlError::Proto(format!("failed to decode {origin}: {e}")))?;
+        match self.seen.get(file.name()) {
+            Some(&(idx, ref first_origin)) => {
+                let first = &self.files[idx];
+                let same = prost_types::FileDescriptorProto {
+                    source_code_info: None,
+                    ..first.clone()
+                } == prost_types::FileDescriptorProto {
+                    source_code_info: None,
+                    ..file.clone()
+                };
+                if same {
+                    if first.source_code_info.is_none() && file.source_code_info.is_some() {
+                        self.files[idx] = file;
+                        self.encoded.file[idx] = file_bytes;
+                    }
+                } else {
+                    eprintln!(
+                        "warning: file {:?} in {origin} differs from the one in \
+                         {first_origin}; using the first",
+                        file.name()
+                    );
+                }
+            }
+            None => {
+                self.seen
+                    .insert(file.name().to_string(), (self.files.len(), origin.into()));
+                self.files.push(file);
+                self.encoded.file.push(file_bytes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add bundled copies of the well-known type files, such as
+    /// `google/protobuf/timestamp.proto`, that the files import but no input
+    /// supplied. protoc leaves imports out of protosets unless run with
+    /// `--include_imports`.
+    fn add_missing_well_known(&mut self) -> Result<()> {
+        // Files added here are visited in turn, for their own imports
+        let mut next = 0;
+        while next < self.files.len() {
+            for dep in self.files[next].dependency.clone() {
+                if self.seen.contains_key(&dep) {
+                    continue;
+                }
+                if let Some(bytes) = well_known_file_descriptor(&dep) {
+                    self.add(bytes, "bundled well-known types")?;
+                }
+            }
+            next += 1;
+        }
+        Ok(())
+    }
+
+    fn into_source(mut self, inputs: &str) -> Result<FileSource> {
+        self.add_missing_well_known()?;
+        let mut pool = DescriptorPool::new();
+        pool.decode_file_descriptor_set(self.encoded.encode_to_vec().as_slice())
+            .map_err(|e| {
+                GrpcurlError::Proto(format!("failed to add descriptors from {inputs}: {e}"))
+            })?;
+        Ok(FileSource::new(pool))
+    }
+}
 
+/// Read protoset files in order and add their files to `merged`.
+fn add_protosets(merged: &mut MergedFiles, paths: &[String]) -> Result<()> {
     for path in paths {
-        let bytes = fs::read(Path::new(path)).map_err(|e| {
-            GrpcurlError::Io(std::io::Error::new(
-                e.kind(),
-                format!("failed to read protoset file '{path}': {e}"),
-            ))
-        })?;
+        let bytes = read_protoset(path)?;
+        let path = if is_stdin_path(path) { "<stdin>" } else { path };
+        let origin = format!("protoset '{path}'");
 
-        let fds = prost_types::FileDescriptorSet::decode(bytes.as_slice()).map_err(|e| {
+        let fds = EncodedFileDescriptorSet::decode(bytes.as_slice()).map_err(|e| {
             GrpcurlError::Proto(format!("failed to decode protoset file '{path}': {e}"))
         })?;
+        for file_bytes in fds.file {
+            merged.add(file_bytes, &origin)?;
+        }
+    }
+    Ok(())
+}
 
-        pool.add_file_descriptor_set(fds).map_err(|e| {
-            GrpcurlError::Proto(format!(
-                "failed to add descriptors from protoset file '{path}': {e}"
+/// Whether a `--protoset` path names stdin rather than a file.
+pub fn is_stdin_path(path: &str) -> bool {
+    matches!(path, "-" | "@")
+}
+
+/// Read the raw bytes of a protoset file, or of stdin for `-` / `@`.
+fn read_protoset(path: &str) -> Result<Vec<u8>> {
+    if is_stdin_path(path) {
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(&mut std::io::stdin(), &mut bytes).map_err(|e| {
+            GrpcurlError::Io(std::io::Error::new(
+                e.kind(),
+                format!("failed to read protoset from stdin: {e}"),
             ))
         })?;
+        return Ok(bytes);
     }
-
-    Ok(FileSource::new(pool))
+    fs::read(Path::new(path)).map_err(|e| {
+        GrpcurlError::Io(std::io::Error::new(
+            e.kind(),
+            format!("failed to read protoset file '{path}': {e}"),
+        ))
+    })
 }
 
 /// Create a descriptor source from .proto source files.
@@ -527,17 +875,144 @@ pub fn descriptor_source_from_proto_files(
     import_paths: &[String],
     proto_files: &[String],
 ) -> Result<FileSource> {
-    let includes: Vec<&str> = if import_paths.is_empty() {
+    // The compiler's own pool is used rather than `protox::compile`, whose
+    // `FileDescriptorSet` drops custom options.
+    let compiler = compile_proto_files(import_paths, proto_files, true)?;
+    Ok(FileSource::new(compiler.descriptor_pool()))
+}
+
+/// Compile .proto source files, along with everything they import.
+fn compile_proto_files(
+    import_paths: &[String],
+    proto_files: &[String],
+    include_source_info: bool,
+) -> Result<protox::Compiler> {
+    let proto_files = expand_proto_files(proto_files)?;
+    let mut includes: Vec<&str> = if import_paths.is_empty() {
         // Default to current directory if no import paths specified (matches Go)
         vec!["."]
     } else {
         import_paths.iter().map(String::as_str).collect()
     };
+    // Each file's own directory is searched last, so a file outside every
+    // import path still resolves and can import its siblings by bare name
+    for file in &proto_files {
+        let dir = Path::new(file).parent().and_then(Path::to_str);
+        if let Some(dir) = dir.filter(|dir| !dir.is_empty()) {
+            if !includes.contains(&dir) {
+                includes.push(dir);
+            }
+        }
+    }
+
+    protox::Compiler::new(includes)
+        .and_then(|mut compiler| {
+            compiler
+                .include_source_info(include_source_info)
+                .include_imports(true)
+                .open_files(&proto_files)?;
+            Ok(compiler)
+        })
+        .map_err(|e| {
+            GrpcurlError::Proto(format!(
+                "failed to compile proto files: {}",
+                compile_error_message(&e)
+            ))
+        })
+}
+
+/// A protox error, prefixed with `file:line:column:` where it has a source
+/// location, or just `file:` where only the file is known.
+fn compile_error_message(e: &protox::Error) -> String {
+    use miette::Diagnostic;
+
+    let Some(file) = e.file() else {
+        return e.to_string();
+    };
+    let position = e
+        .labels()
+        .and_then(|mut labels| labels.next())
+        .zip(e.source_code())
+        .and_then(|(label, source)| source.read_span(label.inner(), 0, 0).ok())
+        .map(|span| (span.line() + 1, span.column() + 1));
+    match position {
+        Some((line, column)) => format!("{file}:{line}:{column}: {e}"),
+        None => format!("{file}: {e}"),
+    }
+}
 
-    let fds = protox::compile(proto_files, includes)
-        .map_err(|e| GrpcurlError::Proto(format!("failed to compile proto files: {e}")))?;
+/// The encoded descriptor of one of protobuf's well-known type files, such as
+/// `google/protobuf/timestamp.proto`, from the copies bundled with
+/// prost-reflect. Returns `None` for any other file.
+pub fn well_known_file_descriptor(name: &str) -> Option<Vec<u8>> {
+    if !name.starts_with("google/protobuf/") {
+        return None;
+    }
+    DescriptorPool::global()
+        .get_file_by_name(name)
+        .map(|file| file.file_descriptor_proto().encode_to_vec())
+}
 
-    descriptor_source_from_file_descriptor_set(fds)
+/// A `FileDescriptorSet` whose files are left encoded.
+///
+/// `prost_types::FileDescriptorProto` drops extension fields, so decoding
+/// into it loses custom options such as `(google.api.http)`. Keeping the raw
+/// bytes lets `DescriptorPool::decode_file_descriptor_set` see them.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EncodedFileDescriptorSet {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub file: Vec<Vec<u8>>,
+}
+
+/// Expand proto file arguments, replacing each `dir/...` entry with every
+/// `.proto` file found recursively under `dir`.
+///
+/// Other entries are passed through unchanged. Discovered files are sorted so
+/// compilation order is deterministic; they are resolved against the import
+/// paths exactly like explicitly named files.
+pub fn expand_proto_files(proto_files: &[String]) -> Result<Vec<String>> {
+    let mut expanded = Vec::new();
+    for file in proto_files {
+        match file.strip_suffix("/...") {
+            Some(dir) => {
+                let dir = if dir.is_empty() { "/" } else { dir };
+                let mut found = Vec::new();
+                collect_proto_files(Path::new(dir), &mut found)?;
+                if found.is_empty() {
+                    return Err(GrpcurlError::InvalidArgument(format!(
+                        "no .proto files found under '{dir}'"
+                    )));
+                }
+                found.sort();
+                expanded.extend(found);
+            }
+            None => expanded.push(file.clone()),
+        }
+    }
+    Ok(expanded)
+}
+
+/// Recursively collect the paths of all `.proto` files under `dir`.
+///
+/// Symlinked directories are not followed, so a link back up the tree cannot
+/// make the walk loop or list the same file twice.
+fn collect_proto_files(dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        GrpcurlError::Io(std::io::Error::new(
+            e.kind(),
+            format!("failed to read proto directory '{}': {e}", dir.display()),
+        ))
+    })?;
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_proto_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "proto") && !path.is_dir() {
+            out.push(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
 }
 
 /// Create a descriptor source from a `FileDescriptorSet`.
@@ -753,6 +1228,13 @@ mod tests {
         assert_eq!(files[0].name(), "test.proto");
     }
 
+    #[test]
+    fn file_source_pool_snapshot() {
+        let source = FileSource::new(make_test_pool());
+        let snapshot = source.descriptor_pool_snapshot().unwrap();
+        assert!(snapshot.get_service_by_name("test.v1.Greeter").is_some());
+    }
+
     #[tokio::test]
     async fn list_methods_helper() {
         let pool = make_test_pool();
@@ -779,4 +1261,149 @@ mod tests {
         let services = source.list_services().await.unwrap();
         assert_eq!(services, vec!["simple.Echo"]);
     }
+
+    #[test]
+    fn transitive_deps_depth_limit() {
+        // a.proto -> b.proto -> c.proto
+        let file = |name: &str, deps: &[&str]| prost_types::FileDescriptorProto {
+            name: Some(name.into()),
+            dependency: deps.iter().map(|d| d.to_string()).collect(),
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+            file: vec![
+                file("c.proto", &[]),
+                file("b.proto", &["c.proto"]),
+                file("a.proto", &["b.proto"]),
+            ],
+        })
+        .unwrap();
+        let a = pool.get_file_by_name("a.proto").unwrap();
+
+        check_depth([&a], 2).unwrap();
+        let mut all_files = Vec::new();
+        collect_transitive_deps(&mut all_files, &mut HashSet::new(), &a);
+        let names: Vec<_> = all_files.iter().map(|f| f.name()).collect();
+        assert_eq!(names, ["c.proto", "b.proto", "a.proto"]);
+
+        let err = check_depth([&a], 1).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("dependency depth limit of 1 exceeded at file 'c.proto'"),
+            "{err}"
+        );
+        let err = check_depth([&a], 0).unwrap_err();
+        assert!(err.to_string().contains("at file 'b.proto'"), "{err}");
+
+        // A symbol's own file counts as depth 0, even if another imports it
+        let b = pool.get_file_by_name("b.proto").unwrap();
+        check_depth([&a, &b], 1).unwrap();
+    }
+
+    #[test]
+    fn transitive_deps_depth_uses_shortest_import_chain() {
+        // a.proto -> b.proto -> c.proto, and a.proto -> c.proto directly
+        let file = |name: &str, deps: &[&str]| prost_types::FileDescriptorProto {
+            name: Some(name.into()),
+            dependency: deps.iter().map(|d| d.to_string()).collect(),
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+            file: vec![
+                file("c.proto", &[]),
+                file("b.proto", &["c.proto"]),
+                file("a.proto", &["b.proto", "c.proto"]),
+            ],
+        })
+        .unwrap();
+        let a = pool.get_file_by_name("a.proto").unwrap();
+        check_depth([&a], 1).unwrap();
+
+        let mut all_files = Vec::new();
+        collect_transitive_file_descriptors(&mut all_files, &mut HashSet::new(), &a);
+        let names: Vec<_> = all_files.iter().map(|f| f.name()).collect();
+        assert_eq!(names, ["c.proto", "b.proto", "a.proto"]);
+    }
+
+    #[tokio::test]
+    async fn composite_source_preference() {
+        let file_pool = DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("local.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![prost_types::DescriptorProto {
+                    name: Some("HelloRequest".into()),
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".into()),
+                ..Default::default()
+            }],
+        })
+        .unwrap();
+        let composite = || {
+            CompositeSource::new(
+                Box::new(FileSource::new(make_test_pool())),
+                Box::new(FileSource::new(file_pool.clone())),
+            )
+        };
+        let symbol = composite()
+            .find_symbol("test.v1.HelloRequest")
+            .await
+            .unwrap();
+        let SymbolDescriptor::Message(msg) = symbol else {
+            panic!("expected a message");
+        };
+        assert_eq!(msg.parent_file().name(), "test.proto");
+
+        let preferring_file = composite().with_preference(SourcePreference::File);
+        let symbol = preferring_file
+            .find_symbol("test.v1.HelloRequest")
+            .await
+            .unwrap();
+        let SymbolDescriptor::Message(msg) = symbol else {
+            panic!("expected a message");
+        };
+        assert_eq!(msg.parent_file().name(), "local.proto");
+
+        // Symbols missing from the preferred source still fall back
+        assert!(preferring_file.find_symbol("test.v1.Greeter").await.is_ok());
+    }
+
+    #[test]
+    fn merged_files_add_missing_well_known_imports() {
+        let file = prost_types::FileDescriptorProto {
+            name: Some("event.proto".into()),
+            package: Some("event".into()),
+            dependency: vec![
+                "google/protobuf/timestamp.proto".into(),
+                "google/protobuf/type.proto".into(),
+            ],
+            message_type: vec![prost_types::DescriptorProto {
+                name: Some("Event".into()),
+                field: vec![prost_types::FieldDescriptorProto {
+                    name: Some("at".into()),
+                    number: Some(1),
+                    r#type: Some(prost_types::field_descriptor_proto::Type::Message as i32),
+                    type_name: Some(".google.protobuf.Timestamp".into()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        };
+        let mut merged = MergedFiles::default();
+        merged.add(file.encode_to_vec(), "test").unwrap();
+        let source = merged.into_source("test").unwrap();
+
+        let pool = &source.pool;
+        assert!(pool.get_message_by_name("event.Event").is_some());
+        // type.proto's own imports are filled in too
+        assert!(pool.get_file_by_name("google/protobuf/any.proto").is_some());
+        assert!(pool
+            .get_file_by_name("google/protobuf/source_context.proto")
+            .is_some());
+    }
 }
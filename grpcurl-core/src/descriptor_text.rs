@@ -1,7 +1,10 @@
+use prost_reflect::text_format::FormatOptions;
 use prost_reflect::{
-    EnumDescriptor, EnumValueDescriptor, FieldDescriptor, FileDescriptor, Kind, MessageDescriptor,
-    MethodDescriptor, OneofDescriptor, ServiceDescriptor,
+    Cardinality, DynamicMessage, EnumDescriptor, EnumValueDescriptor, FieldDescriptor,
+    FileDescriptor, Kind, MessageDescriptor, MethodDescriptor, OneofDescriptor, ServiceDescriptor,
+    Value,
 };
+use prost_types::SourceCodeInfo;
 
 use crate::descriptor::SymbolDescriptor;
 
@@ -10,16 +13,27 @@ use crate::descriptor::SymbolDescriptor;
 /// Go uses `protoprint.Printer` configured with: compact format, no non-doc comments,
 /// sorted elements, fully-qualified names.
 pub fn get_descriptor_text(sym: &SymbolDescriptor) -> String {
+    descriptor_text(sym, false)
+}
+
+/// Format a symbol descriptor like [`get_descriptor_text`], but keep elements
+/// in the order they are declared in the source file: fields and oneofs of a
+/// message, methods of a service, and values of an enum.
+pub fn get_descriptor_text_preserving_order(sym: &SymbolDescriptor) -> String {
+    descriptor_text(sym, true)
+}
+
+fn descriptor_text(sym: &SymbolDescriptor, preserve_order: bool) -> String {
     match sym {
-        SymbolDescriptor::Service(d) => format_service(d),
+        SymbolDescriptor::Service(d) => format_service(d, preserve_order),
         SymbolDescriptor::Method(d) => format_method(d),
-        SymbolDescriptor::Message(d) => format_message(d),
-        SymbolDescriptor::Enum(d) => format_enum(d),
-        SymbolDescriptor::Field(d) => format_field(d),
+        SymbolDescriptor::Message(d) => format_message(d, preserve_order),
+        SymbolDescriptor::Enum(d) => format_enum(d, preserve_order),
+        SymbolDescriptor::Field(d) => format_field(d, preserve_order),
         SymbolDescriptor::Extension(d) => format_extension(d),
-        SymbolDescriptor::OneOf(d) => format_oneof(d),
+        SymbolDescriptor::OneOf(d) => format_oneof(d, preserve_order),
         SymbolDescriptor::EnumValue(d) => format_enum_value(d),
-        SymbolDescriptor::File(_) => String::new(),
+        SymbolDescriptor::File(d) => format_proto_file(d),
     }
 }
 
@@ -27,9 +41,28 @@ pub fn get_descriptor_text(sym: &SymbolDescriptor) -> String {
 ///
 /// Generates valid proto source text including syntax, package, imports,
 /// file options, messages, enums, services, and extensions.
-/// Matches Go's `protoprint.Printer.PrintProtoFile()` output.
+/// Matches Go's `protoprint.Printer.PrintProtoFile()` output. When the
+/// descriptor carries `SourceCodeInfo`, the leading comment of each
+/// declaration is printed above it; detached and trailing comments are
+/// dropped, as Go does.
 pub fn format_proto_file(fd: &FileDescriptor) -> String {
+    proto_file_text(fd, true)
+}
+
+/// Format a complete .proto file like [`format_proto_file`], but never print
+/// comments, even when the descriptor carries `SourceCodeInfo`.
+pub fn format_proto_file_without_comments(fd: &FileDescriptor) -> String {
+    proto_file_text(fd, false)
+}
+
+fn proto_file_text(fd: &FileDescriptor, with_comments: bool) -> String {
     let proto = fd.file_descriptor_proto();
+    let comments = if with_comments {
+        file_comments(proto.source_code_info.as_ref())
+    } else {
+        Comments::new()
+    };
+    let comments = &comments;
     let mut out = String::new();
 
     // Syntax
@@ -132,14 +165,16 @@ pub fn format_proto_file(fd: &FileDescriptor) -> String {
     // Messages
     for msg in fd.messages() {
         out.push('\n');
-        out.push_str(&file_format_message(&msg, pkg));
+        out.push_str(&leading_comment(comments, msg.path()));
+        out.push_str(&file_format_message(&msg, pkg, comments));
         out.push('\n');
     }
 
     // Enums
     for e in fd.enums() {
         out.push('\n');
-        out.push_str(&file_format_enum(&e));
+        out.push_str(&leading_comment(comments, e.path()));
+        out.push_str(&file_format_enum(&e, comments));
         out.push('\n');
     }
 
@@ -157,6 +192,11 @@ pub fn format_proto_file(fd: &FileDescriptor) -> String {
             out.push('\n');
             out.push_str(&format!("extend {extendee} {{\n"));
             for ext in exts {
+                for line in leading_comment(comments, ext.path()).lines() {
+                    out.push_str("  ");
+                    out.push_str(line);
+                    out.push('\n');
+                }
                 out.push_str("  ");
                 out.push_str(&format_extension(ext));
                 out.push('\n');
@@ -168,13 +208,41 @@ pub fn format_proto_file(fd: &FileDescriptor) -> String {
     // Services (preserve original order, use short names)
     for svc in fd.services() {
         out.push('\n');
-        out.push_str(&file_format_service(&svc, pkg));
+        out.push_str(&leading_comment(comments, svc.path()));
+        out.push_str(&file_format_service(&svc, pkg, comments));
         out.push('\n');
     }
 
     out
 }
 
+/// The leading comments of a file's declarations, keyed by their source
+/// location path.
+type Comments<'a> = std::collections::HashMap<&'a [i32], &'a str>;
+
+/// Index the leading comments in `info` by path, so each declaration's
+/// comment is found without rescanning every location.
+fn file_comments(info: Option<&SourceCodeInfo>) -> Comments<'_> {
+    let mut comments = Comments::new();
+    for loc in info.iter().flat_map(|info| &info.location) {
+        // The first location recorded for a path is the declaration's own
+        let comment = loc.leading_comments.as_deref().unwrap_or_default();
+        comments.entry(loc.path.as_slice()).or_insert(comment);
+    }
+    comments
+}
+
+/// The leading comment recorded for the declaration at `path`, as `//` lines
+/// each ending in a newline, or an empty string when there is none.
+fn leading_comment(comments: &Comments, path: &[i32]) -> String {
+    let comment = comments.get(path).copied().unwrap_or_default();
+    let comment = comment.strip_suffix('\n').unwrap_or(comment);
+    if comment.is_empty() {
+        return String::new();
+    }
+    comment.lines().map(|line| format!("//{line}\n")).collect()
+}
+
 /// Shorten a fully-qualified name by removing the package prefix.
 /// "test.v1.HelloRequest" with package "test.v1" -> "HelloRequest"
 /// Names in other packages keep the fully-qualified form with leading dot.
@@ -192,15 +260,19 @@ fn short_name(full_name: &str, pkg: &str) -> String {
 }
 
 /// Format a service for proto file output (preserves original method order, short names).
-fn file_format_service(svc: &ServiceDescriptor, pkg: &str) -> String {
+fn file_format_service(svc: &ServiceDescriptor, pkg: &str, comments: &Comments) -> String {
     let mut out = format!("service {} {{\n", svc.name());
 
     // Preserve original order (don't sort)
     let methods: Vec<_> = svc.methods().collect();
+    push_option_statements(&mut out, &svc.options(), !methods.is_empty());
     for (i, method) in methods.iter().enumerate() {
-        out.push_str("  ");
-        out.push_str(&file_format_method(method, pkg));
-        out.push('\n');
+        let text = leading_comment(comments, method.path()) + &file_format_method(method, pkg);
+        for line in text.lines() {
+            out.push_str("  ");
+            out.push_str(line);
+            out.push('\n');
+        }
         // Blank line between methods (matching Go's protoprint)
         if i + 1 < methods.len() {
             out.push('\n');
@@ -227,18 +299,69 @@ fn file_format_method(method: &MethodDescriptor, pkg: &str) -> String {
         ""
     };
 
-    format!(
-        "rpc {} ( {}{} ) returns ( {}{} );",
+    let signature = format!(
+        "rpc {} ( {}{} ) returns ( {}{} )",
         method.name(),
         client_stream,
         short_name(input.full_name(), pkg),
         server_stream,
         short_name(output.full_name(), pkg),
-    )
+    );
+
+    let options = format_option_statements(&method.options());
+    if options.is_empty() {
+        return format!("{signature};");
+    }
+    let mut out = format!("{signature} {{\n");
+    for line in &options {
+        out.push_str("  ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push('}');
+    out
+}
+
+/// Write `option` statements for a message or service block, indented one
+/// level and followed by a blank line when more of the block comes after.
+fn push_option_statements(out: &mut String, options: &DynamicMessage, more: bool) {
+    let lines = format_option_statements(options);
+    for line in &lines {
+        out.push_str("  ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !lines.is_empty() && more {
+        out.push('\n');
+    }
+}
+
+/// Format the options set on a message, service, or method as `option`
+/// statements, e.g. `option deprecated = true;`. Standard options come
+/// first, then custom extension options.
+fn format_option_statements(options: &DynamicMessage) -> Vec<String> {
+    let mut assignments = Vec::new();
+    for (field, value) in options.fields() {
+        // Unresolved options have no source form; protoc interprets them away
+        if field.name() == "uninterpreted_option" {
+            continue;
+        }
+        assignments.push(format!(
+            "{} = {}",
+            field.name(),
+            format_option_value(&field.kind(), value)
+        ));
+    }
+    assignments.extend(format_custom_options(options));
+
+    assignments
+        .into_iter()
+        .map(|assignment| format!("option {assignment};"))
+        .collect()
 }
 
 /// Format a message for proto file output (uses short type names).
-fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
+fn file_format_message(msg: &MessageDescriptor, pkg: &str, comments: &Comments) -> String {
     let mut out = format!("message {} {{\n", msg.name());
 
     let mut field_entries: Vec<FieldEntry> = Vec::new();
@@ -259,7 +382,8 @@ fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
         }
         field_entries.push(FieldEntry {
             number: field.number(),
-            text: file_format_field(&field, pkg),
+            text: leading_comment(comments, field.path())
+                + &file_format_field(&field, pkg, comments),
         });
     }
 
@@ -270,14 +394,16 @@ fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
         let min_number = oneof.fields().map(|f| f.number()).min().unwrap_or(u32::MAX);
         field_entries.push(FieldEntry {
             number: min_number,
-            text: file_format_oneof(&oneof, pkg),
+            text: leading_comment(comments, oneof.path())
+                + &file_format_oneof(&oneof, pkg, comments),
         });
     }
 
     // Nested messages
     for nested in msg.child_messages() {
-        // Skip map entry types (they're synthesized)
-        if nested.is_map_entry() {
+        // Skip map entry types (they're synthesized) and group types
+        // (they're printed inline with their field)
+        if nested.is_map_entry() || is_group_type(msg, &nested) {
             continue;
         }
         let min_num = nested
@@ -287,7 +413,8 @@ fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
             .unwrap_or(u32::MAX);
         field_entries.push(FieldEntry {
             number: min_num,
-            text: file_format_message(&nested, pkg),
+            text: leading_comment(comments, nested.path())
+                + &file_format_message(&nested, pkg, comments),
         });
     }
 
@@ -300,12 +427,15 @@ fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
             .unwrap_or(u32::MAX);
         field_entries.push(FieldEntry {
             number: min_num,
-            text: file_format_enum(&nested_enum),
+            text: leading_comment(comments, nested_enum.path())
+                + &file_format_enum(&nested_enum, comments),
         });
     }
 
     field_entries.sort_by_key(|e| e.number);
 
+    push_option_statements(&mut out, &msg.options(), !field_entries.is_empty());
+
     for (i, entry) in field_entries.iter().enumerate() {
         for line in entry.text.lines() {
             out.push_str("  ");
@@ -323,13 +453,18 @@ fn file_format_message(msg: &MessageDescriptor, pkg: &str) -> String {
 }
 
 /// Format an enum for proto file output (blank lines between values).
-fn file_format_enum(e: &EnumDescriptor) -> String {
+fn file_format_enum(e: &EnumDescriptor, comments: &Comments) -> String {
     let mut out = format!("enum {} {{\n", e.name());
 
     let mut values: Vec<EnumValueDescriptor> = e.values().collect();
     values.sort_by_key(|v| v.number());
 
     for (i, val) in values.iter().enumerate() {
+        for line in leading_comment(comments, val.path()).lines() {
+            out.push_str("  ");
+            out.push_str(line);
+            out.push('\n');
+        }
         out.push_str("  ");
         out.push_str(&format_enum_value(val));
         out.push('\n');
@@ -343,7 +478,11 @@ fn file_format_enum(e: &EnumDescriptor) -> String {
 }
 
 /// Format a field for proto file output (uses short type names).
-fn file_format_field(field: &FieldDescriptor, pkg: &str) -> String {
+fn file_format_field(field: &FieldDescriptor, pkg: &str, comments: &Comments) -> String {
+    if let Some(group) = group_message(field) {
+        return format_group(field, &group, &file_format_message(&group, pkg, comments));
+    }
+
     let options = format_field_options(field);
 
     if field.is_map() {
@@ -368,10 +507,9 @@ fn file_format_field(field: &FieldDescriptor, pkg: &str) -> String {
     }
 
     let type_name = file_field_type_name(field, pkg);
-    let repeated = if field.is_list() { "repeated " } else { "" };
     format!(
         "{}{} {} = {}{};",
-        repeated,
+        field_label(field),
         type_name,
         field.name(),
         field.number(),
@@ -380,16 +518,20 @@ fn file_format_field(field: &FieldDescriptor, pkg: &str) -> String {
 }
 
 /// Format a oneof for proto file output (uses short type names).
-fn file_format_oneof(oneof: &OneofDescriptor, pkg: &str) -> String {
+fn file_format_oneof(oneof: &OneofDescriptor, pkg: &str, comments: &Comments) -> String {
     let mut out = format!("oneof {} {{\n", oneof.name());
 
     let mut fields: Vec<FieldDescriptor> = oneof.fields().collect();
     fields.sort_by_key(|f| f.number());
 
     for field in &fields {
-        out.push_str("  ");
-        out.push_str(&file_format_field(field, pkg));
-        out.push('\n');
+        let text =
+            leading_comment(comments, field.path()) + &file_format_field(field, pkg, comments);
+        for line in text.lines() {
+            out.push_str("  ");
+            out.push_str(line);
+            out.push('\n');
+        }
     }
 
     out.push('}');
@@ -405,11 +547,13 @@ fn file_field_type_name(field: &FieldDescriptor, pkg: &str) -> String {
     }
 }
 
-fn format_service(svc: &ServiceDescriptor) -> String {
+fn format_service(svc: &ServiceDescriptor, preserve_order: bool) -> String {
     let mut out = format!("service {} {{\n", svc.name());
 
     let mut methods: Vec<MethodDescriptor> = svc.methods().collect();
-    methods.sort_by(|a, b| a.name().cmp(b.name()));
+    if !preserve_order {
+        methods.sort_by(|a, b| a.name().cmp(b.name()));
+    }
 
     for method in &methods {
         out.push_str("  ");
@@ -446,7 +590,7 @@ fn format_method(method: &MethodDescriptor) -> String {
     )
 }
 
-fn format_message(msg: &MessageDescriptor) -> String {
+fn format_message(msg: &MessageDescriptor, preserve_order: bool) -> String {
     let mut out = format!("message {} {{\n", msg.name());
 
     // Reserved ranges and names (at the top of the message, matching Go)
@@ -477,8 +621,8 @@ fn format_message(msg: &MessageDescriptor) -> String {
             continue;
         }
         field_entries.push(FieldEntry {
-            number: field.number(),
-            text: format_field(&field),
+            number: entry_key(&field, preserve_order),
+            text: format_field(&field, preserve_order),
         });
     }
 
@@ -487,15 +631,19 @@ fn format_message(msg: &MessageDescriptor) -> String {
         if is_synthetic_oneof(&oneof) {
             continue;
         }
-        // Use the lowest field number in the oneof for ordering
-        let min_number = oneof.fields().map(|f| f.number()).min().unwrap_or(u32::MAX);
+        // Use the lowest key of the oneof's fields for ordering
+        let min_number = oneof
+            .fields()
+            .map(|f| entry_key(&f, preserve_order))
+            .min()
+            .unwrap_or(u32::MAX);
         field_entries.push(FieldEntry {
             number: min_number,
-            text: format_oneof(&oneof),
+            text: format_oneof(&oneof, preserve_order),
         });
     }
 
-    // Sort by field number (preserves proto source order)
+    // Sort by field number, or by declaration order when preserving it
     field_entries.sort_by_key(|e| e.number);
 
     for entry in &field_entries {
@@ -515,7 +663,21 @@ struct FieldEntry {
     text: String,
 }
 
-fn format_field(field: &FieldDescriptor) -> String {
+/// The sort key for a field within its message: the field number, or its
+/// position in the message declaration when `preserve_order` is set.
+fn entry_key(field: &FieldDescriptor, preserve_order: bool) -> u32 {
+    if preserve_order {
+        field.path().last().map_or(0, |&i| i as u32)
+    } else {
+        field.number()
+    }
+}
+
+fn format_field(field: &FieldDescriptor, preserve_order: bool) -> String {
+    if let Some(group) = group_message(field) {
+        return format_group(field, &group, &format_message(&group, preserve_order));
+    }
+
     let type_name = field_type_name(field);
     let options = format_field_options(field);
 
@@ -541,10 +703,9 @@ fn format_field(field: &FieldDescriptor) -> String {
         }
     }
 
-    let repeated = if field.is_list() { "repeated " } else { "" };
     format!(
         "{}{} {} = {}{};",
-        repeated,
+        field_label(field),
         type_name,
         field.name(),
         field.number(),
@@ -552,12 +713,78 @@ fn format_field(field: &FieldDescriptor) -> String {
     )
 }
 
+/// The label written before a (non-map) field's type.
+///
+/// Fields omit proto2 `optional` and `required` like Go's output does, except
+/// inside a group body: it is printed inline with the group, where protoc
+/// requires every field outside a oneof to carry its label.
+fn field_label(field: &FieldDescriptor) -> &'static str {
+    let parent = field.parent_message();
+    let in_group = parent
+        .parent_message()
+        .is_some_and(|outer| is_group_type(&outer, parent));
+    let in_oneof = field
+        .containing_oneof()
+        .is_some_and(|oneof| !is_synthetic_oneof(&oneof));
+    match field.cardinality() {
+        Cardinality::Repeated => "repeated ",
+        Cardinality::Required if in_group && !in_oneof => "required ",
+        Cardinality::Optional if in_group && !in_oneof => "optional ",
+        _ => "",
+    }
+}
+
+/// Get the message type of a proto2 group field, or `None` for any other field.
+fn group_message(field: &FieldDescriptor) -> Option<MessageDescriptor> {
+    match field.kind() {
+        Kind::Message(msg) if field.is_group() => Some(msg),
+        _ => None,
+    }
+}
+
+/// Check if a nested message is the type of one of the group fields of `parent`.
+fn is_group_type(parent: &MessageDescriptor, nested: &MessageDescriptor) -> bool {
+    parent
+        .fields()
+        .filter_map(|f| group_message(&f))
+        .any(|group| group.full_name() == nested.full_name())
+}
+
+/// Format a group field as `optional group Name = N { ... }`, reusing the body
+/// of the already formatted group message.
+///
+/// Group syntax always carries a label, so proto2 `optional` is spelled out here
+/// even though regular fields omit it.
+fn format_group(field: &FieldDescriptor, group: &MessageDescriptor, message_text: &str) -> String {
+    let label = match field.cardinality() {
+        Cardinality::Repeated => "repeated",
+        Cardinality::Required => "required",
+        Cardinality::Optional => "optional",
+    };
+    let body = message_text.split_once('\n').map_or("}", |(_, rest)| rest);
+    format!(
+        "{} group {} = {}{} {{\n{}",
+        label,
+        group.name(),
+        field.number(),
+        format_field_options(field),
+        body
+    )
+}
+
 /// Format field options in brackets, e.g. ` [deprecated = true, json_name = "foo"]`.
 /// Returns empty string if no options are set.
 fn format_field_options(field: &FieldDescriptor) -> String {
     let proto = field.field_descriptor_proto();
     let mut opts = Vec::new();
 
+    if let Some(ref default) = proto.default_value {
+        opts.push(format!(
+            "default = {}",
+            format_default_value(field, default)
+        ));
+    }
+
     if let Some(ref field_opts) = proto.options {
         if field_opts.deprecated == Some(true) {
             opts.push("deprecated = true".to_string());
@@ -588,6 +815,8 @@ fn format_field_options(field: &FieldDescriptor) -> String {
         }
     }
 
+    opts.extend(format_custom_options(&field.options()));
+
     if opts.is_empty() {
         String::new()
     } else {
@@ -595,6 +824,131 @@ fn format_field_options(field: &FieldDescriptor) -> String {
     }
 }
 
+/// Format the extension options set on an options message, e.g.
+/// `(my.custom.option) = "value"`, ordered by extension number.
+///
+/// Only extensions known to the descriptor pool can be named; options whose
+/// definition is not available show up as unknown fields and are skipped.
+fn format_custom_options(options: &DynamicMessage) -> Vec<String> {
+    let mut extensions: Vec<_> = options.extensions().collect();
+    extensions.sort_by_key(|(ext, _)| ext.number());
+
+    let mut opts = Vec::new();
+    for (ext, value) in extensions {
+        let kind = ext.kind();
+        let values = match value {
+            Value::List(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            opts.push(format!(
+                "({}) = {}",
+                ext.full_name(),
+                format_option_value(&kind, value)
+            ));
+        }
+    }
+    opts
+}
+
+/// Format a single option value as a proto source literal. Message values use
+/// the text format aggregate syntax, e.g. `{ get: "/v1/foo" }`.
+fn format_option_value(kind: &Kind, value: &Value) -> String {
+    match value {
+        Value::Bool(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::F32(v) => format_float(f64::from(*v)),
+        Value::F64(v) => format_float(*v),
+        Value::String(v) => format!("\"{}\"", escape_string(v)),
+        Value::Bytes(v) => format!("\"{}\"", escape_bytes(v)),
+        Value::EnumNumber(n) => match kind {
+            Kind::Enum(e) => e
+                .get_value(*n)
+                .map_or_else(|| n.to_string(), |v| v.name().to_string()),
+            _ => n.to_string(),
+        },
+        Value::Message(msg) => {
+            let text = msg.to_text_format_with_options(&FormatOptions::new().pretty(true));
+            let fields: Vec<&str> = text.lines().map(str::trim).collect();
+            if fields.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", fields.join(" "))
+            }
+        }
+        Value::List(_) | Value::Map(_) => String::new(),
+    }
+}
+
+/// Format a float option value, spelling out non-finite values like protoc.
+fn format_float(v: f64) -> String {
+    if v.is_nan() {
+        "nan".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        v.to_string()
+    }
+}
+
+/// Format a proto2 default value as it would appear in source.
+///
+/// The descriptor stores string defaults unescaped and bytes defaults already
+/// C-escaped, so only strings need escaping before they are quoted. Numbers,
+/// bools, and enum value names are written as-is.
+fn format_default_value(field: &FieldDescriptor, default: &str) -> String {
+    match field.kind() {
+        Kind::String => format!("\"{}\"", escape_string(default)),
+        Kind::Bytes => format!("\"{default}\""),
+        _ => default.to_string(),
+    }
+}
+
+/// Escape a string for use inside a double-quoted proto string literal.
+fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let mut buf = [0; 4];
+                for b in c.encode_utf8(&mut buf).bytes() {
+                    out.push_str(&format!("\\{b:03o}"));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape bytes for use inside a double-quoted proto string literal, using
+/// octal escapes for anything that is not printable ASCII.
+fn escape_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &b in bytes {
+        match b {
+            b'\\' => out.push_str("\\\\"),
+            b'"' => out.push_str("\\\""),
+            b'\'' => out.push_str("\\'"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(b as char),
+            _ => out.push_str(&format!("\\{b:03o}")),
+        }
+    }
+    out
+}
+
 /// Convert snake_case to lowerCamelCase (protobuf default json_name mapping).
 fn to_lower_camel_case(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
@@ -662,11 +1016,13 @@ fn format_extension(ext: &prost_reflect::ExtensionDescriptor) -> String {
     )
 }
 
-fn format_enum(e: &EnumDescriptor) -> String {
+fn format_enum(e: &EnumDescriptor, preserve_order: bool) -> String {
     let mut out = format!("enum {} {{\n", e.name());
 
     let mut values: Vec<EnumValueDescriptor> = e.values().collect();
-    values.sort_by_key(|v| v.number());
+    if !preserve_order {
+        values.sort_by_key(|v| v.number());
+    }
 
     for val in &values {
         out.push_str("  ");
@@ -682,15 +1038,15 @@ fn format_enum_value(val: &EnumValueDescriptor) -> String {
     format!("{} = {};", val.name(), val.number())
 }
 
-fn format_oneof(oneof: &OneofDescriptor) -> String {
+fn format_oneof(oneof: &OneofDescriptor, preserve_order: bool) -> String {
     let mut out = format!("oneof {} {{\n", oneof.name());
 
     let mut fields: Vec<FieldDescriptor> = oneof.fields().collect();
-    fields.sort_by_key(|f| f.number());
+    fields.sort_by_key(|f| entry_key(f, preserve_order));
 
     for field in &fields {
         out.push_str("  ");
-        out.push_str(&format_field(field));
+        out.push_str(&format_field(field, preserve_order));
         out.push('\n');
     }
 
@@ -833,11 +1189,65 @@ mod tests {
         DescriptorPool::from_file_descriptor_set(fds).unwrap()
     }
 
+    /// A proto2 file equivalent to:
+    ///
+    /// ```proto
+    /// message SearchResponse {
+    ///   repeated group Result = 1 {
+    ///     required string url = 2;
+    ///   }
+    ///   optional int32 total = 3;
+    /// }
+    /// ```
+    fn make_group_pool() -> DescriptorPool {
+        let fds = prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("search.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![prost_types::DescriptorProto {
+                    name: Some("SearchResponse".into()),
+                    field: vec![
+                        prost_types::FieldDescriptorProto {
+                            name: Some("result".into()),
+                            number: Some(1),
+                            r#type: Some(10), // TYPE_GROUP
+                            type_name: Some(".test.v1.SearchResponse.Result".into()),
+                            label: Some(3), // LABEL_REPEATED
+                            ..Default::default()
+                        },
+                        prost_types::FieldDescriptorProto {
+                            name: Some("total".into()),
+                            number: Some(3),
+                            r#type: Some(5), // TYPE_INT32
+                            label: Some(1),
+                            ..Default::default()
+                        },
+                    ],
+                    nested_type: vec![prost_types::DescriptorProto {
+                        name: Some("Result".into()),
+                        field: vec![prost_types::FieldDescriptorProto {
+                            name: Some("url".into()),
+                            number: Some(2),
+                            r#type: Some(9),
+                            label: Some(2), // LABEL_REQUIRED
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                syntax: Some("proto2".into()),
+                ..Default::default()
+            }],
+        };
+        DescriptorPool::from_file_descriptor_set(fds).unwrap()
+    }
+
     #[test]
     fn service_text() {
         let pool = make_pool();
         let svc = pool.get_service_by_name("test.v1.Greeter").unwrap();
-        let text = format_service(&svc);
+        let text = format_service(&svc, false);
         assert!(text.contains("service Greeter {"));
         assert!(text.contains("rpc SayGoodbye"));
         assert!(text.contains("rpc SayHello"));
@@ -851,7 +1261,7 @@ mod tests {
     fn message_text() {
         let pool = make_pool();
         let msg = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
-        let text = format_message(&msg);
+        let text = format_message(&msg, false);
         assert_eq!(text, "message HelloRequest {\n  string name = 1;\n}");
     }
 
@@ -871,7 +1281,7 @@ mod tests {
     fn enum_text() {
         let pool = make_pool();
         let e = pool.get_enum_by_name("test.v1.Status").unwrap();
-        let text = format_enum(&e);
+        let text = format_enum(&e, false);
         assert!(text.contains("enum Status {"));
         assert!(text.contains("UNKNOWN = 0;"));
         assert!(text.contains("ACTIVE = 1;"));
@@ -882,7 +1292,7 @@ mod tests {
         let pool = make_pool();
         let msg = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
         let field = msg.get_field_by_name("name").unwrap();
-        let text = format_field(&field);
+        let text = format_field(&field, false);
         assert_eq!(text, "string name = 1;");
     }
 
@@ -934,6 +1344,84 @@ mod tests {
         assert!(text.contains("ACTIVE = 1;"));
     }
 
+    #[test]
+    fn group_message_text() {
+        let pool = make_group_pool();
+        let msg = pool.get_message_by_name("test.v1.SearchResponse").unwrap();
+        let text = format_message(&msg, false);
+        assert_eq!(
+            text,
+            "message SearchResponse {\n  \
+             repeated group Result = 1 {\n    \
+             required string url = 2;\n  \
+             }\n  \
+             int32 total = 3;\n\
+             }"
+        );
+
+        let field = msg.get_field_by_name("result").unwrap();
+        assert_eq!(
+            format_field(&field, false),
+            "repeated group Result = 1 {\n  required string url = 2;\n}"
+        );
+    }
+
+    #[test]
+    fn group_proto_file_output() {
+        let pool = make_group_pool();
+        let file = pool.get_file_by_name("search.proto").unwrap();
+        let text = format_proto_file(&file);
+
+        assert!(text.starts_with("syntax = \"proto2\";\n"));
+        let group = "  repeated group Result = 1 {\n    required string url = 2;\n  }\n";
+        assert!(text.contains(group));
+        // The group type is printed inline, not as a separate nested message
+        assert!(!text.contains("message Result"));
+    }
+
+    #[test]
+    fn field_default_values() {
+        let field = |name: &str, number: i32, r#type: i32, default: &str| {
+            prost_types::FieldDescriptorProto {
+                name: Some(name.into()),
+                number: Some(number),
+                r#type: Some(r#type),
+                label: Some(1),
+                default_value: Some(default.into()),
+                ..Default::default()
+            }
+        };
+        let fds = prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("defaults.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![prost_types::DescriptorProto {
+                    name: Some("Defaults".into()),
+                    field: vec![
+                        field("count", 1, 5, "5"),               // TYPE_INT32
+                        field("ratio", 2, 1, "-inf"),            // TYPE_DOUBLE
+                        field("greeting", 3, 9, "say \"hi\"\n"), // TYPE_STRING
+                        field("raw", 4, 12, "\\001x"),           // TYPE_BYTES
+                    ],
+                    ..Default::default()
+                }],
+                syntax: Some("proto2".into()),
+                ..Default::default()
+            }],
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(fds).unwrap();
+        let msg = pool.get_message_by_name("test.v1.Defaults").unwrap();
+        let text = |name: &str| format_field(&msg.get_field_by_name(name).unwrap(), false);
+
+        assert_eq!(text("count"), "int32 count = 1 [default = 5];");
+        assert_eq!(text("ratio"), "double ratio = 2 [default = -inf];");
+        assert_eq!(
+            text("greeting"),
+            r#"string greeting = 3 [default = "say \"hi\"\n"];"#
+        );
+        assert_eq!(text("raw"), r#"bytes raw = 4 [default = "\001x"];"#);
+    }
+
     #[test]
     fn short_name_same_package() {
         assert_eq!(
@@ -1,10 +1,15 @@
+use std::borrow::Cow;
 use std::cell::Cell;
 use std::fmt;
 use std::io::{self, Read};
+use std::ops::Range;
 use std::str::FromStr;
 
-use prost_reflect::{DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions};
+use prost_reflect::{
+    DescriptorPool, DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions,
+};
 
+use crate::descriptor::DescriptorSource;
 use crate::error::{GrpcurlError, Result};
 
 /// Format for request/response data.
@@ -12,6 +17,8 @@ use crate::error::{GrpcurlError, Result};
 pub enum Format {
     Json,
     Text,
+    /// Newline-delimited JSON: one compact JSON object per line.
+    Ndjson,
 }
 
 impl FromStr for Format {
@@ -21,8 +28,9 @@ impl FromStr for Format {
         match s {
             "json" => Ok(Format::Json),
             "text" => Ok(Format::Text),
+            "ndjson" => Ok(Format::Ndjson),
             other => Err(format!(
-                "The --format option must be 'json' or 'text', got '{other}'."
+                "The --format option must be 'json', 'text' or 'ndjson', got '{other}'."
             )),
         }
     }
@@ -33,6 +41,83 @@ impl fmt::Display for Format {
         match self {
             Format::Json => write!(f, "json"),
             Format::Text => write!(f, "text"),
+            Format::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
+/// How a non-OK RPC status is reported on stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// The `ERROR:` block printed by Go grpcurl.
+    #[default]
+    Human,
+    /// A single-line JSON object, for automation.
+    Json,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            other => Err(format!(
+                "The --error-format option must be 'human' or 'json', got '{other}'."
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ErrorFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorFormat::Human => write!(f, "human"),
+            ErrorFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// How gRPC status code names are written in status output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusStyle {
+    /// Go's `codes.Code.String()` names, e.g. `Canceled`.
+    #[default]
+    Go,
+    /// The canonical SCREAMING_SNAKE_CASE names, e.g. `CANCELLED`.
+    Upper,
+}
+
+impl StatusStyle {
+    /// The name of `code` in this style.
+    pub fn code_name(self, code: tonic::Code) -> &'static str {
+        match self {
+            StatusStyle::Go => status_code_name(code),
+            StatusStyle::Upper => upper_status_code_name(code),
+        }
+    }
+}
+
+impl FromStr for StatusStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "go" => Ok(StatusStyle::Go),
+            "upper" => Ok(StatusStyle::Upper),
+            other => Err(format!(
+                "The --status-style option must be 'go' or 'upper', got '{other}'."
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StatusStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusStyle::Go => write!(f, "go"),
+            StatusStyle::Upper => write!(f, "upper"),
         }
     }
 }
@@ -46,9 +131,28 @@ pub struct FormatOptions {
     /// Maps to prost-reflect's `skip_default_fields(!emit_defaults)`.
     pub emit_defaults: bool,
 
-    /// Accept unknown fields in JSON input without error.
-    /// Maps to prost-reflect's `deny_unknown_fields(!allow_unknown)`.
+    /// Accept unknown fields in JSON and text input without error.
+    /// Maps to prost-reflect's `deny_unknown_fields(!allow_unknown)` for JSON;
+    /// text input has unknown fields removed before it is parsed.
     pub allow_unknown_fields: bool,
+
+    /// Write `google.protobuf.Any` fields in JSON output as their wire form,
+    /// `{"@type": ..., "value": "<base64>"}`, instead of expanding them.
+    pub raw_any: bool,
+
+    /// Indentation for each nesting level of pretty-printed JSON output.
+    /// `None` uses two spaces.
+    pub json_indent: Option<String>,
+
+    /// Write each set oneof in JSON output as a wrapper object keyed by the
+    /// oneof name, `{"which": "<member>", "value": ...}`, instead of as the
+    /// member field itself. JSON input accepts both forms regardless.
+    pub oneof_wrapped: bool,
+
+    /// Write `google.protobuf.Duration` and `Timestamp` values in JSON output
+    /// for reading, as `3s 120ms` and as a local date and time, instead of
+    /// their proto3 JSON strings.
+    pub humanize_time: bool,
 }
 
 /// Parse error indicating end of input.
@@ -66,6 +170,22 @@ impl From<GrpcurlError> for ParseError {
     }
 }
 
+/// Read raw request data: stdin if `data` is "@" or "@-", otherwise the
+/// string itself. Missing data reads as empty.
+pub fn read_request_data(data: Option<&str>) -> Result<String> {
+    match data {
+        Some("@" | "@-") => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf).map_err(|e| {
+                GrpcurlError::Io(io::Error::new(e.kind(), format!("reading stdin: {e}")))
+            })?;
+            Ok(buf)
+        }
+        Some(s) => Ok(s.to_string()),
+        None => Ok(String::new()),
+    }
+}
+
 /// Stream-based request message parser.
 ///
 /// Equivalent to Go's `RequestParser` interface (format.go:24-33).
@@ -81,19 +201,9 @@ pub struct JsonRequestParser {
 impl JsonRequestParser {
     /// Create a new JSON request parser from the input data.
     ///
-    /// If `data` is "@", reads from stdin. Otherwise uses the string directly.
+    /// If `data` is "@" or "@-", reads from stdin. Otherwise uses the string directly.
     pub fn new(data: Option<&str>, options: &FormatOptions) -> Result<Self> {
-        let input = match data {
-            Some("@") => {
-                let mut buf = String::new();
-                io::stdin().read_to_string(&mut buf).map_err(|e| {
-                    GrpcurlError::Io(io::Error::new(e.kind(), format!("reading stdin: {e}")))
-                })?;
-                buf
-            }
-            Some(s) => s.to_string(),
-            None => String::new(),
-        };
+        let input = read_request_data(data)?;
 
         let de_options =
             DeserializeOptions::new().deny_unknown_fields(!options.allow_unknown_fields);
@@ -133,22 +243,123 @@ impl JsonRequestParser {
                 let bytes_consumed = de.byte_offset();
                 self.offset += bytes_consumed;
                 self.num_requests += 1;
+                json_request_message(desc, value, &self.options)
+            }
+            Some(Err(e)) => {
+                // serde_json reports the position within this message; point
+                // at the whole input instead, and name the failing message
+                let (line, column) = absolute_position(&self.data, self.offset, &e);
+                let error = e.to_string();
+                let suffix = format!(" at line {} column {}", e.line(), e.column());
+                Err(ParseError::Error(GrpcurlError::Proto(format!(
+                    "invalid JSON in request message {} at line {line} column {column}: {}",
+                    self.num_requests + 1,
+                    error.strip_suffix(&suffix).unwrap_or(&error)
+                ))))
+            }
+            None => Err(ParseError::Eof),
+        }
+    }
+
+    /// Return the number of messages parsed so far.
+    pub fn num_requests(&self) -> usize {
+        self.num_requests
+    }
+}
+
+/// The 1-based line and column in `data` of a JSON error `e` found while
+/// parsing the message that starts at byte `start`.
+fn absolute_position(data: &str, start: usize, e: &serde_json::Error) -> (usize, usize) {
+    let before = &data[..start];
+    let line = before.matches('\n').count() + e.line();
+    if e.line() > 1 {
+        return (line, e.column());
+    }
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    (line, start - line_start + e.column())
+}
+
+/// Convert one parsed JSON request value into a message of type `desc`.
+fn json_request_message(
+    desc: &MessageDescriptor,
+    mut value: serde_json::Value,
+    options: &DeserializeOptions,
+) -> std::result::Result<DynamicMessage, ParseError> {
+    let parse_error = |e: String| {
+        ParseError::Error(GrpcurlError::Proto(format!(
+            "failed to parse JSON request: {e}"
+        )))
+    };
+    unwrap_oneofs(desc, &mut value).map_err(|e| parse_error(e.to_string()))?;
+    DynamicMessage::deserialize_with_options(desc.clone(), value, options)
+        .map_err(|e| parse_error(e.to_string()))
+}
+
+/// Newline-delimited JSON request parser, selected with `--ndjson-input`.
+///
+/// Each non-blank line holds exactly one JSON message and is parsed on its
+/// own, so a malformed line is reported by its line number instead of
+/// throwing off the boundaries of every message after it.
+pub struct NdjsonRequestParser {
+    data: String,
+    offset: usize,
+    line: usize,
+    num_requests: usize,
+    options: DeserializeOptions,
+}
+
+impl NdjsonRequestParser {
+    /// Create a new NDJSON request parser from the input data.
+    ///
+    /// If `data` is "@" or "@-", reads from stdin. Otherwise uses the string directly.
+    pub fn new(data: Option<&str>, options: &FormatOptions) -> Result<Self> {
+        let input = read_request_data(data)?;
+
+        let de_options =
+            DeserializeOptions::new().deny_unknown_fields(!options.allow_unknown_fields);
 
-                // Deserialize the JSON value into a DynamicMessage
-                let msg =
-                    DynamicMessage::deserialize_with_options(desc.clone(), value, &self.options)
-                        .map_err(|e| {
-                            ParseError::Error(GrpcurlError::Proto(format!(
-                                "failed to parse JSON request: {e}"
-                            )))
-                        })?;
+        Ok(NdjsonRequestParser {
+            data: input,
+            offset: 0,
+            line: 0,
+            num_requests: 0,
+            options: de_options,
+        })
+    }
 
-                Ok(msg)
+    /// Parse the message on the next non-blank line.
+    ///
+    /// Returns `ParseError::Eof` when there are no more lines.
+    pub fn next(
+        &mut self,
+        desc: &MessageDescriptor,
+    ) -> std::result::Result<DynamicMessage, ParseError> {
+        loop {
+            let remaining = &self.data[self.offset..];
+            if remaining.is_empty() {
+                return Err(ParseError::Eof);
             }
-            Some(Err(e)) => Err(ParseError::Error(GrpcurlError::Proto(format!(
-                "invalid JSON in request data: {e}"
-            )))),
-            None => Err(ParseError::Eof),
+            let (text, consumed) = match remaining.find('\n') {
+                Some(pos) => (&remaining[..pos], pos + 1),
+                None => (remaining, remaining.len()),
+            };
+            self.offset += consumed;
+            self.line += 1;
+
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let line = self.line;
+            let at_line =
+                |msg: String| ParseError::Error(GrpcurlError::Proto(format!("line {line}: {msg}")));
+            let value = serde_json::from_str(text)
+                .map_err(|e| at_line(format!("invalid JSON request: {e}")))?;
+            self.num_requests += 1;
+            return json_request_message(desc, value, &self.options).map_err(|e| match e {
+                ParseError::Error(GrpcurlError::Proto(msg)) => at_line(msg),
+                e => e,
+            });
         }
     }
 
@@ -162,33 +373,27 @@ impl JsonRequestParser {
 ///
 /// Equivalent to Go's `textRequestParser` (format.go:84-88).
 /// Messages are separated by the 0x1E record separator character.
+/// Like the JSON parser, unknown fields are an error unless
+/// [`FormatOptions::allow_unknown_fields`] is set.
 pub struct TextRequestParser {
     data: String,
     offset: usize,
     num_requests: usize,
+    allow_unknown_fields: bool,
 }
 
 impl TextRequestParser {
     /// Create a new text format request parser from the input data.
     ///
-    /// If `data` is "@", reads from stdin. Otherwise uses the string directly.
-    pub fn new(data: Option<&str>) -> Result<Self> {
-        let input = match data {
-            Some("@") => {
-                let mut buf = String::new();
-                io::stdin().read_to_string(&mut buf).map_err(|e| {
-                    GrpcurlError::Io(io::Error::new(e.kind(), format!("reading stdin: {e}")))
-                })?;
-                buf
-            }
-            Some(s) => s.to_string(),
-            None => String::new(),
-        };
+    /// If `data` is "@" or "@-", reads from stdin. Otherwise uses the string directly.
+    pub fn new(data: Option<&str>, options: &FormatOptions) -> Result<Self> {
+        let input = read_request_data(data)?;
 
         Ok(TextRequestParser {
             data: input,
             offset: 0,
             num_requests: 0,
+            allow_unknown_fields: options.allow_unknown_fields,
         })
     }
 
@@ -199,7 +404,9 @@ impl TextRequestParser {
     ///
     /// Matches Go behavior: on the first call with empty input, returns an
     /// empty DynamicMessage (empty text is a valid empty proto message).
-    /// Subsequent calls return `ParseError::Eof`.
+    /// Subsequent calls return `ParseError::Eof`. Unary and server-streaming
+    /// calls always send that one empty message; client and bidi streaming
+    /// callers can check [`TextRequestParser::is_empty`] first to send none.
     pub fn next(
         &mut self,
         desc: &MessageDescriptor,
@@ -237,7 +444,12 @@ impl TextRequestParser {
         self.offset += consumed;
         self.num_requests += 1;
 
-        DynamicMessage::parse_text_format(desc.clone(), text).map_err(|e| {
+        let text = if self.allow_unknown_fields {
+            strip_unknown_text_fields(desc, text)
+        } else {
+            Cow::Borrowed(text)
+        };
+        DynamicMessage::parse_text_format(desc.clone(), &text).map_err(|e| {
             ParseError::Error(GrpcurlError::Proto(format!(
                 "failed to parse text format request: {e}"
             )))
@@ -248,15 +460,315 @@ impl TextRequestParser {
     pub fn num_requests(&self) -> usize {
         self.num_requests
     }
+
+    /// Return true if the input contains nothing but whitespace.
+    pub fn is_empty(&self) -> bool {
+        self.data.trim().is_empty()
+    }
+}
+
+/// Request parser for binary messages given as base64, selected with
+/// `--request-type`.
+///
+/// Each whitespace-separated base64 blob is the encoding of one
+/// `request_type` message. The bytes must decode as that type, and are then
+/// read as the method's request type, so a compatible type's encoding can be
+/// sent as is; fields the request type does not know are kept as unknown
+/// fields.
+pub struct Base64RequestParser {
+    data: String,
+    offset: usize,
+    num_requests: usize,
+    request_type: MessageDescriptor,
+}
+
+impl Base64RequestParser {
+    /// Create a new base64 request parser from the input data.
+    ///
+    /// If `data` is "@" or "@-", reads from stdin. Otherwise uses the string directly.
+    pub fn new(data: Option<&str>, request_type: MessageDescriptor) -> Result<Self> {
+        Ok(Base64RequestParser {
+            data: read_request_data(data)?,
+            offset: 0,
+            num_requests: 0,
+            request_type,
+        })
+    }
+
+    /// Decode the next message from the input stream.
+    ///
+    /// Returns `ParseError::Eof` when there are no more messages.
+    pub fn next(
+        &mut self,
+        desc: &MessageDescriptor,
+    ) -> std::result::Result<DynamicMessage, ParseError> {
+        use base64::Engine;
+
+        let remaining = &self.data[self.offset..];
+        let trimmed = remaining.trim_start();
+        if trimmed.is_empty() {
+            return Err(ParseError::Eof);
+        }
+        let blob = trimmed.split_whitespace().next().unwrap_or_default();
+        self.offset += remaining.len() - trimmed.len() + blob.len();
+        self.num_requests += 1;
+
+        let parse_error = |msg: String| ParseError::Error(GrpcurlError::Proto(msg));
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(blob)
+            .map_err(|e| {
+                parse_error(format!(
+                    "invalid base64 in request message {}: {e}",
+                    self.num_requests
+                ))
+            })?;
+        DynamicMessage::decode(self.request_type.clone(), bytes.as_slice()).map_err(|e| {
+            parse_error(format!(
+                "failed to decode request message {} as {}: {e}",
+                self.num_requests,
+                self.request_type.full_name()
+            ))
+        })?;
+        DynamicMessage::decode(desc.clone(), bytes.as_slice()).map_err(|e| {
+            parse_error(format!(
+                "failed to decode request message {} as {}: {e}",
+                self.num_requests,
+                desc.full_name()
+            ))
+        })
+    }
+
+    /// Return the number of messages parsed so far.
+    pub fn num_requests(&self) -> usize {
+        self.num_requests
+    }
+}
+
+/// Remove the fields `desc` does not know from protobuf text format input.
+///
+/// prost-reflect's text parser always rejects unknown fields, so with
+/// `--allow-unknown-fields` they are cut out first, nested messages included.
+/// Input that does not scan cleanly is returned unchanged, leaving the parser
+/// to report the error.
+fn strip_unknown_text_fields<'a>(desc: &MessageDescriptor, text: &'a str) -> Cow<'a, str> {
+    let mut scanner = TextFieldScanner {
+        text,
+        pos: 0,
+        unknown: Vec::new(),
+    };
+    if scanner.message(Some(desc), None).is_none() || scanner.unknown.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut copied = 0;
+    for range in scanner.unknown {
+        stripped.push_str(&text[copied..range.start]);
+        // Keep the tokens on either side of the field apart
+        stripped.push(' ');
+        copied = range.end;
+    }
+    stripped.push_str(&text[copied..]);
+    Cow::Owned(stripped)
+}
+
+/// Walks protobuf text format just closely enough to find the byte ranges of
+/// unknown fields. Every method returns `None` on input it cannot follow.
+struct TextFieldScanner<'a> {
+    text: &'a str,
+    pos: usize,
+    unknown: Vec<Range<usize>>,
+}
+
+impl<'a> TextFieldScanner<'a> {
+    /// Scan fields until `end`, the closing bracket of a message value, or to
+    /// the end of input at the top level. With no descriptor the fields are
+    /// only skipped over, as inside the value of an unknown field.
+    fn message(&mut self, desc: Option<&MessageDescriptor>, end: Option<u8>) -> Option<()> {
+        loop {
+            match self.peek() {
+                next if next == end => {
+                    self.pos += usize::from(end.is_some());
+                    return Some(());
+                }
+                None => return None,
+                Some(b',' | b';') => self.pos += 1,
+                Some(_) => self.field(desc)?,
+            }
+        }
+    }
+
+    fn field(&mut self, desc: Option<&MessageDescriptor>) -> Option<()> {
+        let start = self.pos;
+        let name = if self.eat(b'[') {
+            let name_start = self.pos;
+            self.pos += self.text[name_start..].find(']')?;
+            let name: String = self.text[name_start..self.pos].split_whitespace().collect();
+            self.pos += 1;
+            name
+        } else {
+            self.word()?.to_string()
+        };
+
+        // The message type of the field's value, if any, and whether the
+        // field is known at all
+        let (value_desc, known) = match desc {
+            None => (None, true),
+            Some(desc) => match text_field_value_desc(desc, &name) {
+                Some(value_desc) => (value_desc, true),
+                None => (None, false),
+            },
+        };
+
+        self.eat(b':');
+        self.value(value_desc.as_ref())?;
+        if !known {
+            if matches!(self.peek(), Some(b',' | b';')) {
+                self.pos += 1;
+            }
+            self.unknown.push(start..self.pos);
+        }
+        Some(())
+    }
+
+    /// Scan one field value: a message, a list, or a scalar.
+    fn value(&mut self, desc: Option<&MessageDescriptor>) -> Option<()> {
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                self.message(desc, Some(b'}'))
+            }
+            b'<' => {
+                self.pos += 1;
+                self.message(desc, Some(b'>'))
+            }
+            b'[' => {
+                self.pos += 1;
+                if self.eat(b']') {
+                    return Some(());
+                }
+                loop {
+                    self.value(desc)?;
+                    if self.eat(b']') {
+                        return Some(());
+                    }
+                    if !self.eat(b',') {
+                        return None;
+                    }
+                }
+            }
+            // Adjacent string literals are concatenated
+            b'"' | b'\'' => {
+                while matches!(self.peek(), Some(b'"' | b'\'')) {
+                    self.string()?;
+                }
+                Some(())
+            }
+            _ => {
+                if self.word()? == "-" {
+                    self.word()?;
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// A run of identifier or number characters: a field name, enum value,
+    /// number, or `true`/`false`.
+    fn word(&mut self) -> Option<&'a str> {
+        self.skip_space();
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len()
+            && (bytes[self.pos].is_ascii_alphanumeric() || b"_.+-".contains(&bytes[self.pos]))
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| &self.text[start..self.pos])
+    }
+
+    fn string(&mut self) -> Option<()> {
+        let bytes = self.text.as_bytes();
+        let quote = bytes[self.pos];
+        self.pos += 1;
+        while self.pos < bytes.len() {
+            match bytes[self.pos] {
+                b'\\' => self.pos += 2,
+                b if b == quote => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+
+    fn skip_space(&mut self) {
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len() {
+            match bytes[self.pos] {
+                b'#' => {
+                    while self.pos < bytes.len() && bytes[self.pos] != b'\n' {
+                        self.pos += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_space();
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        self.pos += usize::from(found);
+        found
+    }
+}
+
+/// Look up a field of `desc` by the name text format uses for it, returning
+/// the message type of its value (`None` for scalars). Returns `None` overall
+/// for unknown fields. Bracketed names are extensions or, in an `Any`, a type
+/// URL; a URL naming an unknown type is left for the parser to report.
+fn text_field_value_desc(
+    desc: &MessageDescriptor,
+    name: &str,
+) -> Option<Option<MessageDescriptor>> {
+    if desc.full_name() == "google.protobuf.Any" {
+        if let Some((_, type_name)) = name.rsplit_once('/') {
+            return Some(desc.parent_pool().get_message_by_name(type_name));
+        }
+    }
+    if let Some(extension) = desc.get_extension_by_full_name(name) {
+        return Some(extension.kind().as_message().cloned());
+    }
+    // Groups are named by their message type, the field name capitalized
+    let field = desc
+        .get_field_by_name(name)
+        .filter(|field| !field.is_group())
+        .or_else(|| {
+            desc.get_field_by_name(&name.to_ascii_lowercase())
+                .filter(|field| {
+                    field.is_group() && field.kind().as_message().map(|m| m.name()) == Some(name)
+                })
+        })?;
+    Some(field.kind().as_message().cloned())
 }
 
 /// Unified request parser that dispatches to the appropriate format.
 ///
-/// This enum wraps either a JSON or text format parser, providing a
+/// This enum wraps a JSON, NDJSON, or text format parser, providing a
 /// common interface for the invocation engine.
 pub enum RequestParser {
     Json(JsonRequestParser),
+    Ndjson(NdjsonRequestParser),
     Text(TextRequestParser),
+    Base64(Base64RequestParser),
 }
 
 impl RequestParser {
@@ -267,7 +779,9 @@ impl RequestParser {
     ) -> std::result::Result<DynamicMessage, ParseError> {
         match self {
             RequestParser::Json(p) => p.next(desc),
+            RequestParser::Ndjson(p) => p.next(desc),
             RequestParser::Text(p) => p.next(desc),
+            RequestParser::Base64(p) => p.next(desc),
         }
     }
 
@@ -275,9 +789,16 @@ impl RequestParser {
     pub fn num_requests(&self) -> usize {
         match self {
             RequestParser::Json(p) => p.num_requests(),
+            RequestParser::Ndjson(p) => p.num_requests(),
             RequestParser::Text(p) => p.num_requests(),
+            RequestParser::Base64(p) => p.num_requests(),
         }
     }
+
+    /// Return true if this is a text parser whose input is empty.
+    pub fn is_empty_text(&self) -> bool {
+        matches!(self, RequestParser::Text(p) if p.is_empty())
+    }
 }
 
 /// Create a template DynamicMessage with default values for all fields.
@@ -395,43 +916,73 @@ fn make_template_inner(desc: &MessageDescriptor, path: &mut Vec<String>) -> Dyna
     let mut msg = DynamicMessage::new(desc.clone());
 
     for field in desc.fields() {
-        if field.is_map() {
-            // Map field: add one entry with default key and value
-            let kind = field.kind();
-            let entry_desc = kind.as_message().expect("map field has message type");
-            let key_field = entry_desc.get_field(1).expect("map entry has key field");
-            let value_field = entry_desc.get_field(2).expect("map entry has value field");
-
-            let key = default_map_key(&key_field);
-            let value = if let prost_reflect::Kind::Message(value_desc) = value_field.kind() {
-                prost_reflect::Value::Message(make_template_inner(&value_desc, path))
-            } else {
-                default_value_for_kind(&value_field)
-            };
+        match field_shape(&field) {
+            FieldShape::Map { key, value } => {
+                // Map field: add one entry with default key and value
+                let key = default_map_key(&key);
+                let value = if let prost_reflect::Kind::Message(value_desc) = value.kind() {
+                    prost_reflect::Value::Message(make_template_inner(&value_desc, path))
+                } else {
+                    default_value_for_kind(&value)
+                };
 
-            let mut map = std::collections::HashMap::new();
-            map.insert(key, value);
-            msg.set_field(&field, prost_reflect::Value::Map(map));
-        } else if field.is_list() {
-            // Repeated field: add one default element
-            let element = if let prost_reflect::Kind::Message(elem_desc) = field.kind() {
-                prost_reflect::Value::Message(make_template_inner(&elem_desc, path))
-            } else {
-                default_value_for_kind(&field)
-            };
-            msg.set_field(&field, prost_reflect::Value::List(vec![element]));
-        } else if let prost_reflect::Kind::Message(sub_desc) = field.kind() {
-            // Non-repeated message field: recursively populate
-            let sub_msg = make_template_inner(&sub_desc, path);
-            msg.set_field(&field, prost_reflect::Value::Message(sub_msg));
+                let mut map = std::collections::HashMap::new();
+                map.insert(key, value);
+                msg.set_field(&field, prost_reflect::Value::Map(map));
+            }
+            FieldShape::List => {
+                // Repeated field: add one default element
+                let element = if let prost_reflect::Kind::Message(elem_desc) = field.kind() {
+                    prost_reflect::Value::Message(make_template_inner(&elem_desc, path))
+                } else {
+                    default_value_for_kind(&field)
+                };
+                msg.set_field(&field, prost_reflect::Value::List(vec![element]));
+            }
+            FieldShape::Single => {
+                // Non-repeated message field: recursively populate
+                if let prost_reflect::Kind::Message(sub_desc) = field.kind() {
+                    let sub_msg = make_template_inner(&sub_desc, path);
+                    msg.set_field(&field, prost_reflect::Value::Message(sub_msg));
+                }
+                // Scalar non-repeated fields: leave at defaults (emit_defaults will show them)
+            }
         }
-        // Scalar non-repeated fields: leave at defaults (emit_defaults will show them)
     }
 
     path.pop();
     msg
 }
 
+/// How a field's value is laid out in JSON, shared by `make_template` and
+/// `make_json_schema` so both walk a message's fields the same way.
+enum FieldShape {
+    /// A map field, with the key and value fields of its entry type.
+    Map {
+        key: prost_reflect::FieldDescriptor,
+        value: prost_reflect::FieldDescriptor,
+    },
+    /// A repeated field; elements have the field's own kind.
+    List,
+    /// A single value of the field's kind.
+    Single,
+}
+
+fn field_shape(field: &prost_reflect::FieldDescriptor) -> FieldShape {
+    if field.is_map() {
+        let kind = field.kind();
+        let entry_desc = kind.as_message().expect("map field has message type");
+        FieldShape::Map {
+            key: entry_desc.get_field(1).expect("map entry has key field"),
+            value: entry_desc.get_field(2).expect("map entry has value field"),
+        }
+    } else if field.is_list() {
+        FieldShape::List
+    } else {
+        FieldShape::Single
+    }
+}
+
 /// Return a default MapKey for a given field descriptor.
 fn default_map_key(field: &prost_reflect::FieldDescriptor) -> prost_reflect::MapKey {
     use prost_reflect::Kind;
@@ -467,6 +1018,160 @@ fn default_value_for_kind(field: &prost_reflect::FieldDescriptor) -> prost_refle
     }
 }
 
+/// Create a JSON Schema (draft-07) for the JSON accepted as a message.
+///
+/// Walks the same fields as `make_template`: each message becomes an object
+/// schema under `definitions`, keyed by its full name and referenced with
+/// `$ref`, so recursive types are described once. Each field is listed
+/// under both its JSON name and its proto name, since request parsing
+/// accepts either; enums take their value names or numbers; and well-known
+/// types follow their proto3 JSON mapping (e.g. Timestamp is a `date-time`
+/// string).
+pub fn make_json_schema(desc: &MessageDescriptor) -> serde_json::Value {
+    let mut definitions = serde_json::Map::new();
+    let root = message_schema_ref(desc, &mut definitions);
+    let mut schema = serde_json::Map::new();
+    schema.insert(
+        "$schema".into(),
+        "http://json-schema.org/draft-07/schema#".into(),
+    );
+    schema.insert("title".into(), desc.full_name().into());
+    if let serde_json::Value::Object(root) = root {
+        schema.extend(root);
+    }
+    if !definitions.is_empty() {
+        schema.insert("definitions".into(), definitions.into());
+    }
+    schema.into()
+}
+
+/// The schema for a message-typed value: a well-known type's JSON mapping,
+/// or a `$ref` to its object schema, which is added to `definitions`.
+fn message_schema_ref(
+    desc: &MessageDescriptor,
+    definitions: &mut serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    use serde_json::json;
+
+    let full_name = desc.full_name();
+    match full_name {
+        TIMESTAMP_TYPE => return json!({"type": "string", "format": "date-time"}),
+        DURATION_TYPE => return json!({"type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?s$"}),
+        "google.protobuf.FieldMask" => return json!({"type": "string"}),
+        "google.protobuf.Struct" => return json!({"type": "object"}),
+        "google.protobuf.ListValue" => return json!({"type": "array"}),
+        // Any JSON value at all
+        "google.protobuf.Value" => return json!({}),
+        "google.protobuf.Any" => {
+            return json!({
+                "type": "object",
+                "properties": {"@type": {"type": "string"}},
+                "required": ["@type"],
+            })
+        }
+        // Wrappers are written as their bare value
+        "google.protobuf.DoubleValue"
+        | "google.protobuf.FloatValue"
+        | "google.protobuf.Int64Value"
+        | "google.protobuf.UInt64Value"
+        | "google.protobuf.Int32Value"
+        | "google.protobuf.UInt32Value"
+        | "google.protobuf.BoolValue"
+        | "google.protobuf.StringValue"
+        | "google.protobuf.BytesValue" => {
+            if let Some(value) = desc.get_field_by_name("value") {
+                return kind_schema(&value.kind(), definitions);
+            }
+        }
+        _ => {}
+    }
+
+    let reference = json!({ "$ref": format!("#/definitions/{full_name}") });
+    // Cycle detection: a type already defined, or being defined, is only
+    // referenced
+    if definitions.contains_key(full_name) {
+        return reference;
+    }
+    definitions.insert(full_name.to_string(), serde_json::Value::Null);
+
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    // Required fields whose JSON and proto names differ may be given under
+    // either name
+    let mut required_either = Vec::new();
+    for field in desc.fields() {
+        let schema = match field_shape(&field) {
+            FieldShape::Map { value, .. } => json!({
+                "type": "object",
+                "additionalProperties": kind_schema(&value.kind(), definitions),
+            }),
+            FieldShape::List => {
+                json!({"type": "array", "items": kind_schema(&field.kind(), definitions)})
+            }
+            FieldShape::Single => kind_schema(&field.kind(), definitions),
+        };
+        let json_name = field.json_name();
+        let proto_name = field.name();
+        if field.cardinality() == prost_reflect::Cardinality::Required {
+            if json_name == proto_name {
+                required.push(serde_json::Value::from(json_name));
+            } else {
+                required_either.push(json!({
+                    "anyOf": [{"required": [json_name]}, {"required": [proto_name]}],
+                }));
+            }
+        }
+        if json_name != proto_name {
+            properties.insert(json_name.to_string(), schema.clone());
+        }
+        properties.insert(proto_name.to_string(), schema);
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    });
+    if !required.is_empty() {
+        schema["required"] = required.into();
+    }
+    if !required_either.is_empty() {
+        schema["allOf"] = required_either.into();
+    }
+    definitions.insert(full_name.to_string(), schema);
+    reference
+}
+
+/// The schema for a single (not repeated) value of the given kind, following
+/// the proto3 JSON mapping that request parsing accepts.
+fn kind_schema(
+    kind: &prost_reflect::Kind,
+    definitions: &mut serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    use prost_reflect::Kind;
+    use serde_json::json;
+
+    match kind {
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => json!({"type": "integer"}),
+        Kind::Uint32 | Kind::Fixed32 => json!({"type": "integer", "minimum": 0}),
+        // 64-bit integers are written as strings, but numbers are accepted
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => json!({"type": ["integer", "string"]}),
+        Kind::Uint64 | Kind::Fixed64 => json!({"type": ["integer", "string"]}),
+        // Strings allow "NaN", "Infinity" and "-Infinity"
+        Kind::Double | Kind::Float => json!({"type": ["number", "string"]}),
+        Kind::Bool => json!({"type": "boolean"}),
+        Kind::String => json!({"type": "string"}),
+        Kind::Bytes => json!({"type": "string", "contentEncoding": "base64"}),
+        Kind::Enum(e) if e.full_name() == "google.protobuf.NullValue" => json!({"type": "null"}),
+        Kind::Enum(e) => {
+            // Values are written by name, but numbers are accepted too
+            let names: Vec<String> = e.values().map(|v| v.name().to_string()).collect();
+            json!({"anyOf": [{"type": "string", "enum": names}, {"type": "integer"}]})
+        }
+        Kind::Message(m) => message_schema_ref(m, definitions),
+    }
+}
+
 /// Type alias for a response formatter function.
 ///
 /// Equivalent to Go's `Formatter` type (format.go:129).
@@ -474,56 +1179,864 @@ pub type Formatter = Box<dyn Fn(&DynamicMessage) -> Result<String>>;
 
 /// Create a JSON response formatter.
 ///
-/// Produces pretty-printed JSON with 2-space indentation.
-/// If `emit_defaults` is true, includes fields with default/zero values.
+/// Produces pretty-printed JSON, indented with `json_indent` (two spaces by
+/// default). If `emit_defaults` is true, includes fields with default/zero values.
+///
+/// `resolver` is the descriptor pool used to expand `google.protobuf.Any`
+/// fields. When given, each message is re-read against it so embedded Any
+/// values resolve to any type the invocation's descriptor source knows about,
+/// not only those in the method's own files.
 ///
 /// Equivalent to Go's `NewJSONFormatter()` (format.go:137-157).
-pub fn json_formatter(options: &FormatOptions) -> Formatter {
+pub fn json_formatter(options: &FormatOptions, resolver: Option<DescriptorPool>) -> Formatter {
+    make_json_formatter(options, resolver, true)
+}
+
+/// Create a newline-delimited JSON response formatter.
+///
+/// Each message is rendered as a single line of compact JSON, with no
+/// record separators between messages, so streamed responses can be piped
+/// straight into log processors. Options and `resolver` behave as in
+/// [`json_formatter`].
+pub fn ndjson_formatter(options: &FormatOptions, resolver: Option<DescriptorPool>) -> Formatter {
+    make_json_formatter(options, resolver, false)
+}
+
+fn make_json_formatter(
+    options: &FormatOptions,
+    resolver: Option<DescriptorPool>,
+    pretty: bool,
+) -> Formatter {
     let serialize_options = SerializeOptions::new()
         .skip_default_fields(!options.emit_defaults)
         .stringify_64_bit_integers(true);
 
+    let raw_any = options.raw_any;
+    let oneof_wrapped = options.oneof_wrapped;
+    let humanize_time = options.humanize_time;
+    let indent = options
+        .json_indent
+        .clone()
+        .unwrap_or_else(|| "  ".to_string());
+
     Box::new(move |msg: &DynamicMessage| {
-        let mut buf = Vec::new();
-        let mut serializer = serde_json::Serializer::pretty(&mut buf);
+        use prost_reflect::ReflectMessage;
+        use serde::Serialize;
+        use serde_json::ser::{CompactFormatter, PrettyFormatter};
+
+        // Whole-valued floats are written as "42", not "42.0", like Go's
+        // encoding/json
+        let pretty_formatter = || GoFloatFormatter(PrettyFormatter::with_indent(indent.as_bytes()));
+        let compact_formatter = || GoFloatFormatter(CompactFormatter);
+        let serialize = |msg: &DynamicMessage| -> serde_json::Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            if raw_any || oneof_wrapped || humanize_time {
+                let mut value = if raw_any {
+                    raw_any_json(msg, &serialize_options)?
+                } else {
+                    msg.serialize_with_options(serde_json::value::Serializer, &serialize_options)?
+                };
+                // Before oneofs are wrapped, while fields are still where
+                // the descriptor says
+                if humanize_time {
+                    humanize_times(&msg.descriptor(), &mut value);
+                }
+                if oneof_wrapped {
+                    wrap_oneofs(&msg.descriptor(), &mut value);
+                }
+                if pretty {
+                    value.serialize(&mut serde_json::Serializer::with_formatter(
+                        &mut buf,
+                        pretty_formatter(),
+                    ))?;
+                } else {
+                    value.serialize(&mut serde_json::Serializer::with_formatter(
+                        &mut buf,
+                        compact_formatter(),
+                    ))?;
+                }
+            } else if pretty {
+                msg.serialize_with_options(
+                    &mut serde_json::Serializer::with_formatter(&mut buf, pretty_formatter()),
+                    &serialize_options,
+                )?;
+            } else {
+                msg.serialize_with_options(
+                    &mut serde_json::Serializer::with_formatter(&mut buf, compact_formatter()),
+                    &serialize_options,
+                )?;
+            }
+            Ok(buf)
+        };
 
-        msg.serialize_with_options(&mut serializer, &serialize_options)
+        let resolved = resolver
+            .as_ref()
+            .filter(|_| !raw_any)
+            .and_then(|pool| resolve_in_pool(msg, pool));
+        let msg = resolved.as_ref().unwrap_or(msg);
+        let buf = serialize(msg)
+            .or_else(|err| {
+                // A google.protobuf.Value cannot hold NaN or Infinity as a
+                // number, so retry with those written as strings
+                let mut finite = msg.clone();
+                if stringify_non_finite_values(&mut finite) {
+                    serialize(&finite)
+                } else {
+                    Err(err)
+                }
+            })
             .map_err(|e| GrpcurlError::Proto(format!("failed to format response as JSON: {e}")))?;
 
-        let json = String::from_utf8(buf)
-            .map_err(|e| GrpcurlError::Proto(format!("JSON output is not valid UTF-8: {e}")))?;
+        String::from_utf8(buf)
+            .map_err(|e| GrpcurlError::Proto(format!("JSON output is not valid UTF-8: {e}")))
+    })
+}
+
+/// A JSON formatter that writes whole-valued floats without the trailing
+/// ".0" serde_json adds (`42` rather than `42.0`), matching Go's
+/// encoding/json, and otherwise behaves like the formatter it wraps.
+///
+/// Only the layout methods are forwarded: `PrettyFormatter` and
+/// `CompactFormatter` use the default for everything else.
+struct GoFloatFormatter<F>(F);
+
+impl<F: serde_json::ser::Formatter> serde_json::ser::Formatter for GoFloatFormatter<F> {
+    fn write_f32<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f32) -> io::Result<()> {
+        if is_whole_float(f64::from(value)) {
+            write!(writer, "{value}")
+        } else {
+            self.0.write_f32(writer, value)
+        }
+    }
+
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        if is_whole_float(value) {
+            write!(writer, "{value}")
+        } else {
+            self.0.write_f64(writer, value)
+        }
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_key(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+/// Whether serde_json would write `value` as digits followed by ".0". Larger
+/// whole values are written with an exponent, which Go shares.
+fn is_whole_float(value: f64) -> bool {
+    value.fract() == 0.0 && value.abs() < 1e16
+}
+
+/// Convert a message to JSON with every `google.protobuf.Any` left unexpanded.
+///
+/// prost-reflect always expands Any, so fields that can hold one are cleared
+/// before serializing the rest of the message, then filled back in by hand:
+/// Any values become `{"@type", "value"}` objects and other messages recurse.
+fn raw_any_json(
+    msg: &DynamicMessage,
+    options: &SerializeOptions,
+) -> std::result::Result<serde_json::Value, serde_json::Error> {
+    use prost_reflect::{Kind, ReflectMessage};
+
+    let desc = msg.descriptor();
+    if desc.full_name() == ANY_TYPE {
+        return Ok(raw_any_value(msg));
+    }
+
+    let any_fields: Vec<_> = desc
+        .fields()
+        .filter(|field| match field.kind() {
+            Kind::Message(m) => may_contain_any(&m, &mut Vec::new()),
+            _ => false,
+        })
+        .collect();
+    if any_fields.is_empty() {
+        return msg.serialize_with_options(serde_json::value::Serializer, options);
+    }
+
+    let mut stripped = msg.clone();
+    for field in &any_fields {
+        stripped.clear_field(field);
+    }
+    let mut value = stripped.serialize_with_options(serde_json::value::Serializer, options)?;
+    let Some(obj) = value.as_object_mut() else {
+        return Ok(value);
+    };
+
+    for field in any_fields.iter().filter(|f| msg.has_field(f)) {
+        let json = match &*msg.get_field(field) {
+            prost_reflect::Value::Message(m) => raw_any_json(m, options)?,
+            prost_reflect::Value::List(items) => serde_json::Value::Array(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_message())
+                    .map(|m| raw_any_json(m, options))
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            prost_reflect::Value::Map(entries) => {
+                let mut map = serde_json::Map::new();
+                for (key, item) in entries {
+                    if let Some(m) = item.as_message() {
+                        map.insert(map_key_string(key), raw_any_json(m, options)?);
+                    }
+                }
+                serde_json::Value::Object(map)
+            }
+            _ => continue,
+        };
+        obj.insert(field.json_name().to_string(), json);
+    }
+    Ok(value)
+}
+
+/// Rewrite each set oneof in a message's JSON as a wrapper object,
+/// `"<oneof>": {"which": "<member>", "value": ...}`, recursing into nested
+/// messages first.
+///
+/// Well-known types are left alone, since their JSON forms are not objects
+/// of their fields.
+fn wrap_oneofs(desc: &MessageDescriptor, json: &mut serde_json::Value) {
+    let Some(obj) = json.as_object_mut() else {
+        return;
+    };
+    if is_well_known_type(desc) {
+        return;
+    }
+
+    for field in desc.fields() {
+        if let Some(value) = obj.get_mut(field.json_name()) {
+            for (inner_desc, inner) in message_json_values(&field, value) {
+                wrap_oneofs(&inner_desc, inner);
+            }
+        }
+    }
+
+    // Rebuild the object so each wrapper takes its member's place and the
+    // other fields keep their order
+    let mut oneof_members = std::collections::HashMap::new();
+    for oneof in desc.oneofs().filter(|oneof| !oneof.is_synthetic()) {
+        for field in oneof.fields() {
+            oneof_members.insert(field.json_name().to_string(), oneof.name().to_string());
+        }
+    }
+    for (key, value) in std::mem::take(obj) {
+        match oneof_members.get(key.as_str()) {
+            Some(oneof) => {
+                let wrapped = serde_json::json!({ "which": key, "value": value });
+                obj.insert(oneof.clone(), wrapped);
+            }
+            None => {
+                obj.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Rewrite the `google.protobuf.Duration` and `Timestamp` values in a
+/// message's JSON for reading: durations as `3s 120ms`, and timestamps in the
+/// local time zone as `2024-05-01 14:03:07.250 +02:00`. Values not in their
+/// canonical proto3 JSON
+/// form are left as they are.
+fn humanize_times(desc: &MessageDescriptor, json: &mut serde_json::Value) {
+    let humanized = match desc.full_name() {
+        DURATION_TYPE => json.as_str().and_then(humanize_duration),
+        TIMESTAMP_TYPE => json
+            .as_str()
+            .and_then(|s| humanize_timestamp(s, local_utc_offset)),
+        ANY_TYPE => {
+            if let Some((inner_desc, inner)) = any_payload_json(desc, json) {
+                humanize_times(&inner_desc, inner);
+            }
+            return;
+        }
+        _ => {
+            let Some(obj) = json.as_object_mut() else {
+                return;
+            };
+            if is_well_known_type(desc) {
+                return;
+            }
+            for field in desc.fields() {
+                if let Some(value) = obj.get_mut(field.json_name()) {
+                    for (inner_desc, inner) in message_json_values(&field, value) {
+                        humanize_times(&inner_desc, inner);
+                    }
+                }
+            }
+            return;
+        }
+    };
+    if let Some(humanized) = humanized {
+        *json = serde_json::Value::String(humanized);
+    }
+}
+
+/// Render a proto3 JSON duration such as `"3723.120s"` as `1h 2m 3s 120ms`,
+/// leaving out units that are zero.
+fn humanize_duration(duration: &str) -> Option<String> {
+    let secs = duration.strip_suffix('s')?;
+    let (sign, secs) = match secs.strip_prefix('-') {
+        Some(secs) => ("-", secs),
+        None => ("", secs),
+    };
+    let (whole, frac) = secs.split_once('.').unwrap_or((secs, ""));
+    if !whole.bytes().all(|b| b.is_ascii_digit())
+        || frac.len() > 9
+        || !frac.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let whole: u64 = whole.parse().ok()?;
+    let nanos: u64 = format!("{frac:0<9}").parse().ok()?;
+
+    let parts: Vec<String> = [
+        (whole / 3600, "h"),
+        (whole / 60 % 60, "m"),
+        (whole % 60, "s"),
+        (nanos / 1_000_000, "ms"),
+        (nanos / 1000 % 1000, "µs"),
+        (nanos % 1000, "ns"),
+    ]
+    .into_iter()
+    .filter(|&(n, _)| n > 0)
+    .map(|(n, unit)| format!("{n}{unit}"))
+    .collect();
+    if parts.is_empty() {
+        return Some("0s".to_string());
+    }
+    Some(format!("{sign}{}", parts.join(" ")))
+}
+
+/// Render a proto3 JSON timestamp such as `"2024-05-01T12:03:07.250Z"` as a
+/// date and time in the zone given by `utc_offset`, which maps seconds since
+/// the Unix epoch to that zone's offset from UTC in seconds, followed by the
+/// offset: `2024-05-01 14:03:07.250 +02:00`.
+fn humanize_timestamp(timestamp: &str, utc_offset: fn(i64) -> i64) -> Option<String> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let (clock, frac) = match time.split_once('.') {
+        Some((clock, frac)) => (clock, Some(frac)),
+        None => (time, None),
+    };
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if frac.is_some_and(|frac| !is_number(frac)) {
+        return None;
+    }
+    let fields = |s: &str, sep: char| -> Option<Vec<i64>> {
+        s.split(sep)
+            .map(|part| is_number(part).then(|| part.parse().ok()).flatten())
+            .collect()
+    };
+    let (&[year, month, day], &[hour, minute, second]) =
+        (&fields(date, '-')?[..], &fields(clock, ':')?[..])
+    else {
+        return None;
+    };
+
+    let utc = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+    let offset = utc_offset(utc);
+    let local = utc + offset;
+    let (year, month, day) = civil_from_days(local.div_euclid(86400));
+    let secs = local.rem_euclid(86400);
+    let frac = frac.map(|frac| format!(".{frac}")).unwrap_or_default();
+    let offset_sign = if offset < 0 { '-' } else { '+' };
+    Some(format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}{frac} {offset_sign}{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        offset.abs() / 3600,
+        offset.abs() / 60 % 60,
+    ))
+}
+
+/// Days from 1970-01-01 to the given proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The proleptic Gregorian date `days` after 1970-01-01, as year, month, day.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// The local time zone's offset from UTC, in seconds, at `secs` since the
+/// Unix epoch. Honors `TZ`.
+#[cfg(unix)]
+fn local_utc_offset(secs: i64) -> i64 {
+    let time = secs as libc::time_t;
+    // SAFETY: an all-zero `tm` is valid, and localtime_r only writes to it.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    let local = days_from_civil(
+        i64::from(tm.tm_year) + 1900,
+        i64::from(tm.tm_mon) + 1,
+        i64::from(tm.tm_mday),
+    ) * 86400
+        + i64::from(tm.tm_hour) * 3600
+        + i64::from(tm.tm_min) * 60
+        + i64::from(tm.tm_sec);
+    local - secs
+}
+
+/// Without a portable way to find the local time zone, timestamps stay in UTC.
+#[cfg(not(unix))]
+fn local_utc_offset(_secs: i64) -> i64 {
+    0
+}
+
+/// The message type and JSON of the payload of an expanded
+/// `google.protobuf.Any`, resolved from `@type` in the pool of `desc`.
+/// Well-known types hold their JSON under `value`; other messages are the
+/// `Any` object itself, alongside `@type`.
+fn any_payload_json<'a>(
+    desc: &MessageDescriptor,
+    json: &'a mut serde_json::Value,
+) -> Option<(MessageDescriptor, &'a mut serde_json::Value)> {
+    let type_url = json.get("@type")?.as_str()?;
+    let type_name = type_url.rsplit('/').next()?;
+    let inner_desc = desc.parent_pool().get_message_by_name(type_name)?;
+    if is_well_known_type(&inner_desc) {
+        let value = json.get_mut("value")?;
+        Some((inner_desc, value))
+    } else {
+        Some((inner_desc, json))
+    }
+}
+
+/// Turn oneofs written in the wrapped form back into plain member fields, so
+/// requests can use either form. `which` may be the member's proto or JSON
+/// name. Objects under a oneof's name without a `which` are left as they are.
+fn unwrap_oneofs(
+    desc: &MessageDescriptor,
+    json: &mut serde_json::Value,
+) -> std::result::Result<(), String> {
+    let Some(obj) = json.as_object_mut() else {
+        return Ok(());
+    };
+    if is_well_known_type(desc) {
+        return Ok(());
+    }
+
+    for oneof in desc.oneofs().filter(|oneof| !oneof.is_synthetic()) {
+        let Some(wrapper) = obj.get(oneof.name()).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        let Some(which) = wrapper.get("which").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let field = oneof
+            .fields()
+            .find(|f| f.name() == which || f.json_name() == which)
+            .ok_or_else(|| format!("oneof \"{}\" has no member \"{which}\"", oneof.name()))?;
+        let value = wrapper
+            .get("value")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        obj.remove(oneof.name());
+        obj.insert(field.json_name().to_string(), value);
+    }
 
-        // Post-process to match Go's float formatting: strip trailing ".0" from
-        // whole-valued doubles (e.g., "42.0" -> "42"). Go's encoding/json omits
-        // the decimal point for whole numbers, while serde_json always includes it.
-        Ok(normalize_json_floats(&json))
+    for field in desc.fields() {
+        let key = if obj.contains_key(field.json_name()) {
+            field.json_name()
+        } else {
+            field.name()
+        };
+        if let Some(value) = obj.get_mut(key) {
+            for (inner_desc, inner) in message_json_values(&field, value) {
+                unwrap_oneofs(&inner_desc, inner)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The JSON values of the messages held by `field`: the value itself for a
+/// singular message, each element of a list, or each value of a map.
+fn message_json_values<'a>(
+    field: &prost_reflect::FieldDescriptor,
+    value: &'a mut serde_json::Value,
+) -> Vec<(MessageDescriptor, &'a mut serde_json::Value)> {
+    let Some(desc) = field.kind().as_message().cloned() else {
+        return Vec::new();
+    };
+    if field.is_map() {
+        let value_field = desc.map_entry_value_field();
+        let Some(value_desc) = value_field.kind().as_message().cloned() else {
+            return Vec::new();
+        };
+        match value.as_object_mut() {
+            Some(entries) => entries
+                .values_mut()
+                .map(|v| (value_desc.clone(), v))
+                .collect(),
+            None => Vec::new(),
+        }
+    } else if field.is_list() {
+        match value.as_array_mut() {
+            Some(items) => items.iter_mut().map(|v| (desc.clone(), v)).collect(),
+            None => Vec::new(),
+        }
+    } else {
+        vec![(desc, value)]
+    }
+}
+
+/// Whether a message is one of the `google.protobuf` well-known types, whose
+/// JSON mappings are special.
+fn is_well_known_type(desc: &MessageDescriptor) -> bool {
+    desc.package_name() == "google.protobuf"
+}
+
+const ANY_TYPE: &str = "google.protobuf.Any";
+const DURATION_TYPE: &str = "google.protobuf.Duration";
+const TIMESTAMP_TYPE: &str = "google.protobuf.Timestamp";
+const VALUE_TYPE: &str = "google.protobuf.Value";
+
+/// Replace non-finite numbers held in `google.protobuf.Value` messages with
+/// the strings `"NaN"`, `"Infinity"`, and `"-Infinity"`.
+///
+/// prost-reflect already writes non-finite float and double fields as those
+/// strings, per the proto3 JSON mapping, but refuses them inside a `Value`,
+/// whose JSON form is a bare number. Returns whether anything was replaced.
+fn stringify_non_finite_values(msg: &mut DynamicMessage) -> bool {
+    use prost_reflect::{ReflectMessage, Value};
+
+    let desc = msg.descriptor();
+    let number = (desc.full_name() == VALUE_TYPE && msg.has_field_by_name("number_value"))
+        .then(|| msg.get_field_by_name("number_value")?.as_f64())
+        .flatten();
+    if let Some(number) = number {
+        if number.is_finite() {
+            return false;
+        }
+        let text = if number.is_nan() {
+            "NaN"
+        } else if number > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        };
+        msg.set_field_by_name("string_value", Value::String(text.to_string()));
+        return true;
+    }
+
+    // Anything else, including a Value holding a Struct or ListValue
+    let mut changed = false;
+    for field in desc.fields() {
+        if field.kind().as_message().is_none() || !msg.has_field(&field) {
+            continue;
+        }
+        match msg.get_field_mut(&field) {
+            Value::Message(inner) => changed |= stringify_non_finite_values(inner),
+            Value::List(items) => {
+                for item in items {
+                    if let Value::Message(inner) = item {
+                        changed |= stringify_non_finite_values(inner);
+                    }
+                }
+            }
+            Value::Map(entries) => {
+                for item in entries.values_mut() {
+                    if let Value::Message(inner) = item {
+                        changed |= stringify_non_finite_values(inner);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+/// Whether a message of this type can hold an Any anywhere inside it.
+fn may_contain_any(desc: &MessageDescriptor, visited: &mut Vec<String>) -> bool {
+    use prost_reflect::Kind;
+
+    if desc.full_name() == ANY_TYPE {
+        return true;
+    }
+    if visited.iter().any(|name| name == desc.full_name()) {
+        return false;
+    }
+    visited.push(desc.full_name().to_string());
+    desc.fields().any(|field| match field.kind() {
+        Kind::Message(m) => may_contain_any(&m, visited),
+        _ => false,
+    })
+}
+
+/// The wire form of an Any message: its type URL and base64-encoded bytes.
+fn raw_any_value(any: &DynamicMessage) -> serde_json::Value {
+    use base64::Engine;
+
+    let type_url = any
+        .get_field_by_name("type_url")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let bytes = any
+        .get_field_by_name("value")
+        .and_then(|v| v.as_bytes().cloned())
+        .unwrap_or_default();
+    serde_json::json!({
+        "@type": type_url,
+        "value": base64::engine::general_purpose::STANDARD.encode(bytes),
     })
 }
 
-/// Strip trailing ".0" from whole-valued JSON numbers to match Go's encoding/json.
+/// Render a map key the way protobuf JSON does: always as a string.
+fn map_key_string(key: &prost_reflect::MapKey) -> String {
+    use prost_reflect::MapKey;
+
+    match key {
+        MapKey::Bool(b) => b.to_string(),
+        MapKey::I32(n) => n.to_string(),
+        MapKey::I64(n) => n.to_string(),
+        MapKey::U32(n) => n.to_string(),
+        MapKey::U64(n) => n.to_string(),
+        MapKey::String(s) => s.clone(),
+    }
+}
+
+/// Re-decode a message against another descriptor pool.
 ///
-/// Only modifies numeric values (not strings). Handles the pretty-printed
-/// JSON format where numbers appear at the end of lines or before commas/brackets.
-fn normalize_json_floats(json: &str) -> String {
-    use regex::Regex;
-    use std::sync::LazyLock;
+/// prost-reflect resolves Any type URLs using the pool that owns the message's
+/// descriptor, so moving the message into `pool` makes its types available.
+/// Returns `None` if the message already belongs to `pool` or `pool` does not
+/// define the message type.
+fn resolve_in_pool(msg: &DynamicMessage, pool: &DescriptorPool) -> Option<DynamicMessage> {
+    use prost::Message;
+    use prost_reflect::ReflectMessage;
 
-    // Match numbers like 42.0 that are NOT inside quotes.
-    // This regex finds: digits followed by ".0" at a word boundary,
-    // not preceded by another digit after the decimal (i.e., exactly ".0").
-    static FLOAT_REGEX: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?m): (\d+)\.0([,\s\n\r\}\]]|$)").expect("float regex"));
+    let desc = msg.descriptor();
+    if desc.parent_pool() == pool {
+        return None;
+    }
+    let target = pool.get_message_by_name(desc.full_name())?;
+    DynamicMessage::decode(target, msg.encode_to_vec().as_slice()).ok()
+}
 
-    FLOAT_REGEX.replace_all(json, ": $1$2").into_owned()
+/// A set of field paths selecting parts of a message, in the style of
+/// `google.protobuf.FieldMask`: `a.b` keeps only field `b` of message field
+/// `a`, while `a` keeps all of it.
+///
+/// Used by `--response-fields` to project responses client-side. Paths use
+/// proto field names or their JSON names, and reach through repeated and map
+/// fields into each message element.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMask {
+    /// Selected fields by proto name. An empty sub-mask selects the whole field.
+    fields: std::collections::BTreeMap<String, FieldMask>,
+}
+
+impl FieldMask {
+    /// Build a mask for messages of type `desc` from dotted `paths`.
+    ///
+    /// Fails if a path names a field `desc` does not have, or continues past
+    /// a field that is not a message.
+    pub fn new(desc: &MessageDescriptor, paths: &[String]) -> Result<FieldMask> {
+        let mut mask = FieldMask::default();
+        for path in paths {
+            let mut names = Vec::new();
+            let mut current = Some(desc.clone());
+            for segment in path.split('.') {
+                let field = current
+                    .as_ref()
+                    .and_then(|msg| {
+                        msg.get_field_by_name(segment)
+                            .or_else(|| msg.get_field_by_json_name(segment))
+                    })
+                    .ok_or_else(|| {
+                        GrpcurlError::InvalidArgument(format!(
+                            "field path \"{path}\" does not match a field of {}",
+                            desc.full_name()
+                        ))
+                    })?;
+                current = match field.kind() {
+                    prost_reflect::Kind::Message(msg) if field.is_map() => {
+                        msg.map_entry_value_field().kind().as_message().cloned()
+                    }
+                    prost_reflect::Kind::Message(msg) => Some(msg),
+                    _ => None,
+                };
+                names.push(field.name().to_string());
+            }
+            mask.insert(&names);
+        }
+        Ok(mask)
+    }
+
+    fn insert(&mut self, path: &[String]) {
+        use std::collections::btree_map::Entry;
+
+        let Some((first, rest)) = path.split_first() else {
+            return;
+        };
+        match self.fields.entry(first.clone()) {
+            // The whole field is already selected
+            Entry::Occupied(entry) if entry.get().fields.is_empty() => {}
+            Entry::Occupied(mut entry) if rest.is_empty() => entry.get_mut().fields.clear(),
+            Entry::Occupied(mut entry) => entry.get_mut().insert(rest),
+            Entry::Vacant(entry) => entry.insert(FieldMask::default()).insert(rest),
+        }
+    }
+
+    /// Clear every field of `msg` that the mask does not select.
+    pub fn apply(&self, msg: &mut DynamicMessage) {
+        use prost_reflect::{ReflectMessage, Value};
+
+        for field in msg.descriptor().fields() {
+            let Some(sub) = self.fields.get(field.name()) else {
+                msg.clear_field(&field);
+                continue;
+            };
+            if sub.fields.is_empty() || !msg.has_field(&field) {
+                continue;
+            }
+            match msg.get_field_mut(&field) {
+                Value::Message(inner) => sub.apply(inner),
+                Value::List(items) => items.iter_mut().for_each(|item| sub.apply_value(item)),
+                Value::Map(entries) => entries.values_mut().for_each(|item| sub.apply_value(item)),
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_value(&self, value: &mut prost_reflect::Value) {
+        if let prost_reflect::Value::Message(msg) = value {
+            self.apply(msg);
+        }
+    }
+}
+
+/// Wrap `formatter` so each message is projected through `mask` first.
+pub fn masked_formatter(mask: FieldMask, formatter: Formatter) -> Formatter {
+    Box::new(move |msg: &DynamicMessage| {
+        let mut msg = msg.clone();
+        mask.apply(&mut msg);
+        formatter(&msg)
+    })
+}
+
+/// Format a single message as pretty-printed JSON, exactly as a response is
+/// printed with `--format json`.
+///
+/// A one-shot shortcut for [`json_formatter`] with no resolver, so
+/// `google.protobuf.Any` fields are expanded using the pool of `msg`'s own
+/// descriptor.
+///
+/// # Examples
+///
+/// ```
+/// use grpcurl_core::format::{format_message_json, FormatOptions};
+/// use prost_reflect::ReflectMessage;
+///
+/// let msg = prost_types::SourceContext {
+///     file_name: "greeter.proto".into(),
+/// }
+/// .transcode_to_dynamic();
+///
+/// let json = format_message_json(&msg, &FormatOptions::default()).unwrap();
+/// assert_eq!(json, "{\n  \"fileName\": \"greeter.proto\"\n}");
+/// ```
+pub fn format_message_json(msg: &DynamicMessage, options: &FormatOptions) -> Result<String> {
+    json_formatter(options, None)(msg)
+}
+
+/// Format a single message as protobuf text, exactly as a response is printed
+/// with `--format text`, without a leading record separator.
+///
+/// When `legacy_brackets` is true, submessages use Go's `name: <...>` style.
+///
+/// # Examples
+///
+/// ```
+/// use grpcurl_core::format::format_message_text;
+/// use prost_reflect::ReflectMessage;
+///
+/// let msg = prost_types::SourceContext {
+///     file_name: "greeter.proto".into(),
+/// }
+/// .transcode_to_dynamic();
+///
+/// let text = format_message_text(&msg, false).unwrap();
+/// assert_eq!(text, "file_name: \"greeter.proto\"");
+/// ```
+pub fn format_message_text(msg: &DynamicMessage, legacy_brackets: bool) -> Result<String> {
+    text_formatter(false, legacy_brackets)(msg)
 }
 
 /// Create a protobuf text format response formatter.
 ///
 /// When `use_separator` is true, prepends a 0x1E record separator
-/// character between messages (after the first).
+/// character between messages (after the first). When `legacy_brackets` is
+/// true, submessages are written in Go's legacy `name: <...>` style instead
+/// of the default `name {...}`.
 ///
 /// Equivalent to Go's `NewTextFormatter()` (format.go:164-213).
-pub fn text_formatter(use_separator: bool) -> Formatter {
+pub fn text_formatter(use_separator: bool, legacy_brackets: bool) -> Formatter {
     let num_formatted = Cell::new(0usize);
 
     Box::new(move |msg: &DynamicMessage| {
@@ -539,13 +2052,64 @@ pub fn text_formatter(use_separator: bool) -> Formatter {
         let text = format!("{msg:#}");
         // Remove trailing newline (matching Go behavior)
         let text = text.trim_end_matches('\n');
-        output.push_str(text);
+        if legacy_brackets {
+            output.push_str(&to_legacy_brackets(text));
+        } else {
+            output.push_str(text);
+        }
 
         num_formatted.set(num_formatted.get() + 1);
         Ok(output)
     })
 }
 
+/// Rewrite prost-reflect's text format to use the legacy angle-bracket style
+/// emitted by Go's grpcurl, e.g. `payload {` becomes `payload: <`.
+///
+/// Braces inside string literals are left untouched.
+fn to_legacy_brackets(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for ch in text.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            out.push(ch);
+            continue;
+        }
+        match ch {
+            '"' => {
+                in_string = true;
+                out.push(ch);
+            }
+            '{' => {
+                // A field name (or Any type URL) is followed by a space before
+                // the brace; Go separates it from the value with a colon.
+                let mut prev = out.chars().rev();
+                if prev.next() == Some(' ')
+                    && prev
+                        .next()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == ']')
+                {
+                    out.pop();
+                    out.push_str(": ");
+                }
+                out.push('<');
+            }
+            '}' => out.push('>'),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
 /// Map a tonic gRPC status code to its canonical name.
 ///
 /// Equivalent to Go's `codes.Code.String()`.
@@ -571,6 +2135,47 @@ pub fn status_code_name(code: tonic::Code) -> &'static str {
     }
 }
 
+/// Map a tonic gRPC status code to its canonical SCREAMING_SNAKE_CASE name,
+/// as used in the gRPC spec and by most other gRPC implementations.
+pub fn upper_status_code_name(code: tonic::Code) -> &'static str {
+    match code {
+        tonic::Code::Ok => "OK",
+        tonic::Code::Cancelled => "CANCELLED",
+        tonic::Code::Unknown => "UNKNOWN",
+        tonic::Code::InvalidArgument => "INVALID_ARGUMENT",
+        tonic::Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        tonic::Code::NotFound => "NOT_FOUND",
+        tonic::Code::AlreadyExists => "ALREADY_EXISTS",
+        tonic::Code::PermissionDenied => "PERMISSION_DENIED",
+        tonic::Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        tonic::Code::FailedPrecondition => "FAILED_PRECONDITION",
+        tonic::Code::Aborted => "ABORTED",
+        tonic::Code::OutOfRange => "OUT_OF_RANGE",
+        tonic::Code::Unimplemented => "UNIMPLEMENTED",
+        tonic::Code::Internal => "INTERNAL",
+        tonic::Code::Unavailable => "UNAVAILABLE",
+        tonic::Code::DataLoss => "DATA_LOSS",
+        tonic::Code::Unauthenticated => "UNAUTHENTICATED",
+    }
+}
+
+/// Map a status code name back to its tonic gRPC status code.
+///
+/// The inverse of [`status_code_name`] and [`upper_status_code_name`]:
+/// accepts either style, case-insensitively, as well as the code's number
+/// (0-16).
+pub fn status_code_from_name(name: &str) -> Option<tonic::Code> {
+    if let Ok(number) = name.parse::<i32>() {
+        return (0..=16)
+            .contains(&number)
+            .then(|| tonic::Code::from_i32(number));
+    }
+    (0..=16).map(tonic::Code::from_i32).find(|&code| {
+        status_code_name(code).eq_ignore_ascii_case(name)
+            || upper_status_code_name(code).eq_ignore_ascii_case(name)
+    })
+}
+
 /// Print a gRPC status to stderr in the standard format.
 ///
 /// Equivalent to Go's `PrintStatus()` (format.go:517-554).
@@ -581,8 +2186,13 @@ pub fn status_code_name(code: tonic::Code) -> &'static str {
 ///   Code: <CODE_NAME>
 ///   Message: <message>
 /// ```
-pub fn print_status(status: &tonic::Status, formatter: Option<&Formatter>) {
-    write_status(&mut io::stderr(), status, formatter);
+pub fn print_status(
+    status: &tonic::Status,
+    formatter: Option<&Formatter>,
+    source: Option<&dyn DescriptorSource>,
+    style: StatusStyle,
+) {
+    write_status(&mut io::stderr(), status, formatter, source, style);
 }
 
 /// Write a gRPC status to the given writer.
@@ -590,13 +2200,24 @@ pub fn print_status(status: &tonic::Status, formatter: Option<&Formatter>) {
 /// Allows callers to direct status output to any writer (stderr, buffer, etc.)
 /// rather than hardcoding to stderr. The `print_status` function uses this
 /// with `io::stderr()`.
-pub fn write_status(w: &mut dyn io::Write, status: &tonic::Status, formatter: Option<&Formatter>) {
+///
+/// Detail types are looked up in `source` first, so error details declared in
+/// the user's own protos (or reachable via reflection) are expanded. Types not
+/// found there fall back to the well-known types pool. The code name is
+/// written in the given `style`.
+pub fn write_status(
+    w: &mut dyn io::Write,
+    status: &tonic::Status,
+    formatter: Option<&Formatter>,
+    source: Option<&dyn DescriptorSource>,
+    style: StatusStyle,
+) {
     if status.code() == tonic::Code::Ok {
         let _ = writeln!(w, "OK");
         return;
     }
     let _ = writeln!(w, "ERROR:");
-    let _ = writeln!(w, "  Code: {}", status_code_name(status.code()));
+    let _ = writeln!(w, "  Code: {}", style.code_name(status.code()));
     let _ = writeln!(w, "  Message: {}", status.message());
 
     // Parse status details from grpc-status-details-bin trailer.
@@ -620,7 +2241,10 @@ pub fn write_status(w: &mut dyn io::Write, status: &tonic::Status, formatter: Op
             let _ = writeln!(w, "  Details:");
         }
         // Try to format the Any message using the formatter if available
-        let formatted = formatter.and_then(|fmt| format_any_detail(any, fmt).ok());
+        let formatted = match formatter {
+            Some(fmt) => format_any_detail(any, fmt, source).ok(),
+            None => None,
+        };
 
         if let Some(text) = formatted {
             let _ = writeln!(w, "  - {}", any.type_url);
@@ -634,6 +2258,67 @@ pub fn write_status(w: &mut dyn io::Write, status: &tonic::Status, formatter: Op
     }
 }
 
+/// Print a gRPC status to stderr as a JSON object.
+pub fn print_status_json(
+    status: &tonic::Status,
+    source: Option<&dyn DescriptorSource>,
+    style: StatusStyle,
+) {
+    write_status_json(&mut io::stderr(), status, source, style);
+}
+
+/// Write a gRPC status to the given writer as a single-line JSON object:
+/// `{"code":"NotFound","codeNumber":5,"message":"...","details":[...]}`.
+///
+/// Details are resolved like in `write_status` and rendered as their JSON
+/// mapping plus an `@type` field. A detail whose type cannot be resolved keeps
+/// its raw bytes, base64-encoded, in `value`.
+pub fn write_status_json(
+    w: &mut dyn io::Write,
+    status: &tonic::Status,
+    source: Option<&dyn DescriptorSource>,
+    style: StatusStyle,
+) {
+    use base64::Engine;
+    use serde_json::{json, Value};
+
+    let formatter = make_json_formatter(
+        &FormatOptions::default(),
+        source.and_then(|src| src.descriptor_pool_snapshot()),
+        false,
+    );
+
+    let mut details = Vec::new();
+    for any in decode_status_details(status.details()) {
+        let expanded = format_any_detail(&any, &formatter, source)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+        let detail = match expanded {
+            Some(Value::Object(fields)) => {
+                let mut obj = serde_json::Map::new();
+                obj.insert("@type".into(), any.type_url.clone().into());
+                obj.extend(fields);
+                Value::Object(obj)
+            }
+            // Well-known types with a non-object JSON mapping (e.g. Duration)
+            Some(value) => json!({ "@type": any.type_url, "value": value }),
+            None => json!({
+                "@type": any.type_url,
+                "value": base64::engine::general_purpose::STANDARD.encode(&any.value),
+            }),
+        };
+        details.push(detail);
+    }
+
+    let out = json!({
+        "code": style.code_name(status.code()),
+        "codeNumber": status.code() as i32,
+        "message": status.message(),
+        "details": details,
+    });
+    let _ = writeln!(w, "{out}");
+}
+
 /// Decode the details field (field 3, repeated Any) from a serialized google.rpc.Status.
 ///
 /// google.rpc.Status wire format:
@@ -666,11 +2351,13 @@ fn decode_status_details(data: &[u8]) -> Vec<prost_types::Any> {
 
 /// Attempt to format an Any-typed detail message as JSON.
 ///
-/// Uses a well-known types descriptor pool to decode common error detail types
-/// like google.rpc.ErrorInfo, google.rpc.BadRequest, etc.
+/// Resolves the detail type among the files the invocation's descriptor
+/// source has loaded, when one is given, then falls back to the well-known
+/// types descriptor pool.
 fn format_any_detail(
     any: &prost_types::Any,
     formatter: &Formatter,
+    source: Option<&dyn DescriptorSource>,
 ) -> std::result::Result<String, Box<dyn std::error::Error>> {
     // Extract the message type name from the type_url
     let type_name = any
@@ -679,9 +2366,12 @@ fn format_any_detail(
         .map(|(_, name)| name)
         .unwrap_or(&any.type_url);
 
-    // Try to find the message type in a pool with well-known types
-    let pool = prost_reflect::DescriptorPool::global();
-    let msg_desc = pool.get_message_by_name(type_name).ok_or("unknown type")?;
+    let from_source = source
+        .and_then(|src| src.descriptor_pool_snapshot())
+        .and_then(|pool| pool.get_message_by_name(type_name));
+    let msg_desc = from_source
+        .or_else(|| DescriptorPool::global().get_message_by_name(type_name))
+        .ok_or("unknown type")?;
 
     let msg = DynamicMessage::decode(msg_desc, any.value.as_slice())
         .map_err(|e| format!("failed to decode detail: {e}"))?;
@@ -769,6 +2459,90 @@ mod tests {
         assert_eq!(parser.num_requests(), 2);
     }
 
+    #[test]
+    fn json_parse_error_reports_absolute_position() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let opts = FormatOptions::default();
+
+        let error = |data: &str| {
+            let mut parser = JsonRequestParser::new(Some(data), &opts).unwrap();
+            loop {
+                match parser.next(&desc) {
+                    Ok(_) => continue,
+                    Err(ParseError::Error(GrpcurlError::Proto(msg))) => return msg,
+                    Err(ParseError::Error(e)) => panic!("unexpected error: {e}"),
+                    Err(ParseError::Eof) => panic!("no error in {data:?}"),
+                }
+            }
+        };
+
+        assert_eq!(
+            error("{\"name\": \"first\"}\n  {\"name\": }"),
+            "invalid JSON in request message 2 at line 2 column 12: expected value"
+        );
+        assert_eq!(
+            error("{\"name\": \"a\"} {\"name\": \"b\"}\n{\n  \"name\" 1\n}"),
+            "invalid JSON in request message 3 at line 3 column 10: expected `:`"
+        );
+        assert_eq!(
+            error("{\"name\": \"a\"}{"),
+            "invalid JSON in request message 2 at line 1 column 14: EOF while parsing an object"
+        );
+    }
+
+    #[test]
+    fn parse_ndjson_messages() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let opts = FormatOptions::default();
+        let data = "{\"name\": \"first\"}\n\n  {\"name\": \"second\"}  \r\n";
+        let mut parser = NdjsonRequestParser::new(Some(data), &opts).unwrap();
+        let name = desc.get_field_by_name("name").unwrap();
+
+        let msg1 = parser.next(&desc).unwrap();
+        assert_eq!(msg1.get_field(&name).as_str(), Some("first"));
+        let msg2 = parser.next(&desc).unwrap();
+        assert_eq!(msg2.get_field(&name).as_str(), Some("second"));
+
+        assert!(matches!(parser.next(&desc), Err(ParseError::Eof)));
+        assert_eq!(parser.num_requests(), 2);
+    }
+
+    #[test]
+    fn parse_ndjson_reports_bad_line() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let opts = FormatOptions::default();
+        // Two messages on one line are not valid NDJSON
+        let data = "{\"name\": \"first\"}\n{\"name\": \"a\"} {\"name\": \"b\"}\n";
+        let mut parser = NdjsonRequestParser::new(Some(data), &opts).unwrap();
+
+        parser.next(&desc).unwrap();
+        match parser.next(&desc) {
+            Err(ParseError::Error(e)) => {
+                assert!(
+                    e.to_string().contains("line 2: invalid JSON request"),
+                    "{e}"
+                )
+            }
+            _ => panic!("expected a parse error on line 2"),
+        }
+
+        // Valid JSON that does not fit the message type names its line too
+        let data = "{\"name\": \"first\"}\n\n{\"nmae\": \"second\"}\n";
+        let mut parser = NdjsonRequestParser::new(Some(data), &opts).unwrap();
+        parser.next(&desc).unwrap();
+        match parser.next(&desc) {
+            Err(ParseError::Error(e)) => {
+                let e = e.to_string();
+                assert!(e.contains("line 3: failed to parse JSON request"), "{e}");
+                assert!(e.contains("nmae"), "{e}");
+            }
+            _ => panic!("expected a parse error on line 3"),
+        }
+    }
+
     #[test]
     fn parse_empty_input() {
         let pool = make_pool();
@@ -788,7 +2562,7 @@ mod tests {
             emit_defaults: false,
             ..Default::default()
         };
-        let formatter = json_formatter(&opts);
+        let formatter = json_formatter(&opts, None);
 
         let mut msg = DynamicMessage::new(desc.clone());
         let name_field = desc.get_field_by_name("name").unwrap();
@@ -800,6 +2574,28 @@ mod tests {
         assert!(!output.contains("count"));
     }
 
+    #[test]
+    fn format_json_with_custom_indent() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let mut msg = DynamicMessage::new(desc.clone());
+        let name_field = desc.get_field_by_name("name").unwrap();
+        msg.set_field(&name_field, prost_reflect::Value::String("world".into()));
+
+        let output = (json_formatter(&FormatOptions::default(), None))(&msg).unwrap();
+        assert_eq!(output, "{\n  \"name\": \"world\"\n}");
+
+        for raw_any in [false, true] {
+            let opts = FormatOptions {
+                json_indent: Some("\t".into()),
+                raw_any,
+                ..Default::default()
+            };
+            let output = (json_formatter(&opts, None))(&msg).unwrap();
+            assert_eq!(output, "{\n\t\"name\": \"world\"\n}");
+        }
+    }
+
     #[test]
     fn format_json_with_defaults() {
         let pool = make_pool();
@@ -808,7 +2604,7 @@ mod tests {
             emit_defaults: true,
             ..Default::default()
         };
-        let formatter = json_formatter(&opts);
+        let formatter = json_formatter(&opts, None);
 
         let mut msg = DynamicMessage::new(desc.clone());
         let name_field = desc.get_field_by_name("name").unwrap();
@@ -820,6 +2616,694 @@ mod tests {
         assert!(output.contains("\"count\""));
     }
 
+    #[test]
+    fn format_ndjson_is_single_line() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let formatter = ndjson_formatter(&FormatOptions::default(), None);
+
+        let mut msg = DynamicMessage::new(desc.clone());
+        let name_field = desc.get_field_by_name("name").unwrap();
+        msg.set_field(&name_field, prost_reflect::Value::String("world".into()));
+
+        let first = (formatter)(&msg).unwrap();
+        let second = (formatter)(&msg).unwrap();
+        assert_eq!(first, r#"{"name":"world"}"#);
+        // No record separator between messages
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn format_json_whole_floats_like_go() {
+        let pool = make_oneof_pool();
+        let desc = pool.get_message_by_name("test.v1.Shape").unwrap();
+        let msg = parse_json(
+            &desc,
+            r#"{"label": "v:1.0,", "children": [{"area": -2}, {"area": 1.5}], "area": 3}"#,
+        )
+        .unwrap();
+
+        let output = (ndjson_formatter(&FormatOptions::default(), None))(&msg).unwrap();
+        assert_eq!(
+            output,
+            r#"{"label":"v:1.0,","children":[{"area":-2},{"area":1.5}],"area":3}"#
+        );
+        let output = (json_formatter(&FormatOptions::default(), None))(&msg).unwrap();
+        assert!(output.contains("\"label\": \"v:1.0,\""), "{output}");
+        assert!(output.ends_with("\"area\": 3\n}"), "{output}");
+
+        // The same when the output goes through serde_json::Value
+        let opts = FormatOptions {
+            oneof_wrapped: true,
+            ..Default::default()
+        };
+        let output = (ndjson_formatter(&opts, None))(&msg).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"kind":{"which":"label","value":"v:1.0,"},"#,
+                r#""children":[{"area":-2},{"area":1.5}],"area":3}"#
+            )
+        );
+    }
+
+    #[test]
+    fn format_json_non_finite_floats() {
+        let pool = DescriptorPool::global();
+        let formatter = ndjson_formatter(&FormatOptions::default(), None);
+
+        // A double field, per the proto3 JSON mapping
+        let desc = pool
+            .get_message_by_name("google.protobuf.UninterpretedOption")
+            .unwrap();
+        let mut msg = DynamicMessage::new(desc);
+        msg.set_field_by_name("double_value", prost_reflect::Value::F64(f64::NAN));
+        assert_eq!(formatter(&msg).unwrap(), r#"{"doubleValue":"NaN"}"#);
+
+        // A number inside google.protobuf.Struct, which has no string form
+        let desc = pool.get_message_by_name("google.protobuf.Struct").unwrap();
+        let value_desc = pool.get_message_by_name("google.protobuf.Value").unwrap();
+        let mut fields = std::collections::HashMap::new();
+        for (key, number) in [("a", f64::INFINITY), ("b", f64::NEG_INFINITY), ("c", 1.5)] {
+            let mut value = DynamicMessage::new(value_desc.clone());
+            value.set_field_by_name("number_value", prost_reflect::Value::F64(number));
+            fields.insert(
+                prost_reflect::MapKey::String(key.into()),
+                prost_reflect::Value::Message(value),
+            );
+        }
+        let mut msg = DynamicMessage::new(desc.clone());
+        msg.set_field_by_name("fields", prost_reflect::Value::Map(fields));
+        let json: serde_json::Value = serde_json::from_str(&formatter(&msg).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"a": "Infinity", "b": "-Infinity", "c": 1.5})
+        );
+
+        // A Value holding a ListValue, and one holding a Struct
+        let list_desc = pool
+            .get_message_by_name("google.protobuf.ListValue")
+            .unwrap();
+        let number = |n: f64| {
+            let mut value = DynamicMessage::new(value_desc.clone());
+            value.set_field_by_name("number_value", prost_reflect::Value::F64(n));
+            prost_reflect::Value::Message(value)
+        };
+        let mut list = DynamicMessage::new(list_desc);
+        list.set_field_by_name("values", prost_reflect::Value::List(vec![number(f64::NAN)]));
+        let mut msg = DynamicMessage::new(value_desc.clone());
+        msg.set_field_by_name("list_value", prost_reflect::Value::Message(list.clone()));
+        assert_eq!(formatter(&msg).unwrap(), r#"["NaN"]"#);
+
+        let mut inner = DynamicMessage::new(value_desc.clone());
+        inner.set_field_by_name("list_value", prost_reflect::Value::Message(list));
+        let mut fields = std::collections::HashMap::new();
+        fields.insert(
+            prost_reflect::MapKey::String("x".into()),
+            prost_reflect::Value::Message(inner),
+        );
+        let mut strukt = DynamicMessage::new(desc);
+        strukt.set_field_by_name("fields", prost_reflect::Value::Map(fields));
+        let mut msg = DynamicMessage::new(value_desc);
+        msg.set_field_by_name("struct_value", prost_reflect::Value::Message(strukt));
+        assert_eq!(formatter(&msg).unwrap(), r#"{"x":["NaN"]}"#);
+    }
+
+    #[test]
+    fn field_mask_projects_messages() {
+        let desc = DescriptorPool::global()
+            .get_message_by_name("google.protobuf.DescriptorProto")
+            .unwrap();
+        let input = r#"{"name": "M", "field": [{"name": "a", "number": 1, "jsonName": "a"},
+            {"name": "b", "number": 2}], "options": {"deprecated": true}}"#;
+        let msg = DynamicMessage::deserialize(
+            desc.clone(),
+            &mut serde_json::Deserializer::from_str(input),
+        )
+        .unwrap();
+        let project = |paths: &[&str]| {
+            let paths: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+            let mask = FieldMask::new(&desc, &paths).unwrap();
+            let formatter =
+                masked_formatter(mask, ndjson_formatter(&FormatOptions::default(), None));
+            formatter(&msg).unwrap()
+        };
+
+        assert_eq!(
+            project(&["name", "field.number"]),
+            r#"{"name":"M","field":[{"number":1},{"number":2}]}"#
+        );
+        // JSON names work, and a whole field wins over one of its sub-paths
+        assert_eq!(
+            project(&["field.jsonName", "options.deprecated", "options"]),
+            r#"{"field":[{"jsonName":"a"},{}],"options":{"deprecated":true}}"#
+        );
+
+        for bad in ["nope", "name.length", "field.number.x"] {
+            let err = FieldMask::new(&desc, &[bad.to_string()]).unwrap_err();
+            assert!(err.to_string().contains(bad), "{err}");
+        }
+    }
+
+    /// Build two pools defining `test.v1.Wrapper { google.protobuf.Any detail = 1; }`;
+    /// only the second also defines the `test.v1.Detail` type packed in the Any.
+    fn make_any_pools() -> (DescriptorPool, DescriptorPool) {
+        use prost_types::field_descriptor_proto::{Label, Type};
+
+        let any_file = DescriptorPool::global()
+            .get_file_by_name("google/protobuf/any.proto")
+            .unwrap()
+            .file_descriptor_proto()
+            .clone();
+        let wrapper_file = prost_types::FileDescriptorProto {
+            name: Some("wrapper.proto".into()),
+            package: Some("test.v1".into()),
+            dependency: vec!["google/protobuf/any.proto".into()],
+            message_type: vec![prost_types::DescriptorProto {
+                name: Some("Wrapper".into()),
+                field: vec![prost_types::FieldDescriptorProto {
+                    name: Some("detail".into()),
+                    number: Some(1),
+                    r#type: Some(Type::Message.into()),
+                    type_name: Some(".google.protobuf.Any".into()),
+                    label: Some(Label::Optional.into()),
+                    json_name: Some("detail".into()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        };
+        let detail_file = prost_types::FileDescriptorProto {
+            name: Some("detail.proto".into()),
+            package: Some("test.v1".into()),
+            message_type: vec![prost_types::DescriptorProto {
+                name: Some("Detail".into()),
+                field: vec![prost_types::FieldDescriptorProto {
+                    name: Some("reason".into()),
+                    number: Some(1),
+                    r#type: Some(Type::String.into()),
+                    label: Some(Label::Optional.into()),
+                    json_name: Some("reason".into()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        };
+
+        let method_pool =
+            DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+                file: vec![any_file.clone(), wrapper_file.clone()],
+            })
+            .unwrap();
+        let source_pool =
+            DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+                file: vec![any_file, wrapper_file, detail_file],
+            })
+            .unwrap();
+        (method_pool, source_pool)
+    }
+
+    /// A `test.v1.Detail { reason: "boom" }` packed in an Any; `source_pool`
+    /// is the second pool from [`make_any_pools`].
+    fn make_detail_any(source_pool: &DescriptorPool) -> prost_types::Any {
+        use prost::Message;
+
+        let detail_desc = source_pool.get_message_by_name("test.v1.Detail").unwrap();
+        let mut detail = DynamicMessage::new(detail_desc);
+        detail.set_field_by_name("reason", prost_reflect::Value::String("boom".into()));
+        prost_types::Any {
+            type_url: "type.googleapis.com/test.v1.Detail".into(),
+            value: detail.encode_to_vec(),
+        }
+    }
+
+    /// A `test.v1.Wrapper` from `pool` holding [`make_detail_any`].
+    fn make_wrapper_with_detail(
+        pool: &DescriptorPool,
+        source_pool: &DescriptorPool,
+    ) -> DynamicMessage {
+        use prost::Message;
+
+        let any = make_detail_any(source_pool);
+        let any_desc = pool.get_message_by_name("google.protobuf.Any").unwrap();
+        let wrapper_desc = pool.get_message_by_name("test.v1.Wrapper").unwrap();
+        let mut msg = DynamicMessage::new(wrapper_desc);
+        msg.set_field_by_name(
+            "detail",
+            prost_reflect::Value::Message(
+                DynamicMessage::decode(any_desc, any.encode_to_vec().as_slice()).unwrap(),
+            ),
+        );
+        msg
+    }
+
+    /// A FailedPrecondition status carrying [`make_detail_any`], and a
+    /// descriptor source over `source_pool` that can resolve it.
+    fn make_status_with_detail(
+        source_pool: &DescriptorPool,
+    ) -> (tonic::Status, crate::descriptor::FileSource) {
+        // google.rpc.Status { details (field 3) = [any] }
+        let mut details = Vec::new();
+        prost::encoding::message::encode(3, &make_detail_any(source_pool), &mut details);
+        let status =
+            tonic::Status::with_details(tonic::Code::FailedPrecondition, "bad", details.into());
+
+        let source = crate::descriptor::descriptor_source_from_file_descriptor_set(
+            prost_types::FileDescriptorSet {
+                file: source_pool.file_descriptor_protos().cloned().collect(),
+            },
+        )
+        .unwrap();
+        (status, source)
+    }
+
+    #[test]
+    fn format_json_expands_any_with_resolver() {
+        let (method_pool, source_pool) = make_any_pools();
+        let msg = make_wrapper_with_detail(&method_pool, &source_pool);
+
+        // The method's own pool cannot resolve the packed type
+        let opts = FormatOptions::default();
+        assert!((json_formatter(&opts, None))(&msg).is_err());
+
+        let output = (json_formatter(&opts, Some(source_pool)))(&msg).unwrap();
+        assert!(output.contains("\"@type\": \"type.googleapis.com/test.v1.Detail\""));
+        assert!(output.contains("\"reason\": \"boom\""));
+    }
+
+    #[tokio::test]
+    async fn format_json_expands_any_from_reflection_source() {
+        use crate::descriptor::DescriptorSource;
+
+        let (method_pool, source_pool) = make_any_pools();
+        let msg = make_wrapper_with_detail(&method_pool, &source_pool);
+        let (status, _) = make_status_with_detail(&source_pool);
+        let channel = tonic::transport::Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+        let source = crate::reflection::ServerSource::new(channel).with_pool(source_pool);
+        // Reflection grows its pool behind a lock, so it cannot lend one out
+        assert!(source.descriptor_pool().is_none());
+
+        let formatter =
+            json_formatter(&FormatOptions::default(), source.descriptor_pool_snapshot());
+        let output = formatter(&msg).unwrap();
+        assert!(output.contains("\"reason\": \"boom\""), "{output}");
+
+        let mut out = Vec::new();
+        write_status_json(&mut out, &status, Some(&source), StatusStyle::Go);
+        let out: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(out["details"][0]["reason"], "boom");
+    }
+
+    #[test]
+    fn format_json_raw_any_keeps_wire_form() {
+        let (_, source_pool) = make_any_pools();
+        let msg = make_wrapper_with_detail(&source_pool, &source_pool);
+
+        // Resolvable, but left as-is
+        let opts = FormatOptions {
+            raw_any: true,
+            ..Default::default()
+        };
+        let output = (ndjson_formatter(&opts, Some(source_pool)))(&msg).unwrap();
+        assert_eq!(
+            output,
+            r#"{"detail":{"@type":"type.googleapis.com/test.v1.Detail","value":"CgRib29t"}}"#
+        );
+    }
+
+    #[test]
+    fn status_style_code_names() {
+        assert_eq!(
+            StatusStyle::default().code_name(tonic::Code::Cancelled),
+            "Canceled"
+        );
+        assert_eq!(
+            StatusStyle::Upper.code_name(tonic::Code::Cancelled),
+            "CANCELLED"
+        );
+        assert_eq!(
+            StatusStyle::Upper.code_name(tonic::Code::DeadlineExceeded),
+            "DEADLINE_EXCEEDED"
+        );
+        assert_eq!(StatusStyle::Upper.code_name(tonic::Code::Ok), "OK");
+        assert_eq!("upper".parse::<StatusStyle>(), Ok(StatusStyle::Upper));
+        assert!("UPPER".parse::<StatusStyle>().is_err());
+    }
+
+    #[test]
+    fn status_code_from_name_inverts_both_styles() {
+        for number in 0..=16 {
+            let code = tonic::Code::from_i32(number);
+            for name in [status_code_name(code), upper_status_code_name(code)] {
+                assert_eq!(status_code_from_name(name), Some(code), "{name}");
+                let lower = name.to_lowercase();
+                assert_eq!(status_code_from_name(&lower), Some(code), "{lower}");
+            }
+            assert_eq!(status_code_from_name(&number.to_string()), Some(code));
+        }
+        assert_eq!(
+            status_code_from_name("cancelled"),
+            Some(tonic::Code::Cancelled)
+        );
+        for invalid in ["", "17", "-1", "Bogus", "NOT FOUND"] {
+            assert_eq!(status_code_from_name(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn write_status_resolves_details_from_source() {
+        let (_, source_pool) = make_any_pools();
+        let (status, source) = make_status_with_detail(&source_pool);
+        let formatter = json_formatter(&FormatOptions::default(), None);
+
+        let mut out = Vec::new();
+        write_status(
+            &mut out,
+            &status,
+            Some(&formatter),
+            Some(&source),
+            StatusStyle::Go,
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("- type.googleapis.com/test.v1.Detail"));
+        assert!(out.contains("\"reason\": \"boom\""));
+
+        // Without a source the type is unknown and only its size is shown
+        let mut out = Vec::new();
+        write_status(&mut out, &status, Some(&formatter), None, StatusStyle::Go);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("type.googleapis.com/test.v1.Detail (6 bytes)"));
+    }
+
+    #[test]
+    fn write_status_json_expands_details() {
+        let (_, source_pool) = make_any_pools();
+        let (status, source) = make_status_with_detail(&source_pool);
+
+        let mut out = Vec::new();
+        write_status_json(&mut out, &status, Some(&source), StatusStyle::Go);
+        let out: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            out,
+            serde_json::json!({
+                "code": "FailedPrecondition",
+                "codeNumber": 9,
+                "message": "bad",
+                "details": [{"@type": "type.googleapis.com/test.v1.Detail", "reason": "boom"}],
+            })
+        );
+
+        // Unresolvable details keep their raw bytes
+        let mut out = Vec::new();
+        write_status_json(&mut out, &status, None, StatusStyle::Go);
+        let out: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(out["details"][0]["value"], "CgRib29t");
+    }
+
+    fn make_oneof_pool() -> DescriptorPool {
+        let field = |name: &str, number: i32, r#type: i32| prost_types::FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            r#type: Some(r#type),
+            label: Some(1),
+            json_name: Some(name.into()),
+            ..Default::default()
+        };
+        let fds = prost_types::FileDescriptorSet {
+            file: vec![prost_types::FileDescriptorProto {
+                name: Some("shape.proto".into()),
+                package: Some("test.v1".into()),
+                message_type: vec![
+                    prost_types::DescriptorProto {
+                        name: Some("Shape".into()),
+                        field: vec![
+                            prost_types::FieldDescriptorProto {
+                                type_name: Some(".test.v1.Circle".into()),
+                                oneof_index: Some(0),
+                                ..field("circle", 1, 11) // TYPE_MESSAGE
+                            },
+                            prost_types::FieldDescriptorProto {
+                                oneof_index: Some(0),
+                                ..field("label", 2, 9) // TYPE_STRING
+                            },
+                            prost_types::FieldDescriptorProto {
+                                type_name: Some(".test.v1.Shape".into()),
+                                label: Some(3), // LABEL_REPEATED
+                                ..field("children", 3, 11)
+                            },
+                            field("area", 4, 1), // TYPE_DOUBLE
+                        ],
+                        oneof_decl: vec![prost_types::OneofDescriptorProto {
+                            name: Some("kind".into()),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    },
+                    prost_types::DescriptorProto {
+                        name: Some("Circle".into()),
+                        field: vec![field("radius", 1, 5)], // TYPE_INT32
+                        ..Default::default()
+                    },
+                ],
+                syntax: Some("proto3".into()),
+                ..Default::default()
+            }],
+        };
+        DescriptorPool::from_file_descriptor_set(fds).unwrap()
+    }
+
+    fn parse_json(desc: &MessageDescriptor, data: &str) -> Result<DynamicMessage> {
+        let mut parser = JsonRequestParser::new(Some(data), &FormatOptions::default())?;
+        parser.next(desc).map_err(|e| match e {
+            ParseError::Error(e) => e,
+            ParseError::Eof => panic!("no message in {data}"),
+        })
+    }
+
+    #[test]
+    fn humanize_durations() {
+        assert_eq!(humanize_duration("3.120s").as_deref(), Some("3s 120ms"));
+        assert_eq!(
+            humanize_duration("3723.000001500s").as_deref(),
+            Some("1h 2m 3s 1µs 500ns")
+        );
+        assert_eq!(humanize_duration("-0.5s").as_deref(), Some("-500ms"));
+        assert_eq!(humanize_duration("0s").as_deref(), Some("0s"));
+        assert_eq!(humanize_duration("3m"), None);
+    }
+
+    #[test]
+    fn humanize_timestamps_in_zone() {
+        let utc = |_| 0;
+        assert_eq!(
+            humanize_timestamp("2024-01-01T00:00:00Z", utc).as_deref(),
+            Some("2024-01-01 00:00:00 +00:00")
+        );
+        // Crossing back over a day, month, and year boundary
+        let new_york = |_| -5 * 3600;
+        assert_eq!(
+            humanize_timestamp("2024-01-01T03:04:05.250Z", new_york).as_deref(),
+            Some("2023-12-31 22:04:05.250 -05:00")
+        );
+        let kathmandu = |_| 5 * 3600 + 45 * 60;
+        assert_eq!(
+            humanize_timestamp("2024-02-28T20:00:00Z", kathmandu).as_deref(),
+            Some("2024-02-29 01:45:00 +05:45")
+        );
+        assert_eq!(humanize_timestamp("yesterday", utc), None);
+        assert_eq!(humanize_timestamp("2024-01-01T03:04Z", utc), None);
+        assert_eq!(humanize_timestamp("2024-01-01T03:04:05.Z", utc), None);
+        assert_eq!(humanize_timestamp("2024-01-01T03:04:+5Z", utc), None);
+    }
+
+    /// A pool with `test.v1.Event`, whose Timestamp and Duration fields are
+    /// declared out of alphabetical order.
+    fn make_event_pool() -> DescriptorPool {
+        let field = |name: &str, number: i32, type_name: &str| prost_types::FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            r#type: Some(11), // TYPE_MESSAGE
+            label: Some(1),
+            type_name: Some(type_name.into()),
+            json_name: Some(name.into()),
+            ..Default::default()
+        };
+        let mut pool = DescriptorPool::global();
+        pool.add_file_descriptor_proto(prost_types::FileDescriptorProto {
+            name: Some("event.proto".into()),
+            package: Some("test.v1".into()),
+            dependency: vec![
+                "google/protobuf/duration.proto".into(),
+                "google/protobuf/timestamp.proto".into(),
+            ],
+            message_type: vec![prost_types::DescriptorProto {
+                name: Some("Event".into()),
+                field: vec![
+                    field("when", 1, ".google.protobuf.Timestamp"),
+                    field("after", 2, ".google.protobuf.Duration"),
+                ],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        pool
+    }
+
+    #[test]
+    fn format_json_humanize_time_keeps_order_and_expands_any() {
+        use prost::Message;
+
+        let pool = make_event_pool();
+        let desc = pool.get_message_by_name("test.v1.Event").unwrap();
+        let event = parse_json(
+            &desc,
+            r#"{"after": "1.5s", "when": "2024-01-01T03:04:05Z"}"#,
+        )
+        .unwrap();
+        let opts = FormatOptions {
+            humanize_time: true,
+            ..Default::default()
+        };
+        let formatter = ndjson_formatter(&opts, None);
+        // Rendered in whatever time zone the tests run in
+        let when = humanize_timestamp("2024-01-01T03:04:05Z", local_utc_offset).unwrap();
+        let fields = format!(r#""when":"{when}","after":"1s 500ms""#);
+        assert_eq!(formatter(&event).unwrap(), format!("{{{fields}}}"));
+
+        // Inside an Any, both as a message and as a well-known type
+        let any_desc = pool.get_message_by_name(ANY_TYPE).unwrap();
+        let pack = |type_name: &str, value: Vec<u8>| {
+            let mut any = DynamicMessage::new(any_desc.clone());
+            any.set_field_by_name(
+                "type_url",
+                prost_reflect::Value::String(format!("type.googleapis.com/{type_name}")),
+            );
+            any.set_field_by_name("value", prost_reflect::Value::Bytes(value.into()));
+            any
+        };
+        let any = pack("test.v1.Event", event.encode_to_vec());
+        assert_eq!(
+            formatter(&any).unwrap(),
+            format!(r#"{{"@type":"type.googleapis.com/test.v1.Event",{fields}}}"#)
+        );
+        let duration = prost_types::Duration {
+            seconds: 90,
+            nanos: 0,
+        };
+        let any = pack(DURATION_TYPE, duration.encode_to_vec());
+        assert_eq!(
+            formatter(&any).unwrap(),
+            r#"{"@type":"type.googleapis.com/google.protobuf.Duration","value":"1m 30s"}"#
+        );
+    }
+
+    #[test]
+    fn format_json_humanize_time() {
+        use prost_reflect::ReflectMessage;
+
+        let duration = prost_types::Duration {
+            seconds: 90,
+            nanos: 5_000_000,
+        }
+        .transcode_to_dynamic();
+        let opts = FormatOptions {
+            humanize_time: true,
+            ..Default::default()
+        };
+        let output = json_formatter(&opts, None)(&duration).unwrap();
+        assert_eq!(output, r#""1m 30s 5ms""#);
+
+        let output = json_formatter(&FormatOptions::default(), None)(&duration).unwrap();
+        assert_eq!(output, r#""90.005s""#);
+    }
+
+    #[test]
+    fn format_json_oneof_wrapped() {
+        let pool = make_oneof_pool();
+        let desc = pool.get_message_by_name("test.v1.Shape").unwrap();
+        let msg = parse_json(
+            &desc,
+            r#"{"label": "outer", "children": [{"circle": {"radius": 2}}]}"#,
+        )
+        .unwrap();
+
+        let opts = FormatOptions {
+            oneof_wrapped: true,
+            ..Default::default()
+        };
+        let output = (ndjson_formatter(&opts, None))(&msg).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"kind":{"which":"label","value":"outer"},"#,
+                r#""children":[{"kind":{"which":"circle","value":{"radius":2}}}]}"#
+            )
+        );
+
+        // The default stays flat proto3 JSON
+        let output = (ndjson_formatter(&FormatOptions::default(), None))(&msg).unwrap();
+        assert_eq!(
+            output,
+            r#"{"label":"outer","children":[{"circle":{"radius":2}}]}"#
+        );
+    }
+
+    #[test]
+    fn format_json_oneof_wrapped_keeps_field_order() {
+        let pool = make_oneof_pool();
+        let desc = pool.get_message_by_name("test.v1.Shape").unwrap();
+        // Declared as circle/label, children, area: not alphabetical
+        let msg = parse_json(
+            &desc,
+            r#"{"area": 2.5, "children": [{"label": "inner"}], "circle": {"radius": 1}}"#,
+        )
+        .unwrap();
+
+        let opts = FormatOptions {
+            oneof_wrapped: true,
+            ..Default::default()
+        };
+        let output = (ndjson_formatter(&opts, None))(&msg).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"kind":{"which":"circle","value":{"radius":1}},"#,
+                r#""children":[{"kind":{"which":"label","value":"inner"}}],"area":2.5}"#
+            )
+        );
+    }
+
+    #[test]
+    fn parse_json_accepts_wrapped_oneofs() {
+        let pool = make_oneof_pool();
+        let desc = pool.get_message_by_name("test.v1.Shape").unwrap();
+        let flat = parse_json(
+            &desc,
+            r#"{"label": "outer", "children": [{"circle": {"radius": 2}}]}"#,
+        )
+        .unwrap();
+        let wrapped = parse_json(
+            &desc,
+            r#"{"kind": {"which": "label", "value": "outer"},
+                "children": [{"kind": {"which": "circle", "value": {"radius": 2}}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(wrapped, flat);
+
+        let err = parse_json(&desc, r#"{"kind": {"which": "square", "value": 1}}"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("oneof \"kind\" has no member \"square\""),
+            "{err}"
+        );
+    }
+
     #[test]
     fn parse_unknown_fields_rejected_by_default() {
         let pool = make_pool();
@@ -854,7 +3338,8 @@ mod tests {
     fn parse_text_format_single_message() {
         let pool = make_pool();
         let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
-        let mut parser = TextRequestParser::new(Some("name: \"world\" count: 42")).unwrap();
+        let opts = FormatOptions::default();
+        let mut parser = TextRequestParser::new(Some("name: \"world\" count: 42"), &opts).unwrap();
 
         let msg = parser.next(&desc).unwrap();
         assert_eq!(parser.num_requests(), 1);
@@ -867,11 +3352,152 @@ mod tests {
     }
 
     #[test]
-    fn parse_text_format_multiple_with_separator() {
+    fn parse_text_format_unknown_fields_rejected() {
         let pool = make_pool();
         let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let opts = FormatOptions::default();
         let mut parser =
-            TextRequestParser::new(Some("name: \"first\"\x1ename: \"second\"")).unwrap();
+            TextRequestParser::new(Some("name: \"test\" unknown_field: 42"), &opts).unwrap();
+
+        match parser.next(&desc) {
+            Err(ParseError::Error(e)) => assert!(
+                e.to_string().contains("field 'unknown_field' not found"),
+                "{e}"
+            ),
+            _ => panic!("expected an unknown field error"),
+        }
+    }
+
+    #[test]
+    fn parse_text_format_unknown_fields_allowed() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let opts = FormatOptions {
+            allow_unknown_fields: true,
+            ..Default::default()
+        };
+        let text = "unknown_list: [1, 2], name: \"test\"; nested { a: \"}\" } count: 42";
+        let mut parser = TextRequestParser::new(Some(text), &opts).unwrap();
+
+        let msg = parser.next(&desc).unwrap();
+        let name_val = msg.get_field(&desc.get_field_by_name("name").unwrap());
+        assert_eq!(name_val.as_str(), Some("test"));
+        let count_val = msg.get_field(&desc.get_field_by_name("count").unwrap());
+        assert_eq!(count_val.as_i32(), Some(42));
+    }
+
+    #[test]
+    fn json_schema_for_recursive_message() {
+        let desc = DescriptorPool::global()
+            .get_message_by_name("google.protobuf.DescriptorProto")
+            .unwrap();
+        let schema = make_json_schema(&desc);
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(
+            schema["$ref"],
+            "#/definitions/google.protobuf.DescriptorProto"
+        );
+
+        let defs = &schema["definitions"];
+        let message = &defs["google.protobuf.DescriptorProto"];
+        assert_eq!(message["additionalProperties"], false);
+        assert_eq!(
+            message["properties"]["nestedType"],
+            serde_json::json!({
+                "type": "array",
+                "items": {"$ref": "#/definitions/google.protobuf.DescriptorProto"},
+            })
+        );
+
+        // Proto names are accepted alongside JSON names
+        assert_eq!(
+            message["properties"]["nested_type"],
+            message["properties"]["nestedType"]
+        );
+
+        let field = &defs["google.protobuf.FieldDescriptorProto"]["properties"];
+        assert_eq!(field["number"], serde_json::json!({"type": "integer"}));
+        let label = &field["label"]["anyOf"];
+        assert_eq!(label[0]["type"], "string");
+        assert!(label[0]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&"LABEL_REPEATED".into()));
+        // Enum numbers are accepted as well as names
+        assert_eq!(label[1], serde_json::json!({"type": "integer"}));
+
+        // proto2 required fields are listed, under either name
+        let name_part = &defs["google.protobuf.UninterpretedOption.NamePart"];
+        assert_eq!(
+            name_part["allOf"],
+            serde_json::json!([
+                {"anyOf": [{"required": ["namePart"]}, {"required": ["name_part"]}]},
+                {"anyOf": [{"required": ["isExtension"]}, {"required": ["is_extension"]}]},
+            ])
+        );
+        assert!(name_part.get("required").is_none());
+        let option = &defs["google.protobuf.UninterpretedOption"]["properties"];
+        assert_eq!(
+            option["positiveIntValue"],
+            serde_json::json!({"type": ["integer", "string"]})
+        );
+        assert_eq!(option["stringValue"]["contentEncoding"], "base64");
+    }
+
+    #[test]
+    fn json_schema_for_well_known_types() {
+        let desc = DescriptorPool::global()
+            .get_message_by_name("google.protobuf.Option")
+            .unwrap();
+        let schema = make_json_schema(&desc);
+        let value = &schema["definitions"]["google.protobuf.Option"]["properties"]["value"];
+        assert_eq!(value["required"], serde_json::json!(["@type"]));
+
+        let desc = DescriptorPool::global()
+            .get_message_by_name("google.protobuf.Timestamp")
+            .unwrap();
+        let schema = make_json_schema(&desc);
+        assert_eq!(schema["format"], "date-time");
+        assert!(schema.get("definitions").is_none());
+    }
+
+    #[test]
+    fn strip_unknown_text_fields_in_nested_messages() {
+        let desc = DescriptorPool::global()
+            .get_message_by_name("google.protobuf.DescriptorProto")
+            .unwrap();
+        let text = r#"name: "M" bogus: -1
+            field { name: "f" junk < x: 1 > number: 1 }
+            field: [{ name: "g" }, { nope: 'x' "y" }]
+            # comment: 1
+            options { deprecated: true }"#;
+
+        let stripped = strip_unknown_text_fields(&desc, text);
+        let msg = DynamicMessage::parse_text_format(desc.clone(), &stripped).unwrap();
+        let expected = DynamicMessage::parse_text_format(
+            desc.clone(),
+            r#"name: "M" field { name: "f" number: 1 } field { name: "g" } field {}
+            options { deprecated: true }"#,
+        )
+        .unwrap();
+        assert_eq!(msg, expected);
+
+        // Input the scanner cannot follow is passed through for the parser
+        assert!(matches!(
+            strip_unknown_text_fields(&desc, "bogus: {"),
+            Cow::Borrowed("bogus: {")
+        ));
+    }
+
+    #[test]
+    fn parse_text_format_multiple_with_separator() {
+        let pool = make_pool();
+        let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
+        let mut parser = TextRequestParser::new(
+            Some("name: \"first\"\x1ename: \"second\""),
+            &FormatOptions::default(),
+        )
+        .unwrap();
 
         let msg1 = parser.next(&desc).unwrap();
         let name1 = msg1.get_field(&desc.get_field_by_name("name").unwrap());
@@ -889,7 +3515,7 @@ mod tests {
     fn parse_text_format_empty_input() {
         let pool = make_pool();
         let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
-        let mut parser = TextRequestParser::new(None).unwrap();
+        let mut parser = TextRequestParser::new(None, &FormatOptions::default()).unwrap();
 
         // First call with empty input returns an empty message (matching Go behavior)
         let msg = parser.next(&desc).unwrap();
@@ -903,11 +3529,25 @@ mod tests {
         assert_eq!(parser.num_requests(), 1);
     }
 
+    #[test]
+    fn empty_text_input_is_detected() {
+        let opts = FormatOptions::default();
+        let parser = RequestParser::Text(TextRequestParser::new(Some("  \n"), &opts).unwrap());
+        assert!(parser.is_empty_text());
+
+        let parser =
+            RequestParser::Text(TextRequestParser::new(Some("name: \"a\""), &opts).unwrap());
+        assert!(!parser.is_empty_text());
+
+        let parser = RequestParser::Json(JsonRequestParser::new(None, &opts).unwrap());
+        assert!(!parser.is_empty_text());
+    }
+
     #[test]
     fn format_text_output() {
         let pool = make_pool();
         let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
-        let formatter = text_formatter(false);
+        let formatter = text_formatter(false, false);
 
         let mut msg = DynamicMessage::new(desc.clone());
         let name_field = desc.get_field_by_name("name").unwrap();
@@ -922,7 +3562,7 @@ mod tests {
     fn format_text_with_separator() {
         let pool = make_pool();
         let desc = pool.get_message_by_name("test.v1.HelloRequest").unwrap();
-        let formatter = text_formatter(true);
+        let formatter = text_formatter(true, false);
 
         let mut msg1 = DynamicMessage::new(desc.clone());
         let name_field = desc.get_field_by_name("name").unwrap();
@@ -937,4 +3577,37 @@ mod tests {
         let out2 = (formatter)(&msg2).unwrap();
         assert!(out2.starts_with('\x1e')); // Separator for subsequent messages
     }
+
+    #[test]
+    fn legacy_brackets_rewrites_submessages() {
+        let text = "payload {\n  body: \"{x}\"\n}\nempty {}\nitems: [{\n  id: 1\n}]";
+        assert_eq!(
+            to_legacy_brackets(text),
+            "payload: <\n  body: \"{x}\"\n>\nempty: <>\nitems: [<\n  id: 1\n>]"
+        );
+        assert_eq!(
+            to_legacy_brackets("[type.googleapis.com/a.B] {\n  s: \"\\\"}\"\n}"),
+            "[type.googleapis.com/a.B]: <\n  s: \"\\\"}\"\n>"
+        );
+    }
+
+    #[test]
+    fn format_text_legacy_brackets() {
+        let (pool, _) = make_any_pools();
+        let desc = pool.get_message_by_name("test.v1.Wrapper").unwrap();
+        let any_desc = pool.get_message_by_name("google.protobuf.Any").unwrap();
+        let mut any = DynamicMessage::new(any_desc);
+        any.set_field_by_name(
+            "type_url",
+            prost_reflect::Value::String("type.googleapis.com/test.v1.Unknown".into()),
+        );
+        let mut msg = DynamicMessage::new(desc);
+        msg.set_field_by_name("detail", prost_reflect::Value::Message(any));
+
+        let modern = (text_formatter(false, false))(&msg).unwrap();
+        let legacy = (text_formatter(false, true))(&msg).unwrap();
+        assert!(modern.starts_with("detail {"), "{modern}");
+        assert!(legacy.starts_with("detail: <"), "{legacy}");
+        assert!(legacy.ends_with('>'), "{legacy}");
+    }
 }
@@ -120,6 +120,18 @@ pub fn expand_headers(headers: &[String]) -> Result<Vec<String>> {
     Ok(result)
 }
 
+/// Expand `${VAR}` references in request data with environment variable values.
+///
+/// `$${VAR}` is an escape that produces a literal `${VAR}`. Fails if any
+/// referenced environment variable is undefined.
+pub fn expand_data(data: &str) -> Result<String> {
+    let segments = data
+        .split("$${")
+        .map(expand_env_vars)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(segments.join("${"))
+}
+
 /// Replace all `${VAR}` occurrences with their environment variable values.
 fn expand_env_vars(input: &str) -> Result<String> {
     let mut result = String::with_capacity(input.len());
@@ -146,6 +158,66 @@ fn expand_env_vars(input: &str) -> Result<String> {
     Ok(result)
 }
 
+/// The expectations in `expected` that `trailers` does not meet.
+///
+/// Each expectation is a 'name: value' string, met when some value of that
+/// header equals `value` (binary `-bin` values are compared base64-encoded),
+/// or a bare 'name', met when the header is present at all.
+pub fn unmet_trailer_expectations<'a>(
+    trailers: &MetadataMap,
+    expected: &'a [String],
+) -> Vec<&'a str> {
+    expected
+        .iter()
+        .filter(|expectation| {
+            let (name, value) = match expectation.split_once(':') {
+                Some((n, v)) => (n.trim().to_lowercase(), Some(v.trim())),
+                None => (expectation.trim().to_lowercase(), None),
+            };
+            let values: Vec<String> = if name.ends_with("-bin") {
+                trailers
+                    .get_all_bin(name.as_str())
+                    .iter()
+                    .map(|v| {
+                        let bytes = v.to_bytes().unwrap_or_default();
+                        base64::engine::general_purpose::STANDARD.encode(&bytes)
+                    })
+                    .collect()
+            } else {
+                trailers
+                    .get_all(name.as_str())
+                    .iter()
+                    .filter_map(|v| v.to_str().ok().map(str::to_string))
+                    .collect()
+            };
+            match value {
+                Some(value) => !values.iter().any(|v| v == value),
+                None => values.is_empty(),
+            }
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Generate a random (version 4) UUID, for tagging a call with a request ID.
+///
+/// The randomness is not cryptographically secure, which is fine for
+/// correlating a call with server logs.
+pub fn new_request_id() -> String {
+    let mut bits = fastrand::u128(..);
+    bits &= !(0xf000 << 64 | 0xc000 << 48);
+    bits |= 0x4000 << 64 | 0x8000 << 48;
+    let hex = format!("{bits:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
 /// Format a MetadataMap as a human-readable string.
 ///
 /// Equivalent to Go's `MetadataToString()` (grpcurl.go).
@@ -184,6 +256,43 @@ pub fn metadata_to_string(md: &MetadataMap) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn trailer_expectations() {
+        let trailers = metadata_from_headers(&[
+            "x-checksum: abc".to_string(),
+            "x-tag: one".to_string(),
+            "x-tag: two".to_string(),
+            "x-raw-bin: AQI=".to_string(),
+        ]);
+        let expected: Vec<String> = [
+            "X-Checksum: abc",
+            "x-tag: two",
+            "x-raw-bin: AQI=",
+            "x-tag",
+            "x-checksum: abd",
+            "x-missing",
+            "x-tag: three",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            unmet_trailer_expectations(&trailers, &expected),
+            ["x-checksum: abd", "x-missing", "x-tag: three"]
+        );
+    }
+
+    #[test]
+    fn request_id_is_v4_uuid() {
+        let id = new_request_id();
+        let groups: Vec<&str> = id.split('-').collect();
+        let lens: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+        assert_eq!(lens, [8, 4, 4, 4, 12], "{id}");
+        assert!(groups[2].starts_with('4'), "{id}");
+        assert!(matches!(&groups[3][..1], "8" | "9" | "a" | "b"), "{id}");
+        assert_ne!(id, new_request_id());
+    }
+
     #[test]
     fn parse_ascii_header() {
         let headers = vec!["Authorization: Bearer token123".to_string()];
@@ -257,6 +366,27 @@ mod tests {
         assert_eq!(expanded[0], "x-plain: just a value");
     }
 
+    #[test]
+    fn expand_env_vars_in_data() {
+        std::env::set_var("GRPCURL_TEST_DATA_NAME", "world");
+        let expanded = expand_data(r#"{"name": "${GRPCURL_TEST_DATA_NAME}"}"#).unwrap();
+        assert_eq!(expanded, r#"{"name": "world"}"#);
+        std::env::remove_var("GRPCURL_TEST_DATA_NAME");
+    }
+
+    #[test]
+    fn expand_data_escaped_reference_is_literal() {
+        std::env::set_var("GRPCURL_TEST_DATA_ESC", "value");
+        let expanded = expand_data("$${GRPCURL_TEST_DATA_ESC} ${GRPCURL_TEST_DATA_ESC}").unwrap();
+        assert_eq!(expanded, "${GRPCURL_TEST_DATA_ESC} value");
+        // An escaped reference need not be defined
+        assert_eq!(
+            expand_data("$${GRPCURL_NONEXISTENT_VAR_12345}").unwrap(),
+            "${GRPCURL_NONEXISTENT_VAR_12345}"
+        );
+        std::env::remove_var("GRPCURL_TEST_DATA_ESC");
+    }
+
     #[test]
     fn metadata_to_string_format() {
         let mut md = MetadataMap::new();
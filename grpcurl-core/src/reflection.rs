@@ -1,5 +1,8 @@
-use std::sync::atomic::{AtomicU8, Ordering};
-use std::sync::Mutex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use prost::Message;
@@ -19,6 +22,109 @@ const VERSION_UNKNOWN: u8 = 0;
 const VERSION_V1: u8 = 1;
 const VERSION_V1ALPHA: u8 = 2;
 
+/// Which server reflection API version to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReflectionVersion {
+    /// Try v1 first and fall back to v1alpha if the server lacks it.
+    #[default]
+    Auto,
+    /// Only use `grpc.reflection.v1`.
+    V1,
+    /// Only use `grpc.reflection.v1alpha`.
+    V1alpha,
+}
+
+impl FromStr for ReflectionVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ReflectionVersion::Auto),
+            "v1" => Ok(ReflectionVersion::V1),
+            "v1alpha" => Ok(ReflectionVersion::V1alpha),
+            other => Err(format!(
+                "The --reflect-version option must be 'v1', 'v1alpha' or 'auto', got '{other}'."
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReflectionVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReflectionVersion::Auto => write!(f, "auto"),
+            ReflectionVersion::V1 => write!(f, "v1"),
+            ReflectionVersion::V1alpha => write!(f, "v1alpha"),
+        }
+    }
+}
+
+/// Outcome of probing one reflection API version; see
+/// [`ServerSource::probe_versions`].
+#[derive(Debug)]
+pub struct ReflectionProbe {
+    /// The version probed: `V1` or `V1alpha`.
+    pub version: ReflectionVersion,
+    /// The services the server listed, or the error the probe got.
+    pub result: Result<Vec<String>>,
+}
+
+impl ReflectionVersion {
+    /// The fully-qualified reflection service name for this version, or
+    /// `None` for `Auto`.
+    pub fn service_name(self) -> Option<&'static str> {
+        match self {
+            ReflectionVersion::Auto => None,
+            ReflectionVersion::V1 => Some("grpc.reflection.v1.ServerReflection"),
+            ReflectionVersion::V1alpha => Some("grpc.reflection.v1alpha.ServerReflection"),
+        }
+    }
+}
+
+/// Describe a reflection request for `--trace-reflection`.
+fn trace_request(request: &v1::server_reflection_request::MessageRequest) -> String {
+    use v1::server_reflection_request::MessageRequest;
+    match request {
+        MessageRequest::FileByFilename(name) => format!("FileByFilename({name})"),
+        MessageRequest::FileContainingSymbol(symbol) => {
+            format!("FileContainingSymbol({symbol})")
+        }
+        MessageRequest::FileContainingExtension(ext) => format!(
+            "FileContainingExtension({}, {})",
+            ext.containing_type, ext.extension_number
+        ),
+        MessageRequest::AllExtensionNumbersOfType(name) => {
+            format!("AllExtensionNumbersOfType({name})")
+        }
+        MessageRequest::ListServices(_) => "ListServices".to_string(),
+    }
+}
+
+/// Describe a reflection response, or the error in its place, for
+/// `--trace-reflection`.
+fn trace_response(result: &Result<v1::server_reflection_response::MessageResponse>) -> String {
+    use v1::server_reflection_response::MessageResponse;
+    match result {
+        Ok(MessageResponse::FileDescriptorResponse(fdr)) => format!(
+            "FileDescriptorResponse({} files)",
+            fdr.file_descriptor_proto.len()
+        ),
+        Ok(MessageResponse::AllExtensionNumbersResponse(resp)) => format!(
+            "AllExtensionNumbersResponse({} numbers)",
+            resp.extension_number.len()
+        ),
+        Ok(MessageResponse::ListServicesResponse(list)) => {
+            format!("ListServicesResponse({} services)", list.service.len())
+        }
+        Ok(MessageResponse::ErrorResponse(err)) => format!(
+            "ErrorResponse({:?}: {})",
+            tonic::Code::from_i32(err.error_code),
+            err.error_message
+        ),
+        Err(e) => format!("error: {e}"),
+    }
+}
+
 /// Descriptor source backed by gRPC server reflection.
 ///
 /// Equivalent to Go's `serverSource` (desc_source.go:205-295).
@@ -32,17 +138,32 @@ const VERSION_V1ALPHA: u8 = 2;
 /// The descriptor pool is lazily populated as symbols are queried.
 /// Since prost-reflect descriptors use Arc internally and don't
 /// borrow from the pool, a Mutex provides safe interior mutability.
+/// The pool cannot be lent out through the lock, so
+/// `descriptor_pool_snapshot()` hands out a clone of it instead.
 // TODO: Add multi-threaded integration tests to exercise ServerSource from
 // concurrent tasks, validating that the auto-derived Send+Sync is sound.
 pub struct ServerSource {
     channel: Channel,
-    pool: Mutex<DescriptorPool>,
+    pool: Arc<Mutex<DescriptorPool>>,
     /// Metadata to attach to reflection requests (-H + --reflect-header).
     metadata: tonic::metadata::MetadataMap,
     /// Max decoding message size for reflection responses, matching --max-msg-sz.
     max_msg_sz: Option<usize>,
     /// Cached reflection API version for avoiding repeated v1/v1alpha negotiation.
     version: AtomicU8,
+    /// Version forced with `--reflect-version`; `Auto` negotiates.
+    forced_version: ReflectionVersion,
+    /// Deadline for each reflection request, including any negotiation.
+    timeout: Option<Duration>,
+    /// Log every reflection request and its response to stderr.
+    trace: bool,
+    /// Resolve well-known type dependencies from bundled descriptors instead
+    /// of fetching them from the server.
+    bundled_well_known: bool,
+    /// Show a "fetched N descriptor files" line on stderr while fetching.
+    progress: bool,
+    /// Whether the progress line is on screen and needs clearing.
+    progress_shown: AtomicBool,
 }
 
 impl ServerSource {
@@ -50,10 +171,16 @@ impl ServerSource {
     pub fn new(channel: Channel) -> Self {
         ServerSource {
             channel,
-            pool: Mutex::new(DescriptorPool::new()),
+            pool: Arc::new(Mutex::new(DescriptorPool::new())),
             metadata: tonic::metadata::MetadataMap::new(),
             max_msg_sz: None,
             version: AtomicU8::new(VERSION_UNKNOWN),
+            forced_version: ReflectionVersion::Auto,
+            timeout: None,
+            trace: false,
+            bundled_well_known: false,
+            progress: false,
+            progress_shown: AtomicBool::new(false),
         }
     }
 
@@ -61,13 +188,26 @@ impl ServerSource {
     pub fn with_metadata(channel: Channel, metadata: tonic::metadata::MetadataMap) -> Self {
         ServerSource {
             channel,
-            pool: Mutex::new(DescriptorPool::new()),
+            pool: Arc::new(Mutex::new(DescriptorPool::new())),
             metadata,
             max_msg_sz: None,
             version: AtomicU8::new(VERSION_UNKNOWN),
+            forced_version: ReflectionVersion::Auto,
+            timeout: None,
+            trace: false,
+            bundled_well_known: false,
+            progress: false,
+            progress_shown: AtomicBool::new(false),
         }
     }
 
+    /// Start from the files in `pool`, as if already fetched from the server.
+    #[cfg(test)]
+    pub(crate) fn with_pool(mut self, pool: DescriptorPool) -> Self {
+        self.pool = Arc::new(Mutex::new(pool));
+        self
+    }
+
     /// Set the maximum decoding message size for reflection responses.
     /// Matches Go's behavior where --max-msg-sz applies to all gRPC calls
     /// including reflection queries.
@@ -76,12 +216,149 @@ impl ServerSource {
         self
     }
 
+    /// Use only the given reflection API version instead of negotiating.
+    /// Works around servers that advertise v1 but answer it incorrectly.
+    pub fn with_reflection_version(mut self, version: ReflectionVersion) -> Self {
+        self.forced_version = version;
+        self
+    }
+
+    /// Fail reflection requests that take longer than `timeout`, instead of
+    /// waiting forever on a server that never answers.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Log each reflection request and the response it got to stderr, e.g.
+    /// `reflect: FileContainingSymbol(foo.Bar) -> FileDescriptorResponse(3 files)`.
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Resolve dependencies on protobuf's well-known types, such as
+    /// `google/protobuf/timestamp.proto`, from the descriptors bundled with
+    /// grpcurl instead of requesting them from the server. Many servers do
+    /// not serve those files over reflection.
+    pub fn with_bundled_well_known(mut self, bundled: bool) -> Self {
+        self.bundled_well_known = bundled;
+        self
+    }
+
+    /// Keep a "fetched N descriptor files" line updated on stderr as files
+    /// are fetched, cleared once the lookup completes, so a slow `describe`
+    /// of a service with many dependencies visibly makes progress. The line
+    /// is redrawn with a carriage return, so only enable it for a terminal.
+    pub fn with_progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Redraw the progress line with the pool's current file count.
+    fn show_progress(&self, pool: &DescriptorPool) {
+        if self.progress {
+            eprint!("\rfetched {} descriptor files", pool.files().len());
+            self.progress_shown.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Clear the progress line, if one is showing.
+    fn clear_progress(&self) {
+        if self.progress_shown.swap(false, Ordering::Relaxed) {
+            eprint!("\r\x1b[K");
+        }
+    }
+
+    /// Probe each reflection API version with a ListServices request.
+    ///
+    /// Unlike normal requests, nothing is negotiated or cached: v1 and
+    /// v1alpha are each asked directly, and every outcome is reported, so
+    /// users can see which versions a server actually answers. The timeout
+    /// and tracing settings still apply to each probe.
+    pub async fn probe_versions(&self) -> Vec<ReflectionProbe> {
+        let mut probes = Vec::new();
+        for version in [ReflectionVersion::V1, ReflectionVersion::V1alpha] {
+            let msg = v1::server_reflection_request::MessageRequest::ListServices(String::new());
+            let result = match self.reflect_as(msg, version).await {
+                Ok(v1::server_reflection_response::MessageResponse::ListServicesResponse(list)) => {
+                    Ok(list.service.into_iter().map(|s| s.name).collect())
+                }
+                Ok(_) => Err(GrpcurlError::Other(
+                    "unexpected reflection response for list_services".into(),
+                )),
+                Err(e) => Err(e),
+            };
+            probes.push(ReflectionProbe { version, result });
+        }
+        probes
+    }
+
+    /// Send a reflection request and get the response, giving up once the
+    /// configured timeout expires.
+    async fn reflect(
+        &self,
+        message_request: v1::server_reflection_request::MessageRequest,
+    ) -> Result<v1::server_reflection_response::MessageResponse> {
+        self.reflect_as(message_request, self.forced_version).await
+    }
+
+    /// Send a reflection request using `version`, negotiating if it is `Auto`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, message_request))
+    )]
+    async fn reflect_as(
+        &self,
+        message_request: v1::server_reflection_request::MessageRequest,
+        version: ReflectionVersion,
+    ) -> Result<v1::server_reflection_response::MessageResponse> {
+        let request_text =
+            (self.trace || cfg!(feature = "tracing")).then(|| trace_request(&message_request));
+        let result = match self.timeout {
+            None => self.reflect_negotiated(message_request, version).await,
+            Some(timeout) => {
+                tokio::time::timeout(timeout, self.reflect_negotiated(message_request, version))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(GrpcurlError::Other(
+                            format!(
+                                "reflection request timed out after {}s",
+                                timeout.as_secs_f64()
+                            )
+                            .into(),
+                        ))
+                    })
+            }
+        };
+        if let Some(request_text) = request_text {
+            let response_text = trace_response(&result);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                request = %request_text,
+                response = %response_text,
+                "reflection round-trip"
+            );
+            if self.trace {
+                eprintln!("reflect: {request_text} -> {response_text}");
+            }
+        }
+        result
+    }
+
     /// Send a reflection request and get the response, with v1/v1alpha auto-negotiation.
     /// Caches the discovered version to avoid repeated negotiation overhead.
-    async fn reflect(
+    async fn reflect_negotiated(
         &self,
         message_request: v1::server_reflection_request::MessageRequest,
+        version: ReflectionVersion,
     ) -> Result<v1::server_reflection_response::MessageResponse> {
+        match version {
+            ReflectionVersion::V1 => return self.reflect_v1(message_request).await,
+            ReflectionVersion::V1alpha => return self.reflect_v1alpha(message_request).await,
+            ReflectionVersion::Auto => {}
+        }
+
         let cached = self.version.load(Ordering::Relaxed);
         match cached {
             VERSION_V1 => return self.reflect_v1(message_request).await,
@@ -201,7 +478,7 @@ impl ServerSource {
                     continue;
                 }
 
-                files.push(fdp);
+                files.push((fdp, bytes.clone()));
             }
             files
         };
@@ -217,9 +494,11 @@ impl ServerSource {
                 .lock()
                 .map_err(|_| GrpcurlError::Other("internal lock poisoned".into()))?;
             let mut missing_files = Vec::new();
-            let new_names: std::collections::HashSet<_> =
-                new_files.iter().filter_map(|f| f.name.as_deref()).collect();
-            for fdp in &new_files {
+            let new_names: std::collections::HashSet<_> = new_files
+                .iter()
+                .filter_map(|(f, _)| f.name.as_deref())
+                .collect();
+            for (fdp, _) in &new_files {
                 for dep in &fdp.dependency {
                     if pool.get_file_by_name(dep).is_none() && !new_names.contains(dep.as_str()) {
                         missing_files.push(dep.clone());
@@ -231,6 +510,14 @@ impl ServerSource {
 
         // Fetch missing dependencies from the server (e.g., well-known types).
         for dep_name in missing {
+            let bundled = self
+                .bundled_well_known
+                .then(|| descriptor::well_known_file_descriptor(&dep_name))
+                .flatten();
+            if let Some(bytes) = bundled {
+                Box::pin(self.add_file_descriptors(&[bytes])).await?;
+                continue;
+            }
             let msg = v1::server_reflection_request::MessageRequest::FileByFilename(dep_name);
             if let Ok(v1::server_reflection_response::MessageResponse::FileDescriptorResponse(
                 fdr,
@@ -249,29 +536,31 @@ impl ServerSource {
         // Re-filter in case recursive calls already added some.
         let final_files: Vec<_> = new_files
             .into_iter()
-            .filter(|fdp| {
+            .filter(|(fdp, _)| {
                 let name = fdp.name.as_deref().unwrap_or("");
                 pool.get_file_by_name(name).is_none()
             })
             .collect();
         if !final_files.is_empty() {
-            let fds = prost_types::FileDescriptorSet {
-                file: final_files.clone(),
+            // Files are added from their original bytes so custom options survive.
+            let fds = descriptor::EncodedFileDescriptorSet {
+                file: final_files.iter().map(|(_, bytes)| bytes.clone()).collect(),
             };
-            match pool.add_file_descriptor_set(fds) {
+            match pool.decode_file_descriptor_set(fds.encode_to_vec().as_slice()) {
                 Ok(()) => {}
                 Err(_) => {
                     // Gracefully handle missing dependencies by adding files one at a time.
                     // Matches Go's AllowMissingFileDescriptors() behavior.
-                    for fdp in final_files {
-                        let name = fdp.name.clone().unwrap_or_else(|| "<unknown>".into());
-                        let single_fds = prost_types::FileDescriptorSet { file: vec![fdp] };
-                        if let Err(e) = pool.add_file_descriptor_set(single_fds) {
+                    for (fdp, bytes) in final_files {
+                        let name = fdp.name.unwrap_or_else(|| "<unknown>".into());
+                        if let Err(e) = pool.decode_file_descriptor_proto(bytes.as_slice()) {
+                            self.clear_progress();
                             eprintln!("warning: skipping file descriptor {name}: {e}");
                         }
                     }
                 }
             }
+            self.show_progress(&pool);
         }
         Ok(())
     }
@@ -304,14 +593,18 @@ impl ServerSource {
             }
         }
 
-        // Fetch from server
-        let msg =
-            v1::server_reflection_request::MessageRequest::FileContainingSymbol(name.to_string());
+        // Fetch from server; a name ending in .proto is looked up as a file
+        let msg = if name.ends_with(".proto") {
+            v1::server_reflection_request::MessageRequest::FileByFilename(name.to_string())
+        } else {
+            v1::server_reflection_request::MessageRequest::FileContainingSymbol(name.to_string())
+        };
         let resp = self.reflect(msg).await?;
 
         if let v1::server_reflection_response::MessageResponse::FileDescriptorResponse(fdr) = resp {
-            self.add_file_descriptors(&fdr.file_descriptor_proto)
-                .await?;
+            let added = self.add_file_descriptors(&fdr.file_descriptor_proto).await;
+            self.clear_progress();
+            added?;
         }
 
         let pool = self
@@ -350,6 +643,7 @@ impl ServerSource {
                     let _ = self.add_file_descriptors(&fdr.file_descriptor_proto).await;
                 }
             }
+            self.clear_progress();
         }
 
         // Now collect extensions from the pool for the given message type
@@ -365,6 +659,22 @@ impl ServerSource {
     }
 }
 
+/// Create a reflection-backed descriptor source over an existing channel.
+///
+/// `metadata` is attached to every reflection request and `max_msg_sz` bounds
+/// the size of reflection responses. Use [`ServerSource`] directly to also
+/// force a reflection version or set a timeout.
+///
+/// Counterpart of the file-based factories in `descriptor.rs`, and
+/// equivalent to Go's `DescriptorSourceFromServer()`.
+pub fn descriptor_source_from_channel(
+    channel: Channel,
+    metadata: tonic::metadata::MetadataMap,
+    max_msg_sz: Option<i32>,
+) -> Box<dyn DescriptorSource> {
+    Box::new(ServerSource::with_metadata(channel, metadata).with_max_msg_sz(max_msg_sz))
+}
+
 #[async_trait]
 impl DescriptorSource for ServerSource {
     async fn list_services(&self) -> Result<Vec<String>> {
@@ -382,11 +692,40 @@ impl DescriptorSource for ServerSource {
         self.all_extensions_async(type_name).await
     }
 
+    async fn get_all_files(&self) -> Result<Vec<prost_types::FileDescriptorProto>> {
+        // Reflection cannot enumerate files, so resolve every service to pull
+        // its file and transitive dependencies into the pool first.
+        for service in self.list_services_async().await? {
+            self.find_symbol_async(&service).await?;
+        }
+        let pool = self
+            .pool
+            .lock()
+            .map_err(|_| GrpcurlError::Other("internal lock poisoned".into()))?;
+        Ok(pool.file_descriptor_protos().cloned().collect())
+    }
+
     fn descriptor_pool(&self) -> Option<&DescriptorPool> {
         // Cannot return a reference through a Mutex.
-        // Callers that need the pool should use find_symbol() instead.
+        // Callers that need the pool should use descriptor_pool_snapshot().
         None
     }
+
+    fn descriptor_pool_snapshot(&self) -> Option<DescriptorPool> {
+        // Cloning a pool only bumps a reference count
+        self.pool.lock().ok().map(|pool| pool.clone())
+    }
+
+    fn reflection_version(&self) -> Option<ReflectionVersion> {
+        match self.forced_version {
+            ReflectionVersion::Auto => match self.version.load(Ordering::Relaxed) {
+                VERSION_V1 => Some(ReflectionVersion::V1),
+                VERSION_V1ALPHA => Some(ReflectionVersion::V1alpha),
+                _ => None,
+            },
+            forced => Some(forced),
+        }
+    }
 }
 
 // -- Helper functions ----------------------------------------------------------
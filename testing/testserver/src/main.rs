@@ -1,8 +1,10 @@
 mod service;
 
 use clap::Parser;
+use tonic::codec::CompressionEncoding;
 use tonic::transport::Server;
 use tonic_reflection::server::Builder as ReflectionBuilder;
+use tonic_web::GrpcWebLayer;
 
 pub mod pb {
     tonic::include_proto!("testing");
@@ -40,10 +42,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Listening on {}", local_addr);
 
     let test_service = pb::test_service_server::TestServiceServer::new(service::TestServiceImpl);
+    // Responses are gzip-compressed for clients that accept it, so compressed
+    // framing is exercised end to end.
     let complex_service =
-        pb::complex_service_server::ComplexServiceServer::new(service::ComplexServiceImpl);
+        pb::complex_service_server::ComplexServiceServer::new(service::ComplexServiceImpl)
+            .send_compressed(CompressionEncoding::Gzip);
 
-    let mut builder = Server::builder();
+    // Serve grpc-web alongside native gRPC so -grpc-web can be exercised
+    // without a separate proxy. Native gRPC requests pass through untouched.
+    let mut builder = Server::builder()
+        .accept_http1(true)
+        .layer(GrpcWebLayer::new());
 
     if !cli.noreflect {
         let reflection_service = ReflectionBuilder::configure()
@@ -14,6 +14,9 @@ const METADATA_REPLY_HEADERS: &str = "reply-with-headers";
 /// Metadata key: values echoed back as response trailers.
 const METADATA_REPLY_TRAILERS: &str = "reply-with-trailers";
 
+/// Response header echoing the `grpc-timeout` the request arrived with.
+const METADATA_RECEIVED_TIMEOUT: &str = "received-grpc-timeout";
+
 /// Metadata key: if present and non-zero, return this gRPC status code immediately.
 const METADATA_FAIL_EARLY: &str = "fail-early";
 
@@ -76,12 +79,15 @@ fn code_from_i32(n: i32) -> tonic::Code {
 fn extract_metadata<T>(req: &Request<T>) -> MetadataDirectives {
     let md = req.metadata();
 
-    let reply_headers: Vec<(String, String)> = md
+    let mut reply_headers: Vec<(String, String)> = md
         .get_all(METADATA_REPLY_HEADERS)
         .iter()
         .filter_map(|v| v.to_str().ok())
         .map(parse_header_value)
         .collect();
+    if let Some(timeout) = md.get("grpc-timeout").and_then(|v| v.to_str().ok()) {
+        reply_headers.push((METADATA_RECEIVED_TIMEOUT.into(), timeout.into()));
+    }
 
     let reply_trailers: Vec<(String, String)> = md
         .get_all(METADATA_REPLY_TRAILERS)
@@ -111,12 +117,15 @@ fn extract_metadata<T>(req: &Request<T>) -> MetadataDirectives {
 fn extract_metadata_from_streaming<T>(req: &Request<Streaming<T>>) -> MetadataDirectives {
     let md = req.metadata();
 
-    let reply_headers: Vec<(String, String)> = md
+    let mut reply_headers: Vec<(String, String)> = md
         .get_all(METADATA_REPLY_HEADERS)
         .iter()
         .filter_map(|v| v.to_str().ok())
         .map(parse_header_value)
         .collect();
+    if let Some(timeout) = md.get("grpc-timeout").and_then(|v| v.to_str().ok()) {
+        reply_headers.push((METADATA_RECEIVED_TIMEOUT.into(), timeout.into()));
+    }
 
     let reply_trailers: Vec<(String, String)> = md
         .get_all(METADATA_REPLY_TRAILERS)
@@ -183,7 +192,9 @@ impl pb::test_service_server::TestService for TestServiceImpl {
             return Err(Status::new(code, "fail"));
         }
         if let Some(code) = directives.fail_late {
-            return Err(Status::new(code, "fail"));
+            // An error status is the only way to send trailers from a unary call
+            let trailers = apply_trailers(&directives);
+            return Err(Status::with_metadata(code, "fail", trailers));
         }
 
         let mut response = Response::new(pb::Empty {});